@@ -105,4 +105,32 @@ pub enum NodeKind {
     Ident,
     /// Represents content within code
     Content,
+    /// Represents a macro definition
+    Macro,
+    /// Represents macro parameters
+    Params,
+    /// Represents a single macro parameter
+    Param,
+    /// Represents a binding pattern
+    Pattern,
+    /// Represents a destructuring pattern
+    Destructure,
+    /// Represents a single item of a destructuring pattern
+    DestructureItem,
+    /// Represents a pattern bound to a map key
+    NamedPattern,
+    /// Represents a break expression
+    Break,
+    /// Represents a continue expression
+    Continue,
+    /// Represents an include expression
+    Include,
+    /// Represents an import expression
+    Import,
+    /// Represents a set rule
+    Set,
+    /// Represents a show rule
+    Show,
+    /// Represents a field access
+    Access,
 }
@@ -64,6 +64,20 @@ impl<T: UniformPhase> Phase for T {
     type Literal = T::Meta;
     type Ident = T::Meta;
     type Content = T::Meta;
+    type Macro = T::Meta;
+    type Params = T::Meta;
+    type Param = T::Meta;
+    type Pattern = T::Meta;
+    type Destructure = T::Meta;
+    type DestructureItem = T::Meta;
+    type NamedPattern = T::Meta;
+    type Break = T::Meta;
+    type Continue = T::Meta;
+    type Include = T::Meta;
+    type Import = T::Meta;
+    type Set = T::Meta;
+    type Show = T::Meta;
+    type Access = T::Meta;
 }
 
 /// Defines a processing phase for a document tree.
@@ -170,6 +184,34 @@ pub trait Phase: 'static + Debug + Copy {
     type Ident: Debug + Clone;
     /// Metadata for content nodes
     type Content: Debug + Clone;
+    /// Metadata for macro definition nodes
+    type Macro: Debug + Clone;
+    /// Metadata for macro parameter list nodes
+    type Params: Debug + Clone;
+    /// Metadata for single macro parameter nodes
+    type Param: Debug + Clone;
+    /// Metadata for pattern nodes
+    type Pattern: Debug + Clone;
+    /// Metadata for destructuring pattern nodes
+    type Destructure: Debug + Clone;
+    /// Metadata for destructuring item nodes
+    type DestructureItem: Debug + Clone;
+    /// Metadata for named pattern nodes
+    type NamedPattern: Debug + Clone;
+    /// Metadata for break expression nodes
+    type Break: Debug + Clone;
+    /// Metadata for continue expression nodes
+    type Continue: Debug + Clone;
+    /// Metadata for include expression nodes
+    type Include: Debug + Clone;
+    /// Metadata for import expression nodes
+    type Import: Debug + Clone;
+    /// Metadata for set rule nodes
+    type Set: Debug + Clone;
+    /// Metadata for show rule nodes
+    type Show: Debug + Clone;
+    /// Metadata for field access nodes
+    type Access: Debug + Clone;
 }
 
 /// Provides type-safe casting between node-specific metadata and the generic `Meta` enum.
@@ -282,7 +324,21 @@ impl_meta_cast!(
     Arg,
     Literal,
     Ident,
-    Content
+    Content,
+    Macro,
+    Params,
+    Param,
+    Pattern,
+    Destructure,
+    DestructureItem,
+    NamedPattern,
+    Break,
+    Continue,
+    Include,
+    Import,
+    Set,
+    Show,
+    Access
 );
 
 /// A generic container for node metadata in a specific processing phase.
@@ -389,6 +445,34 @@ pub enum Meta<P: Phase> {
     Ident(<Ident as MetaCast<P>>::Meta),
     /// Content node metadata
     Content(<Content as MetaCast<P>>::Meta),
+    /// Macro definition node metadata
+    Macro(<Macro as MetaCast<P>>::Meta),
+    /// Macro parameter list node metadata
+    Params(<Params as MetaCast<P>>::Meta),
+    /// Single macro parameter node metadata
+    Param(<Param as MetaCast<P>>::Meta),
+    /// Pattern node metadata
+    Pattern(<Pattern as MetaCast<P>>::Meta),
+    /// Destructuring pattern node metadata
+    Destructure(<Destructure as MetaCast<P>>::Meta),
+    /// Destructuring item node metadata
+    DestructureItem(<DestructureItem as MetaCast<P>>::Meta),
+    /// Named pattern node metadata
+    NamedPattern(<NamedPattern as MetaCast<P>>::Meta),
+    /// Break expression node metadata
+    Break(<Break as MetaCast<P>>::Meta),
+    /// Continue expression node metadata
+    Continue(<Continue as MetaCast<P>>::Meta),
+    /// Include expression node metadata
+    Include(<Include as MetaCast<P>>::Meta),
+    /// Import expression node metadata
+    Import(<Import as MetaCast<P>>::Meta),
+    /// Set rule node metadata
+    Set(<Set as MetaCast<P>>::Meta),
+    /// Show rule node metadata
+    Show(<Show as MetaCast<P>>::Meta),
+    /// Field access node metadata
+    Access(<Access as MetaCast<P>>::Meta),
 }
 
 impl<P, M> Meta<P>
@@ -444,6 +528,20 @@ where
             Literal = M,
             Ident = M,
             Content = M,
+            Macro = M,
+            Params = M,
+            Param = M,
+            Pattern = M,
+            Destructure = M,
+            DestructureItem = M,
+            NamedPattern = M,
+            Break = M,
+            Continue = M,
+            Include = M,
+            Import = M,
+            Set = M,
+            Show = M,
+            Access = M,
         >,
 {
     /// Returns a copy of the inner metadata value.
@@ -507,6 +605,20 @@ where
             Self::Literal(m) => *m,
             Self::Ident(m) => *m,
             Self::Content(m) => *m,
+            Self::Macro(m) => *m,
+            Self::Params(m) => *m,
+            Self::Param(m) => *m,
+            Self::Pattern(m) => *m,
+            Self::Destructure(m) => *m,
+            Self::DestructureItem(m) => *m,
+            Self::NamedPattern(m) => *m,
+            Self::Break(m) => *m,
+            Self::Continue(m) => *m,
+            Self::Include(m) => *m,
+            Self::Import(m) => *m,
+            Self::Set(m) => *m,
+            Self::Show(m) => *m,
+            Self::Access(m) => *m,
         }
     }
 
@@ -568,6 +680,20 @@ where
             Self::Literal(m) => m,
             Self::Ident(m) => m,
             Self::Content(m) => m,
+            Self::Macro(m) => m,
+            Self::Params(m) => m,
+            Self::Param(m) => m,
+            Self::Pattern(m) => m,
+            Self::Destructure(m) => m,
+            Self::DestructureItem(m) => m,
+            Self::NamedPattern(m) => m,
+            Self::Break(m) => m,
+            Self::Continue(m) => m,
+            Self::Include(m) => m,
+            Self::Import(m) => m,
+            Self::Set(m) => m,
+            Self::Show(m) => m,
+            Self::Access(m) => m,
         }
     }
 
@@ -629,6 +755,20 @@ where
             Self::Literal(m) => m,
             Self::Ident(m) => m,
             Self::Content(m) => m,
+            Self::Macro(m) => m,
+            Self::Params(m) => m,
+            Self::Param(m) => m,
+            Self::Pattern(m) => m,
+            Self::Destructure(m) => m,
+            Self::DestructureItem(m) => m,
+            Self::NamedPattern(m) => m,
+            Self::Break(m) => m,
+            Self::Continue(m) => m,
+            Self::Include(m) => m,
+            Self::Import(m) => m,
+            Self::Set(m) => m,
+            Self::Show(m) => m,
+            Self::Access(m) => m,
         }
     }
 }
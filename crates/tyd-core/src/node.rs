@@ -110,6 +110,34 @@ pub enum Node {
     Ident(Ident),
     /// Content within a code structure
     Content(Content),
+    /// A macro definition
+    Macro(Macro),
+    /// Parameters of a macro definition
+    Params(Params),
+    /// A single macro parameter
+    Param(Param),
+    /// A binding pattern
+    Pattern(Pattern),
+    /// A destructuring pattern
+    Destructure(Destructure),
+    /// A single item of a destructuring pattern
+    DestructureItem(DestructureItem),
+    /// A pattern bound to a map key
+    NamedPattern(NamedPattern),
+    /// A break expression
+    Break(Break),
+    /// A continue expression
+    Continue(Continue),
+    /// An include expression
+    Include(Include),
+    /// An import expression
+    Import(Import),
+    /// A set rule
+    Set(Set),
+    /// A show rule
+    Show(Show),
+    /// A field access
+    Access(Access),
 }
 
 impl_try_as!(
@@ -162,7 +190,21 @@ impl_try_as!(
     Arg(Arg),
     Literal(Literal),
     Ident(Ident),
-    Content(Content)
+    Content(Content),
+    Macro(Macro),
+    Params(Params),
+    Param(Param),
+    Pattern(Pattern),
+    Destructure(Destructure),
+    DestructureItem(DestructureItem),
+    NamedPattern(NamedPattern),
+    Break(Break),
+    Continue(Continue),
+    Include(Include),
+    Import(Import),
+    Set(Set),
+    Show(Show),
+    Access(Access)
 );
 
 impl Node {
@@ -224,6 +266,20 @@ impl Node {
             Self::Literal(_) => NodeKind::Literal,
             Self::Ident(_) => NodeKind::Ident,
             Self::Content(_) => NodeKind::Content,
+            Self::Macro(_) => NodeKind::Macro,
+            Self::Params(_) => NodeKind::Params,
+            Self::Param(_) => NodeKind::Param,
+            Self::Pattern(_) => NodeKind::Pattern,
+            Self::Destructure(_) => NodeKind::Destructure,
+            Self::DestructureItem(_) => NodeKind::DestructureItem,
+            Self::NamedPattern(_) => NodeKind::NamedPattern,
+            Self::Break(_) => NodeKind::Break,
+            Self::Continue(_) => NodeKind::Continue,
+            Self::Include(_) => NodeKind::Include,
+            Self::Import(_) => NodeKind::Import,
+            Self::Set(_) => NodeKind::Set,
+            Self::Show(_) => NodeKind::Show,
+            Self::Access(_) => NodeKind::Access,
         }
     }
 
@@ -277,7 +333,8 @@ impl Node {
     /// Determines if this node is a code element.
     ///
     /// Code elements include Code, Expr, Let, Bind, If, For, Call, Args, Arg,
-    /// Literal, Ident, and Content nodes.
+    /// Literal, Ident, Content, Macro, Params, Param, Pattern, Destructure,
+    /// DestructureItem, NamedPattern, Break, Continue, Include, Import, Set, Show, and Access nodes.
     pub fn is_code(&self) -> bool {
         matches!(
             self,
@@ -293,6 +350,20 @@ impl Node {
                 | Self::Literal(_)
                 | Self::Ident(_)
                 | Self::Content(_)
+                | Self::Macro(_)
+                | Self::Params(_)
+                | Self::Param(_)
+                | Self::Pattern(_)
+                | Self::Destructure(_)
+                | Self::DestructureItem(_)
+                | Self::NamedPattern(_)
+                | Self::Break(_)
+                | Self::Continue(_)
+                | Self::Include(_)
+                | Self::Import(_)
+                | Self::Set(_)
+                | Self::Show(_)
+                | Self::Access(_)
         )
     }
 }
@@ -97,16 +97,26 @@ pub struct TableRow(pub Vec<NodeId<Block>>);
 pub struct List(pub Vec<NodeId<ListItem>>);
 
 /// Represents a single item in an unordered list.
-#[derive(Debug, From, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct ListItem(pub Vec<NodeId<Block>>);
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ListItem {
+    /// The content of the item
+    pub content: Vec<NodeId<Block>>,
+    /// Optional label for cross-referencing
+    pub label: Option<NodeId<Label>>,
+}
 
 /// Represents an ordered (enumerated) list of items.
 #[derive(Debug, From, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Enum(pub Vec<NodeId<EnumItem>>);
 
 /// Represents a single item in an ordered list.
-#[derive(Debug, From, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct EnumItem(pub Vec<NodeId<Block>>);
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EnumItem {
+    /// The content of the item
+    pub content: Vec<NodeId<Block>>,
+    /// Optional label for cross-referencing
+    pub label: Option<NodeId<Label>>,
+}
 
 /// Represents a definition list with terms and descriptions.
 #[derive(Debug, From, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
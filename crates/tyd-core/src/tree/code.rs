@@ -25,6 +25,22 @@ pub enum Expr {
     Ident(NodeId<Ident>),
     /// Inline content within code.
     Content(NodeId<Content>),
+    /// A macro definition, e.g., `macro greet(name) = [ ... ]`.
+    Macro(NodeId<Macro>),
+    /// Exits the innermost loop, e.g., `break`.
+    Break(NodeId<Break>),
+    /// Skips to the next iteration of the innermost loop, e.g., `continue`.
+    Continue(NodeId<Continue>),
+    /// Includes the content of another file, e.g., `include("other.tyd")`.
+    Include(NodeId<Include>),
+    /// Imports the bindings of another file, e.g., `import("other.tyd")`.
+    Import(NodeId<Import>),
+    /// Sets default arguments of a function, e.g., `set image(width: "80%")`.
+    Set(NodeId<Set>),
+    /// Restyles elements with a function, e.g., `show heading: it => [ ... ]`.
+    Show(NodeId<Show>),
+    /// Accesses a field of a map, e.g., `sys.inputs` or `str.upper("text")`.
+    Access(NodeId<Access>),
 }
 
 /// Represents a let binding that assigns values to identifiers.
@@ -35,9 +51,9 @@ pub struct Let(pub Vec<NodeId<Bind>>);
 /// Represents a single binding in a let expression.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Bind {
-    /// The identifier being bound.
-    pub name: NodeId<Ident>,
-    /// The expression whose value is bound to the identifier.
+    /// The pattern the value is bound to.
+    pub name: NodeId<Pattern>,
+    /// The expression whose value is bound to the pattern.
     pub value: NodeId<Expr>,
 }
 
@@ -55,14 +71,73 @@ pub struct If {
 /// Represents a for-loop expression.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct For {
-    /// The loop variable identifier.
-    pub el: NodeId<Ident>,
+    /// The pattern each element is bound to.
+    pub el: NodeId<Pattern>,
     /// The collection being iterated over.
     pub inside: NodeId<Expr>,
     /// The loop body.
     pub content: NodeId<Content>,
 }
 
+/// Represents a `break` expression that exits the innermost loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Break;
+
+/// Represents a `continue` expression that skips to the next iteration of the innermost loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Continue;
+
+/// Represents the inclusion of another file's content at the current position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Include {
+    /// The expression evaluating to the path of the included file.
+    pub path: NodeId<Expr>,
+}
+
+/// Represents the import of another file's top-level bindings as a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Import {
+    /// The expression evaluating to the path of the imported file.
+    pub path: NodeId<Expr>,
+}
+
+/// Represents a set rule, which sets default arguments for later calls of a function
+/// within the current scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Set {
+    /// The identifier of the function.
+    pub ident: NodeId<Ident>,
+    /// The default arguments.
+    pub args: NodeId<Args>,
+}
+
+/// Represents a show rule, which transforms the selected elements following it
+/// within the current scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Show {
+    /// The selector, an identifier naming the kind of element or a string selecting text.
+    pub selector: NodeId<Expr>,
+    /// The fields the selected elements must have, e.g., `.where(lang: "mermaid")`.
+    pub filter: Option<NodeId<Args>>,
+    /// The parameter of an anonymous transform, e.g., `it` in `it => [ ... ]`.
+    pub param: Option<NodeId<Ident>>,
+    /// The transform, which is the body of an anonymous transform or evaluates to
+    /// a function or the replacement.
+    pub transform: NodeId<Expr>,
+}
+
+/// Represents an access to a field of a map, e.g., a function of a module,
+/// which is called if arguments are given.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Access {
+    /// The identifier of the accessed map.
+    pub ident: NodeId<Ident>,
+    /// The names of the accessed fields, each a field of the previous one.
+    pub fields: Vec<NodeId<Ident>>,
+    /// The arguments the accessed field is called with, if any.
+    pub args: Option<NodeId<Args>>,
+}
+
 /// Represents a function or method call.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Call {
@@ -90,6 +165,72 @@ pub struct Arg {
     pub value: NodeId<Expr>,
 }
 
+/// Represents a macro definition that binds a user-defined function to an identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Macro {
+    /// The name the macro is bound to.
+    pub name: NodeId<Ident>,
+    /// The parameters the macro accepts.
+    pub params: NodeId<Params>,
+    /// The expression evaluated when the macro is called.
+    pub body: NodeId<Expr>,
+}
+
+/// Represents the parameter list of a macro definition.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Params {
+    /// The list of individual parameters.
+    pub params: Vec<NodeId<Param>>,
+    /// Optional parameter receiving the content block passed to the macro.
+    pub content: Option<NodeId<Ident>>,
+}
+
+/// Represents a single parameter of a macro definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Param {
+    /// The name of the parameter.
+    pub name: NodeId<Ident>,
+    /// Optional default value used when the argument is omitted.
+    pub default: Option<NodeId<Expr>>,
+}
+
+/// Represents a pattern that values are bound to in let bindings and for loops.
+#[derive(Debug, From, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Pattern {
+    /// A wildcard that discards the value, e.g., `_`.
+    #[from(ignore)]
+    Wildcard,
+    /// An identifier that binds the whole value, e.g., `x`.
+    Ident(NodeId<Ident>),
+    /// A destructuring of a list or map, e.g., `(key, value)`.
+    Destructure(NodeId<Destructure>),
+}
+
+/// Represents a destructuring pattern, e.g., `(first, name: n, ..rest)`.
+#[derive(Debug, From, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[from(forward)]
+pub struct Destructure(pub Vec<NodeId<DestructureItem>>);
+
+/// Represents a single item of a destructuring pattern.
+#[derive(Debug, From, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DestructureItem {
+    /// A positional pattern, or a map key when destructuring a map, e.g., `a`.
+    Pattern(NodeId<Pattern>),
+    /// A pattern bound to the value of a map key, e.g., `name: n`.
+    Named(NodeId<NamedPattern>),
+    /// Collects the remaining values, e.g., `..rest` or `..`.
+    Rest(Option<NodeId<Ident>>),
+}
+
+/// Represents a pattern bound to the value of a map key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NamedPattern {
+    /// The key of the map entry.
+    pub name: NodeId<Ident>,
+    /// The pattern the entry's value is bound to.
+    pub pattern: NodeId<Pattern>,
+}
+
 /// Represents literal values in the code.
 #[derive(Debug, From, Clone, PartialEq, PartialOrd)]
 pub enum Literal {
@@ -1,8 +1,8 @@
 use derive_more::From;
 use ecow::EcoString;
 
-use super::{Error, Text, code::Code};
-use crate::id::NodeId;
+use super::{Error, Label, Tag, Text, code::Code};
+use crate::{id::NodeId, kind::NodeKind};
 
 /// Represents all inline elements in the document structure.
 ///
@@ -46,6 +46,34 @@ pub enum Inline {
     Code(NodeId<Code>),
 }
 
+impl Inline {
+    /// Returns the kind of node this inline element represents.
+    ///
+    /// This method provides a way to determine the type of inline element
+    /// without having to match on the enum variant.
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Self::Error(_) => NodeKind::Error,
+            Self::Quote(_) => NodeKind::Quote,
+            Self::Strikeout(_) => NodeKind::Strikeout,
+            Self::Emphasis(_) => NodeKind::Emphasis,
+            Self::Strong(_) => NodeKind::Strong,
+            Self::Subscript(_) => NodeKind::Subscript,
+            Self::Supscript(_) => NodeKind::Supscript,
+            Self::Link(_) => NodeKind::Link,
+            Self::Ref(_) => NodeKind::Ref,
+            Self::RawInline(_) => NodeKind::RawInline,
+            Self::MathInline(_) => NodeKind::MathInline,
+            Self::Comment(_) => NodeKind::Comment,
+            Self::Escape(_) => NodeKind::Escape,
+            Self::Word(_) => NodeKind::Word,
+            Self::Spacing(_) => NodeKind::Spacing,
+            Self::SoftBreak(_) => NodeKind::SoftBreak,
+            Self::Code(_) => NodeKind::Code,
+        }
+    }
+}
+
 /// Represents quoted text content.
 ///
 /// Contains a vector of inline elements that form the quoted content.
@@ -95,17 +123,26 @@ pub struct Link {
 
 /// Represents a reference to another element in the document.
 ///
-/// Contains a string identifier for the referenced element.
-#[derive(Debug, From, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[from(forward)]
-pub struct Ref(pub EcoString);
+/// Contains the label of the referenced element and optional display content.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ref {
+    /// The label of the element that is referenced
+    pub label: NodeId<Label>,
+    /// Optional content to display instead of the number of the referenced element
+    pub content: Option<Vec<NodeId<Inline>>>,
+}
 
 /// Represents raw inline content that should be included verbatim.
 ///
-/// Contains the raw string content to be included without processing.
-#[derive(Debug, From, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[from(forward)]
-pub struct RawInline(pub EcoString);
+/// Contains the raw string content to be included without processing,
+/// and the language of the code if given, e.g. `` `let x = 1`{.rust} ``.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawInline {
+    /// The text content of the raw inline element
+    pub text: NodeId<Text>,
+    /// Optional language tag for syntax highlighting
+    pub lang: Option<NodeId<Tag>>,
+}
 
 /// Represents mathematical notation in inline form.
 ///
@@ -229,15 +229,24 @@ pub trait Visitor {
         self.walk_list_item(list_item, doc)
     }
 
-    /// Walk through a list item and visit its children (blocks).
+    /// Walk through a list item and visit its children.
+    ///
+    /// Visits the content blocks and optional label.
     fn walk_list_item(
         &mut self,
         list_item: Full<tree::ListItem>,
         doc: &Doc,
     ) -> Result<(), Self::Error> {
-        for id in &list_item.0.0 {
+        let tree::ListItem { content, label } = list_item.0;
+
+        for id in content {
             self.visit_block(doc.full(*id), doc)?;
         }
+
+        if let Some(id) = *label {
+            self.visit_label(doc.full(id), doc)?;
+        }
+
         Ok(())
     }
 
@@ -267,15 +276,24 @@ pub trait Visitor {
         self.walk_enum_item(enum_item, doc)
     }
 
-    /// Walk through an enum item and visit its children (blocks).
+    /// Walk through an enum item and visit its children.
+    ///
+    /// Visits the content blocks and optional label.
     fn walk_enum_item(
         &mut self,
         enum_item: Full<tree::EnumItem>,
         doc: &Doc,
     ) -> Result<(), Self::Error> {
-        for id in &enum_item.0.0 {
+        let tree::EnumItem { content, label } = enum_item.0;
+
+        for id in content {
             self.visit_block(doc.full(*id), doc)?;
         }
+
+        if let Some(id) = *label {
+            self.visit_label(doc.full(id), doc)?;
+        }
+
         Ok(())
     }
 
@@ -544,19 +562,52 @@ pub trait Visitor {
 
     /// Visit a reference element in the document tree.
     ///
-    /// Default implementation does nothing and returns Ok.
-    fn visit_ref(&mut self, _reference: Full<tree::Ref>, _doc: &Doc) -> Result<(), Self::Error> {
+    /// Default implementation calls walk_ref to traverse its children.
+    fn visit_ref(&mut self, reference: Full<tree::Ref>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_ref(reference, doc)
+    }
+
+    /// Walk through a reference element and visit its label and optional content.
+    fn walk_ref(&mut self, reference: Full<tree::Ref>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Ref { label, content } = reference.0;
+
+        self.visit_label(doc.full(*label), doc)?;
+
+        if let Some(content) = content {
+            for id in content {
+                self.visit_inline(doc.full(*id), doc)?;
+            }
+        }
+
         Ok(())
     }
 
     /// Visit a raw inline element in the document tree.
     ///
-    /// Default implementation does nothing and returns Ok.
+    /// Default implementation calls walk_raw_inline to traverse its children.
     fn visit_raw_inline(
         &mut self,
-        _raw_inline: Full<tree::RawInline>,
-        _doc: &Doc,
+        raw_inline: Full<tree::RawInline>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.walk_raw_inline(raw_inline, doc)
+    }
+
+    /// Walk through a raw inline element and visit its children.
+    ///
+    /// Visits the text content and optional language tag.
+    fn walk_raw_inline(
+        &mut self,
+        raw_inline: Full<tree::RawInline>,
+        doc: &Doc,
     ) -> Result<(), Self::Error> {
+        let tree::RawInline { text, lang } = *raw_inline.0;
+
+        self.visit_text(doc.full(text), doc)?;
+
+        if let Some(id) = lang {
+            self.visit_tag(doc.full(id), doc)?;
+        }
         Ok(())
     }
 
@@ -648,6 +699,14 @@ pub trait Visitor {
             tree::Expr::Literal(id) => self.visit_literal(doc.full(id), doc),
             tree::Expr::Ident(id) => self.visit_ident(doc.full(id), doc),
             tree::Expr::Content(id) => self.visit_content(doc.full(id), doc),
+            tree::Expr::Macro(id) => self.visit_macro(doc.full(id), doc),
+            tree::Expr::Break(id) => self.visit_break(doc.full(id), doc),
+            tree::Expr::Continue(id) => self.visit_continue(doc.full(id), doc),
+            tree::Expr::Include(id) => self.visit_include(doc.full(id), doc),
+            tree::Expr::Import(id) => self.visit_import(doc.full(id), doc),
+            tree::Expr::Set(id) => self.visit_set(doc.full(id), doc),
+            tree::Expr::Show(id) => self.visit_show(doc.full(id), doc),
+            tree::Expr::Access(id) => self.visit_access(doc.full(id), doc),
         }
     }
 
@@ -673,11 +732,11 @@ pub trait Visitor {
         self.walk_bind(bind, doc)
     }
 
-    /// Walk through a bind element and visit its name identifier and value expression.
+    /// Walk through a bind element and visit its name pattern and value expression.
     fn walk_bind(&mut self, bind: Full<tree::Bind>, doc: &Doc) -> Result<(), Self::Error> {
         let tree::Bind { name, value } = bind.0;
 
-        self.visit_ident(doc.full(*name), doc)?;
+        self.visit_pattern(doc.full(*name), doc)?;
         self.visit_expr(doc.full(*value), doc)?;
         Ok(())
     }
@@ -719,13 +778,139 @@ pub trait Visitor {
             content,
         } = for_.0;
 
-        self.visit_ident(doc.full(*el), doc)?;
+        self.visit_pattern(doc.full(*el), doc)?;
         self.visit_expr(doc.full(*inside), doc)?;
         self.visit_content(doc.full(*content), doc)?;
 
         Ok(())
     }
 
+    /// Visit a break expression in the document tree.
+    ///
+    /// Default implementation does nothing and returns Ok.
+    fn visit_break(&mut self, _break: Full<tree::Break>, _doc: &Doc) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Visit a continue expression in the document tree.
+    ///
+    /// Default implementation does nothing and returns Ok.
+    fn visit_continue(
+        &mut self,
+        _continue: Full<tree::Continue>,
+        _doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Visit an include expression in the document tree.
+    ///
+    /// Default implementation calls walk_include to traverse its components.
+    fn visit_include(
+        &mut self,
+        include: Full<tree::Include>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.walk_include(include, doc)
+    }
+
+    /// Walk through an include expression and visit its path expression.
+    fn walk_include(&mut self, include: Full<tree::Include>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Include { path } = include.0;
+
+        self.visit_expr(doc.full(*path), doc)
+    }
+
+    /// Visit an import expression in the document tree.
+    ///
+    /// Default implementation calls walk_import to traverse its components.
+    fn visit_import(&mut self, import: Full<tree::Import>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_import(import, doc)
+    }
+
+    /// Walk through an import expression and visit its path expression.
+    fn walk_import(&mut self, import: Full<tree::Import>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Import { path } = import.0;
+
+        self.visit_expr(doc.full(*path), doc)
+    }
+
+    /// Visit a set rule in the document tree.
+    ///
+    /// Default implementation calls walk_set to traverse its components.
+    fn visit_set(&mut self, set: Full<tree::Set>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_set(set, doc)
+    }
+
+    /// Walk through a set rule and visit its identifier and arguments.
+    fn walk_set(&mut self, set: Full<tree::Set>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Set { ident, args } = set.0;
+
+        self.visit_ident(doc.full(*ident), doc)?;
+        self.visit_args(doc.full(*args), doc)?;
+
+        Ok(())
+    }
+
+    /// Visit a show rule in the document tree.
+    ///
+    /// Default implementation calls walk_show to traverse its components.
+    fn visit_show(&mut self, show: Full<tree::Show>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_show(show, doc)
+    }
+
+    /// Walk through a show rule and visit its selector, filter, parameter and transform.
+    fn walk_show(&mut self, show: Full<tree::Show>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Show {
+            selector,
+            filter,
+            param,
+            transform,
+        } = show.0;
+
+        self.visit_expr(doc.full(*selector), doc)?;
+
+        if let Some(filter) = filter {
+            self.visit_args(doc.full(*filter), doc)?;
+        }
+
+        if let Some(param) = param {
+            self.visit_ident(doc.full(*param), doc)?;
+        }
+
+        self.visit_expr(doc.full(*transform), doc)?;
+
+        Ok(())
+    }
+
+    /// Visit a field access in the document tree.
+    ///
+    /// Default implementation calls walk_access to traverse its components.
+    fn visit_access(&mut self, access: Full<tree::Access>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_access(access, doc)
+    }
+
+    /// Walk through a field access and visit its identifier, fields and arguments.
+    fn walk_access(&mut self, access: Full<tree::Access>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Access {
+            ident,
+            fields,
+            args,
+        } = access.0;
+
+        self.visit_ident(doc.full(*ident), doc)?;
+
+        for field in fields {
+            self.visit_ident(doc.full(*field), doc)?;
+        }
+
+        if let Some(args) = args {
+            self.visit_args(doc.full(*args), doc)?;
+        }
+
+        Ok(())
+    }
+
     /// Visit a function call expression in the document tree.
     ///
     /// Default implementation calls walk_call to traverse its components.
@@ -822,4 +1007,158 @@ pub trait Visitor {
 
         Ok(())
     }
+
+    /// Visit a macro definition in the document tree.
+    ///
+    /// Default implementation calls walk_macro to traverse its components.
+    fn visit_macro(&mut self, macro_: Full<tree::Macro>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_macro(macro_, doc)
+    }
+
+    /// Walk through a macro definition and visit its name, parameters and body.
+    fn walk_macro(&mut self, macro_: Full<tree::Macro>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Macro { name, params, body } = macro_.0;
+
+        self.visit_ident(doc.full(*name), doc)?;
+        self.visit_params(doc.full(*params), doc)?;
+        self.visit_expr(doc.full(*body), doc)?;
+
+        Ok(())
+    }
+
+    /// Visit a parameter list of a macro definition.
+    ///
+    /// Default implementation calls walk_params to traverse its components.
+    fn visit_params(&mut self, params: Full<tree::Params>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_params(params, doc)
+    }
+
+    /// Walk through a parameter list and visit each parameter and the optional content parameter.
+    fn walk_params(&mut self, params: Full<tree::Params>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Params { params, content } = params.0;
+
+        for id in params {
+            self.visit_param(doc.full(*id), doc)?;
+        }
+
+        if let Some(id) = *content {
+            self.visit_ident(doc.full(id), doc)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit a single parameter of a macro definition.
+    ///
+    /// Default implementation calls walk_param to traverse its components.
+    fn visit_param(&mut self, param: Full<tree::Param>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_param(param, doc)
+    }
+
+    /// Walk through a parameter and visit its name and optional default expression.
+    fn walk_param(&mut self, param: Full<tree::Param>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Param { name, default } = param.0;
+
+        self.visit_ident(doc.full(*name), doc)?;
+
+        if let Some(id) = *default {
+            self.visit_expr(doc.full(id), doc)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit a binding pattern in the document tree.
+    ///
+    /// Default implementation calls walk_pattern to traverse its components.
+    fn visit_pattern(
+        &mut self,
+        pattern: Full<tree::Pattern>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.walk_pattern(pattern, doc)
+    }
+
+    /// Walk through a binding pattern and visit its identifier or destructuring.
+    fn walk_pattern(&mut self, pattern: Full<tree::Pattern>, doc: &Doc) -> Result<(), Self::Error> {
+        match *pattern.0 {
+            tree::Pattern::Wildcard => Ok(()),
+            tree::Pattern::Ident(id) => self.visit_ident(doc.full(id), doc),
+            tree::Pattern::Destructure(id) => self.visit_destructure(doc.full(id), doc),
+        }
+    }
+
+    /// Visit a destructuring pattern in the document tree.
+    ///
+    /// Default implementation calls walk_destructure to traverse its items.
+    fn visit_destructure(
+        &mut self,
+        destructure: Full<tree::Destructure>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.walk_destructure(destructure, doc)
+    }
+
+    /// Walk through a destructuring pattern and visit each of its items.
+    fn walk_destructure(
+        &mut self,
+        destructure: Full<tree::Destructure>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        for id in &destructure.0.0 {
+            self.visit_destructure_item(doc.full(*id), doc)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit a single item of a destructuring pattern.
+    ///
+    /// Default implementation calls walk_destructure_item to traverse its components.
+    fn visit_destructure_item(
+        &mut self,
+        item: Full<tree::DestructureItem>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.walk_destructure_item(item, doc)
+    }
+
+    /// Walk through a destructuring item and visit its pattern, named pattern or rest identifier.
+    fn walk_destructure_item(
+        &mut self,
+        item: Full<tree::DestructureItem>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        match *item.0 {
+            tree::DestructureItem::Pattern(id) => self.visit_pattern(doc.full(id), doc),
+            tree::DestructureItem::Named(id) => self.visit_named_pattern(doc.full(id), doc),
+            tree::DestructureItem::Rest(Some(id)) => self.visit_ident(doc.full(id), doc),
+            tree::DestructureItem::Rest(None) => Ok(()),
+        }
+    }
+
+    /// Visit a pattern bound to a map key.
+    ///
+    /// Default implementation calls walk_named_pattern to traverse its components.
+    fn visit_named_pattern(
+        &mut self,
+        named: Full<tree::NamedPattern>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.walk_named_pattern(named, doc)
+    }
+
+    /// Walk through a named pattern and visit its key identifier and pattern.
+    fn walk_named_pattern(
+        &mut self,
+        named: Full<tree::NamedPattern>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let tree::NamedPattern { name, pattern } = named.0;
+
+        self.visit_ident(doc.full(*name), doc)?;
+        self.visit_pattern(doc.full(*pattern), doc)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,535 @@
+use ecow::EcoString;
+use std::collections::BTreeMap;
+
+use super::{Entry, Person};
+
+/// Parses the entries of a BibTeX file.
+///
+/// Supports `@string` abbreviations, values delimited by braces or quotes,
+/// numbers and concatenation with `#`. `@comment` and `@preamble` are skipped.
+///
+/// # Arguments
+/// * `text` - The content of the file
+///
+/// # Returns
+/// The entries, or an error message with the line of the error
+pub fn parse(text: &str) -> Result<Vec<Entry>, String> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+        strings: BTreeMap::new(),
+    };
+
+    parser.entries()
+}
+
+/// A recursive descent parser over the characters of a BibTeX file.
+struct Parser {
+    /// The characters of the file
+    chars: Vec<char>,
+    /// The position of the next character
+    pos: usize,
+    /// The abbreviations defined by `@string`
+    strings: BTreeMap<EcoString, EcoString>,
+}
+
+impl Parser {
+    /// Parses all entries, skipping any text between them.
+    fn entries(&mut self) -> Result<Vec<Entry>, String> {
+        let mut entries = Vec::new();
+
+        while let Some(at) = self.chars[self.pos..].iter().position(|c| *c == '@') {
+            self.pos += at + 1;
+
+            let kind = self.ident().to_lowercase();
+            self.skip_whitespace();
+
+            let close = match self.next() {
+                Some('{') => '}',
+                Some('(') => ')',
+                _ => return Err(self.error(format!("expected '{{' after '@{kind}'"))),
+            };
+
+            match kind.as_str() {
+                "comment" | "preamble" if close == '}' => {
+                    self.pos -= 1;
+                    self.delimited()?;
+                }
+                "comment" | "preamble" => {
+                    self.until(&[close]);
+                    self.expect(close)?;
+                }
+                "string" => {
+                    let (name, value) = self.field()?;
+                    self.strings.insert(name, value);
+                    self.expect(close)?;
+                }
+                _ => entries.push(self.entry(kind.into(), close)?),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Parses the key and fields of an entry after its opening delimiter.
+    fn entry(&mut self, kind: EcoString, close: char) -> Result<Entry, String> {
+        self.skip_whitespace();
+        let key = self.until(&[',', close]).trim().into();
+        let mut fields = BTreeMap::new();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.next() {
+                Some(',') => {}
+                Some(c) if c == close => break,
+                _ => return Err(self.error(format!("expected ',' or '{close}'"))),
+            }
+
+            self.skip_whitespace();
+
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                break;
+            }
+
+            let (name, value) = self.field()?;
+            fields.insert(name, value);
+        }
+
+        Ok(entry(key, kind, fields))
+    }
+
+    /// Parses a field like `title = {A Title}`.
+    fn field(&mut self) -> Result<(EcoString, EcoString), String> {
+        self.skip_whitespace();
+        let name = self.ident().to_lowercase().into();
+        self.skip_whitespace();
+        self.expect('=')?;
+
+        let mut value = EcoString::new();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some('{') => value.push_str(&self.delimited()?),
+                Some('"') => {
+                    self.pos += 1;
+                    value.push_str(&self.until(&['"']));
+                    self.expect('"')?;
+                }
+                Some(c) if c.is_alphanumeric() => {
+                    let word = self.ident();
+                    match self.strings.get(word.as_str()) {
+                        Some(string) => value.push_str(string),
+                        None => value.push_str(&word),
+                    }
+                }
+                _ => return Err(self.error("expected a value")),
+            }
+
+            self.skip_whitespace();
+
+            if self.peek() != Some('#') {
+                break;
+            }
+
+            self.pos += 1;
+        }
+
+        Ok((name, value))
+    }
+
+    /// Parses a value delimited by balanced braces, keeping the inner braces.
+    fn delimited(&mut self) -> Result<EcoString, String> {
+        self.expect('{')?;
+
+        let start = self.pos;
+        let mut depth = 0;
+
+        while let Some(c) = self.next() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    return Ok(self.chars[start..self.pos - 1].iter().copied().collect());
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Err(self.error("unclosed '{'"))
+    }
+
+    /// Parses an identifier, e.g. the type of an entry or the name of a field.
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || "_-:.".contains(c))
+        {
+            self.pos += 1;
+        }
+
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Takes all characters up to one of the given characters.
+    fn until(&mut self, end: &[char]) -> String {
+        let start = self.pos;
+
+        while self.peek().is_some_and(|c| !end.contains(&c)) {
+            self.pos += 1;
+        }
+
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Consumes the given character or returns an error.
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.next() {
+            Some(next) if next == c => Ok(()),
+            _ => Err(self.error(format!("expected '{c}'"))),
+        }
+    }
+
+    /// Skips whitespace characters.
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Returns the next character without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Consumes and returns the next character.
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    /// Creates an error message at the current line.
+    fn error(&self, message: impl std::fmt::Display) -> String {
+        let pos = self.pos.min(self.chars.len());
+        let line = self.chars[..pos].iter().filter(|c| **c == '\n').count() + 1;
+        format!("line {line}: {message}")
+    }
+}
+
+/// Creates an entry from the raw fields of a BibTeX entry.
+fn entry(key: EcoString, kind: EcoString, mut fields: BTreeMap<EcoString, EcoString>) -> Entry {
+    let mut take = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| fields.remove(*name))
+            .map(|value| clean(&value))
+    };
+
+    let authors = take(&["author", "editor"]);
+    let year = take(&["year"])
+        .or_else(|| take(&["date"]).map(|date| date.chars().take_while(|c| *c != '-').collect()));
+
+    Entry {
+        key,
+        kind,
+        authors: authors.as_deref().map(names).unwrap_or_default(),
+        title: take(&["title"]),
+        year,
+        container: take(&["journal", "journaltitle", "booktitle"]),
+        publisher: take(&["publisher", "institution", "school", "organization"]),
+        volume: take(&["volume"]),
+        issue: take(&["number", "issue"]),
+        pages: take(&["pages"]),
+        url: take(&["url"]),
+        doi: take(&["doi"]),
+    }
+}
+
+/// Splits a list of names separated by `and`.
+fn names(list: &str) -> Vec<Person> {
+    list.split(" and ")
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(Person::parse)
+        .collect()
+}
+
+/// The accents of TeX accent macros with the accented letters, as pairs of
+/// the letter and its accented form.
+const ACCENTS: &[(char, &str)] = &[
+    ('"', "aäeëiïoöuüyÿAÄEËIÏOÖUÜYŸ"),
+    ('\'', "aáeéiíoóuúyýcćnńsśzźlĺrŕgǵAÁEÉIÍOÓUÚYÝCĆNŃSŚZŹLĹRŔ"),
+    ('`', "aàeèiìoòuùAÀEÈIÌOÒUÙ"),
+    ('^', "aâeêiîoôuûcĉgĝhĥjĵsŝwŵyŷAÂEÊIÎOÔUÛCĈGĜHĤJĴSŜWŴYŶ"),
+    ('~', "aãnñoõiĩuũAÃNÑOÕIĨUŨ"),
+    ('=', "aāeēiīoōuūAĀEĒIĪOŌUŪ"),
+    ('.', "zżeėcċgġZŻEĖCĊGĠIİ"),
+    ('c', "cçsştţCÇSŞTŢ"),
+    ('v', "cčsšzžrřeěnňdďtťCČSŠZŽRŘEĚNŇDĎTŤ"),
+    ('u', "aăgğuŭAĂGĞUŬ"),
+    ('H', "oőuűOŐUŰ"),
+    ('k', "aąeęAĄEĘ"),
+    ('r', "aåuůAÅUŮ"),
+];
+
+/// The letters written as TeX macros without argument, e.g. `\ss`.
+const LETTERS: &[(&str, char)] = &[
+    ("ss", 'ß'),
+    ("o", 'ø'),
+    ("O", 'Ø'),
+    ("ae", 'æ'),
+    ("AE", 'Æ'),
+    ("oe", 'œ'),
+    ("OE", 'Œ'),
+    ("aa", 'å'),
+    ("AA", 'Å'),
+    ("l", 'ł'),
+    ("L", 'Ł'),
+    ("i", 'ı'),
+    ("j", 'ȷ'),
+];
+
+/// Removes braces and converts LaTeX escapes, accents and dashes of a value.
+///
+/// Escapes which are not known, e.g. `\emph` or an accent on a letter without
+/// an accented form, are kept as they are written.
+fn clean(value: &str) -> EcoString {
+    let chars = value.chars().collect::<Vec<_>>();
+    let mut out = EcoString::new();
+    let mut pos = 0;
+
+    while let Some(&c) = chars.get(pos) {
+        pos += 1;
+
+        let c = match c {
+            '{' | '}' => continue,
+            '\\' => {
+                let start = pos - 1;
+
+                match escape(&chars, &mut pos) {
+                    Some(c) => c,
+                    None => {
+                        out.extend(chars[start..pos].iter().copied());
+                        continue;
+                    }
+                }
+            }
+            '-' if chars.get(pos) == Some(&'-') => {
+                pos += 1;
+
+                if chars.get(pos) == Some(&'-') {
+                    pos += 1;
+                    '—'
+                } else {
+                    '–'
+                }
+            }
+            '~' => '\u{a0}',
+            c => c,
+        };
+
+        if !c.is_whitespace() || c == '\u{a0}' {
+            out.push(c);
+        } else if !out.ends_with(' ') {
+            out.push(' ');
+        }
+    }
+
+    out.trim().into()
+}
+
+/// Converts the escape after a backslash, leaving `pos` after it.
+///
+/// Returns `None` if the escape is not known, which is then kept from the
+/// backslash up to `pos`.
+fn escape(chars: &[char], pos: &mut usize) -> Option<char> {
+    let c = *chars.get(*pos)?;
+
+    if c.is_ascii_alphabetic() {
+        let start = *pos;
+
+        while chars.get(*pos).is_some_and(char::is_ascii_alphabetic) {
+            *pos += 1;
+        }
+
+        let name = chars[start..*pos].iter().collect::<String>();
+
+        if let [mark] = name.as_bytes()
+            && ACCENTS.iter().any(|(accent, _)| *accent == *mark as char)
+        {
+            return accent(*mark as char, chars, pos);
+        }
+
+        let (_, letter) = LETTERS.iter().find(|(macro_name, _)| *macro_name == name)?;
+
+        // Like TeX, spaces after the name of a macro only end the name
+        while chars.get(*pos).is_some_and(|c| *c == ' ') {
+            *pos += 1;
+        }
+
+        return Some(*letter);
+    }
+
+    *pos += 1;
+
+    match c {
+        '&' | '%' | '$' | '_' | '#' | '{' | '}' => Some(c),
+        '\\' | ' ' => Some(' '),
+        _ if ACCENTS.iter().any(|(accent, _)| *accent == c) => accent(c, chars, pos),
+        _ => None,
+    }
+}
+
+/// Converts the letter after an accent macro, e.g. `o` or `{o}` after `\"`.
+///
+/// The letter may also be a dotless `\i` or `\j`, e.g. in `\'{\i}`.
+fn accent(mark: char, chars: &[char], pos: &mut usize) -> Option<char> {
+    let start = *pos;
+
+    while chars.get(*pos).is_some_and(|c| *c == ' ') && mark.is_ascii_alphabetic() {
+        *pos += 1;
+    }
+
+    let braced = chars.get(*pos) == Some(&'{');
+    *pos += braced as usize;
+
+    let letter = match chars.get(*pos..) {
+        Some(['\\', letter @ ('i' | 'j'), rest @ ..])
+            if !rest.first().is_some_and(char::is_ascii_alphabetic) =>
+        {
+            *pos += 2;
+
+            while !braced && chars.get(*pos) == Some(&' ') {
+                *pos += 1;
+            }
+
+            Some(*letter)
+        }
+        Some([letter, ..]) if letter.is_alphabetic() => {
+            *pos += 1;
+            Some(*letter)
+        }
+        _ => None,
+    };
+
+    let Some(letter) = letter.filter(|_| !braced || chars.get(*pos) == Some(&'}')) else {
+        *pos = start;
+        return None;
+    };
+    *pos += braced as usize;
+
+    let (_, letters) = ACCENTS.iter().find(|(accent, _)| *accent == mark)?;
+    let letters = letters.chars().collect::<Vec<_>>();
+
+    letters
+        .chunks(2)
+        .find(|pair| pair[0] == letter)
+        .map(|pair| pair[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accents() {
+        assert_eq!(clean(r#"Schr{\"o}dinger"#), "Schrödinger");
+        assert_eq!(clean(r#"Schr\"{o}dinger"#), "Schrödinger");
+        assert_eq!(clean(r"Erd\H{o}s and {\'E}mile"), "Erdős and Émile");
+        assert_eq!(
+            clean(r"Gar\c{c}on, \v{S}koda, \v Cech"),
+            "Garçon, Škoda, Čech"
+        );
+        assert_eq!(clean(r"Mu\~noz, \`a la, \^ete"), "Muñoz, à la, ête");
+        assert_eq!(clean(r"Mart\'{\i}n, Mart\'\i n"), "Martín, Martín");
+    }
+
+    #[test]
+    fn letters() {
+        assert_eq!(clean(r"Stra{\ss}e"), "Straße");
+        assert_eq!(clean(r"{\O}sterg{\aa}rd, \l ukasz"), "Østergård, łukasz");
+    }
+
+    #[test]
+    fn unknown_escapes_are_kept() {
+        assert_eq!(clean(r#"{\"x}"#), r#"\"x"#);
+        assert_eq!(clean(r"\c{x}"), r"\c{x}");
+        assert_eq!(clean(r#"\"{xy}"#), r#"\"xy"#);
+        assert_eq!(clean(r"\emph{word}"), r"\emphword");
+        assert_eq!(clean(r#"trailing \"#), r#"trailing \"#);
+    }
+
+    #[test]
+    fn special_characters() {
+        assert_eq!(clean(r"R\&D at 50\%"), "R&D at 50%");
+        assert_eq!(clean("pages 1--10, a---b"), "pages 1–10, a—b");
+        assert_eq!(clean("A~B"), "A\u{a0}B");
+        assert_eq!(clean("  {A}   {Title}\n  broken  "), "A Title broken");
+    }
+
+    #[test]
+    fn entries() {
+        let entries = parse(
+            r#"
+@string{jos = "Journal of Studies"}
+@comment{ignored {nested} text}
+
+@article{doe2020,
+  author = {Doe, Jane and John {\"O}zil},
+  title = "A " # {Study},
+  journal = jos,
+  year = 2020,
+  pages = {1--10},
+}
+
+@book(knuth, title = {TeX}, date = {1984-01-01})
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+
+        let article = &entries[0];
+        assert_eq!(article.key, "doe2020");
+        assert_eq!(article.kind, "article");
+        assert_eq!(article.title.as_deref(), Some("A Study"));
+        assert_eq!(article.container.as_deref(), Some("Journal of Studies"));
+        assert_eq!(article.year.as_deref(), Some("2020"));
+        assert_eq!(article.pages.as_deref(), Some("1–10"));
+        assert_eq!(
+            article.authors,
+            [
+                Person {
+                    family: "Doe".into(),
+                    given: "Jane".into()
+                },
+                Person {
+                    family: "Özil".into(),
+                    given: "John".into()
+                },
+            ]
+        );
+
+        let book = &entries[1];
+        assert_eq!(book.key, "knuth");
+        assert_eq!(book.year.as_deref(), Some("1984"));
+    }
+
+    #[test]
+    fn errors() {
+        let error = parse("@article{key,\n  title = {unclosed\n").unwrap_err();
+        assert!(error.contains("unclosed '{'"), "{error}");
+
+        let error = parse("@article{key,\n  title {A}\n}").unwrap_err();
+        assert_eq!(error, "line 2: expected '='");
+
+        assert!(parse("@article key").is_err());
+        assert!(parse("no entries").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+use ecow::EcoString;
+use serde_yaml::Value as Yaml;
+
+use super::{Entry, Person};
+
+/// Parses the entries of a Hayagriva YAML file.
+///
+/// # Arguments
+/// * `text` - The content of the file
+///
+/// # Returns
+/// The entries, or an error message with the location of the error
+pub fn parse(text: &str) -> Result<Vec<Entry>, String> {
+    let root = serde_yaml::from_str::<Yaml>(text).map_err(|error| error.to_string())?;
+
+    match root {
+        Yaml::Null => Ok(Vec::new()),
+        Yaml::Mapping(entries) => entries
+            .iter()
+            .map(|(key, value)| match scalar(key) {
+                Some(key) => Ok(entry(key, value)),
+                None => Err(format!(
+                    "expected a string as key of an entry, found {key:?}"
+                )),
+            })
+            .collect(),
+        _ => Err("expected a map of entries".to_owned()),
+    }
+}
+
+/// Returns the text of a scalar without surrounding whitespace, e.g. of block scalars.
+///
+/// Formattable strings written as a map, e.g. `title: { value: ..., verbatim: true }`,
+/// return the text of their value.
+fn scalar(value: &Yaml) -> Option<EcoString> {
+    match value {
+        Yaml::String(s) => Some(s.trim().into()),
+        Yaml::Number(n) => Some(n.to_string().into()),
+        Yaml::Bool(b) => Some(b.to_string().into()),
+        Yaml::Mapping(_) => value.get("value").and_then(scalar),
+        Yaml::Tagged(tagged) => scalar(&tagged.value),
+        Yaml::Null | Yaml::Sequence(_) => None,
+    }
+}
+
+/// Returns the persons of a name, a list of names or a map like `{ name: ..., given-name: ... }`.
+fn persons(value: &Yaml) -> Vec<Person> {
+    match value {
+        Yaml::Sequence(items) => items.iter().flat_map(persons).collect(),
+        Yaml::Mapping(_) => value
+            .get("name")
+            .and_then(scalar)
+            .map(|family| Person {
+                family,
+                given: value.get("given-name").and_then(scalar).unwrap_or_default(),
+            })
+            .into_iter()
+            .collect(),
+        Yaml::Tagged(tagged) => persons(&tagged.value),
+        value => scalar(value)
+            .map(|name| Person::parse(&name))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Creates an entry from a Hayagriva entry.
+fn entry(key: EcoString, value: &Yaml) -> Entry {
+    let field = |name: &str| value.get(name).and_then(scalar);
+
+    // An entry may have multiple parents, of which the first is the container
+    let parent = match value.get("parent") {
+        Some(Yaml::Sequence(parents)) => parents.first(),
+        parent => parent,
+    };
+    let parent_field = |name: &str| parent.and_then(|parent| scalar(parent.get(name)?));
+
+    let authors = value
+        .get("author")
+        .or_else(|| value.get("editor"))
+        .map(persons)
+        .unwrap_or_default();
+
+    let year = field("date")
+        .or_else(|| parent_field("date"))
+        .map(|date| date.chars().take_while(|c| *c != '-').collect());
+
+    let doi = match value.get("serial-number") {
+        Some(serial @ Yaml::Mapping(_)) => serial.get("doi").and_then(scalar),
+        _ => None,
+    };
+
+    Entry {
+        key,
+        kind: field("type")
+            .unwrap_or_else(|| "misc".into())
+            .to_lowercase(),
+        authors,
+        title: field("title"),
+        year,
+        container: parent_field("title"),
+        publisher: field("publisher").or_else(|| parent_field("publisher")),
+        volume: field("volume").or_else(|| parent_field("volume")),
+        issue: field("issue").or_else(|| parent_field("issue")),
+        pages: field("page-range"),
+        url: field("url"),
+        doi: field("doi").or(doi),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single(text: &str) -> Entry {
+        let mut entries = parse(text).unwrap();
+        assert_eq!(entries.len(), 1);
+        entries.remove(0)
+    }
+
+    #[test]
+    fn article_with_parent() {
+        let entry = single(
+            r#"
+doe2020:
+  type: Article
+  title: "A Study: Part 1"  # the title
+  author: ["Doe, Jane", John Smith]
+  date: 2020-05-01
+  page-range: 1-10
+  serial-number:
+    doi: 10.1000/xyz
+  parent:
+    type: periodical
+    title: Journal of Studies
+    volume: 3
+"#,
+        );
+
+        assert_eq!(entry.key, "doe2020");
+        assert_eq!(entry.kind, "article");
+        assert_eq!(entry.title.as_deref(), Some("A Study: Part 1"));
+        assert_eq!(
+            entry.authors,
+            [
+                Person {
+                    family: "Doe".into(),
+                    given: "Jane".into()
+                },
+                Person {
+                    family: "Smith".into(),
+                    given: "John".into()
+                },
+            ]
+        );
+        assert_eq!(entry.year.as_deref(), Some("2020"));
+        assert_eq!(entry.pages.as_deref(), Some("1-10"));
+        assert_eq!(entry.doi.as_deref(), Some("10.1000/xyz"));
+        assert_eq!(entry.container.as_deref(), Some("Journal of Studies"));
+        assert_eq!(entry.volume.as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn block_scalars_and_numbers() {
+        let entry = single(
+            "
+book:
+  type: book
+  title: >
+    A Title Folded
+    Over Lines
+  date: 1999
+  editor:
+    - name: Knuth
+      given-name: Donald E.
+",
+        );
+
+        assert_eq!(entry.title.as_deref(), Some("A Title Folded Over Lines"));
+        assert_eq!(entry.year.as_deref(), Some("1999"));
+        assert_eq!(entry.authors[0].family, "Knuth");
+        assert_eq!(entry.authors[0].given, "Donald E.");
+    }
+
+    #[test]
+    fn formattable_strings_and_multiple_parents() {
+        let entry = single(
+            "
+chapter:
+  title:
+    value: The Chapter
+    verbatim: true
+  parent:
+    - title: The Book
+    - title: The Series
+",
+        );
+
+        assert_eq!(entry.kind, "misc");
+        assert_eq!(entry.title.as_deref(), Some("The Chapter"));
+        assert_eq!(entry.container.as_deref(), Some("The Book"));
+    }
+
+    #[test]
+    fn empty_file() {
+        assert!(parse("").unwrap().is_empty());
+        assert!(parse("# only a comment\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert!(parse("- a\n- b\n").is_err());
+        assert!(parse("key: [unclosed\n").is_err());
+        assert!(parse("entry:\n  title: a\n title: b\n").is_err());
+    }
+}
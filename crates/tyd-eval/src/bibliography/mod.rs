@@ -0,0 +1,554 @@
+//! Bibliographies and citations
+//!
+//! This module loads bibliographies from BibTeX and Hayagriva YAML files
+//! and formats citations and reference lists with a citation style.
+
+pub use style::*;
+
+mod bibtex;
+mod hayagriva;
+mod style;
+
+use ecow::{eco_format, EcoString};
+use std::{collections::BTreeMap, path::Path};
+use tyd_syntax::{error::SourceDiagnostic, Span};
+
+use crate::{
+    error::ReferenceError,
+    ir,
+    label::{self, Labels, REF_CLASS},
+    tracer::Tracer,
+};
+
+/// The class of the blocks created for bibliographies, which are filled after evaluation
+const BIBLIOGRAPHY_CLASS: &str = "bibliography";
+
+/// A person, e.g. an author of an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Person {
+    /// The family name
+    pub family: EcoString,
+    /// The given names, which may be empty
+    pub given: EcoString,
+}
+
+impl Person {
+    /// Parses a name written as `Family, Given` or `Given Family`.
+    pub fn parse(name: &str) -> Self {
+        let (family, given) = match name.split_once(',') {
+            Some((family, given)) => (family.trim(), given.trim()),
+            None => match name.trim().rsplit_once(' ') {
+                Some((given, family)) => (family, given.trim()),
+                None => (name.trim(), ""),
+            },
+        };
+
+        Self {
+            family: family.into(),
+            given: given.into(),
+        }
+    }
+
+    /// Returns the initials of the given names, e.g. `J. R.` for `John Ronald`.
+    pub fn initials(&self) -> EcoString {
+        self.given
+            .split_whitespace()
+            .filter_map(|name| name.chars().next())
+            .map(|c| eco_format!("{c}."))
+            .collect::<Vec<_>>()
+            .join(" ")
+            .into()
+    }
+}
+
+/// An entry of a bibliography, e.g. an article or a book.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// The key the entry is cited with
+    pub key: EcoString,
+    /// The type of the entry in lowercase, e.g. `article`
+    pub kind: EcoString,
+    /// The authors, or the editors if there are no authors
+    pub authors: Vec<Person>,
+    /// The title
+    pub title: Option<EcoString>,
+    /// The year of publication
+    pub year: Option<EcoString>,
+    /// The journal, proceedings or book the entry is published in
+    pub container: Option<EcoString>,
+    /// The publisher or institution
+    pub publisher: Option<EcoString>,
+    /// The volume of the container
+    pub volume: Option<EcoString>,
+    /// The issue of the container
+    pub issue: Option<EcoString>,
+    /// The page range within the container
+    pub pages: Option<EcoString>,
+    /// A URL to the entry
+    pub url: Option<EcoString>,
+    /// The DOI of the entry
+    pub doi: Option<EcoString>,
+}
+
+impl Entry {
+    /// Returns the family name of the first author, or the title if the entry has no
+    /// authors, which takes the place of the authors when citing and sorting the entry.
+    pub fn lead(&self) -> Option<&EcoString> {
+        self.authors
+            .first()
+            .map(|person| &person.family)
+            .or(self.title.as_ref())
+    }
+}
+
+/// Parses the entries of a bibliography file.
+///
+/// The format is chosen by the extension of the file: `.bib` for BibTeX
+/// and `.yml` or `.yaml` for Hayagriva.
+///
+/// # Arguments
+/// * `path` - The path of the file
+/// * `text` - The content of the file
+///
+/// # Returns
+/// The entries, or an error message
+pub fn parse(path: &Path, text: &str) -> Result<Vec<Entry>, String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("bib") => bibtex::parse(text),
+        Some("yml" | "yaml") => hayagriva::parse(text),
+        _ => Err("unsupported format, expected a '.bib' or '.yml' file".to_owned()),
+    }
+}
+
+/// How the reference list of a bibliography is displayed.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The title displayed above the reference list, empty for none
+    pub title: EcoString,
+    /// Whether all entries are listed, not only the cited ones
+    pub full: bool,
+}
+
+/// The bibliography of the document and the citations of its entries.
+///
+/// Citations may appear before the bibliography is added, so they are inserted as
+/// placeholders while the document is evaluated and formatted once all are known.
+#[derive(Debug, Clone, Default)]
+pub struct Bibliography {
+    /// The entries by key
+    entries: BTreeMap<EcoString, Entry>,
+    /// The style of citations and the reference list
+    style: Style,
+    /// The options of the reference list, if the bibliography was added
+    options: Option<Options>,
+    /// The cited keys together with the span of the citation
+    cites: Vec<(EcoString, Span)>,
+}
+
+impl Bibliography {
+    /// Returns whether the bibliography was added to the document.
+    pub fn is_added(&self) -> bool {
+        self.options.is_some()
+    }
+
+    /// Returns whether an entry with the given key exists.
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Adds the bibliography and creates a placeholder for its reference list,
+    /// which is filled by `Bibliography::resolve`.
+    ///
+    /// # Arguments
+    /// * `entries` - The entries of the bibliography
+    /// * `style` - The citation style
+    /// * `options` - How the reference list is displayed
+    pub fn add(&mut self, entries: Vec<Entry>, style: Style, options: Options) -> ir::Block {
+        self.entries
+            .extend(entries.into_iter().map(|entry| (entry.key.clone(), entry)));
+        self.style = style;
+        self.options = Some(options);
+
+        let attr = ir::AttrBuilder::new().class(BIBLIOGRAPHY_CLASS).build();
+        ir::Block::Div(attr, Vec::new())
+    }
+
+    /// Creates a citation of an entry, which is formatted by `Bibliography::resolve`.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the cited entry
+    /// * `locator` - The cited part of the entry, e.g. `p. 5`, which may be empty
+    /// * `span` - The span of the citation
+    pub fn cite(&mut self, key: EcoString, locator: ir::Content, span: Span) -> ir::Inline {
+        self.cites.push((key.clone(), span));
+        ir::Inline::Cite(vec![citation(key, locator)], Vec::new())
+    }
+
+    /// Reports an error for every citation of a key which is not in the bibliography,
+    /// suggesting the most similar key.
+    pub fn check(&self, tracer: &mut Tracer) {
+        for (key, span) in &self.cites {
+            if self.entries.contains_key(key) {
+                continue;
+            }
+
+            let error = ReferenceError::UnknownCitation(key.clone());
+            let mut diag = SourceDiagnostic::error(*span, error.to_string());
+
+            if !self.is_added() {
+                diag.set_help("Add a bibliography with `#bibliography(\"refs.bib\")`");
+            } else if let Some(similar) = label::similar(key, self.entries.keys()) {
+                diag.set_help(format!("Did you mean '{similar}'?"));
+            }
+
+            tracer.diagnose_source(diag);
+        }
+    }
+
+    /// Formats all citations and fills the reference list.
+    ///
+    /// References to labels which are not defined but are keys of the bibliography
+    /// are turned into citations, e.g. `@doe2020` or `@doe2020[p. 5]`.
+    ///
+    /// # Arguments
+    /// * `blocks` - The evaluated blocks of the document
+    /// * `labels` - The labels of the document, which take precedence over keys
+    pub fn resolve(&self, blocks: &mut Vec<ir::Block>, labels: &Labels) {
+        let mut citer = Citer {
+            bibliography: self,
+            labels,
+            order: Vec::new(),
+        };
+        ir::MutVisitor::visit_vec_block(&mut citer, blocks);
+
+        let mut lister = Lister {
+            bibliography: self,
+            order: citer.order,
+        };
+        ir::MutVisitor::visit_vec_block(&mut lister, blocks);
+    }
+}
+
+/// Creates a citation of an entry.
+fn citation(key: EcoString, locator: ir::Content) -> ir::Citation {
+    ir::Citation {
+        citationId: key.to_string(),
+        citationPrefix: Vec::new(),
+        citationSuffix: locator,
+        citationMode: ir::CitationMode::NormalCitation,
+        citationNoteNum: 0,
+        citationHash: 0,
+    }
+}
+
+/// Formats citations in document order, numbering entries by their first citation.
+struct Citer<'a> {
+    /// The bibliography containing the cited entries
+    bibliography: &'a Bibliography,
+    /// The labels, which take precedence over keys of the bibliography
+    labels: &'a Labels,
+    /// The cited keys in the order of their first citation
+    order: Vec<EcoString>,
+}
+
+impl Citer<'_> {
+    /// Returns the number of an entry, numbering it if it was not cited before.
+    fn number(&mut self, key: &EcoString) -> usize {
+        match self.order.iter().position(|cited| cited == key) {
+            Some(pos) => pos + 1,
+            None => {
+                self.order.push(key.clone());
+                self.order.len()
+            }
+        }
+    }
+
+    /// Formats citations with the style of the bibliography.
+    fn format(&mut self, citations: &[ir::Citation]) -> ir::Content {
+        let mut content = Vec::new();
+
+        for citation in citations {
+            let key = EcoString::from(citation.citationId.as_str());
+
+            // Unknown keys are reported by `Bibliography::check` and not numbered
+            if let Some(entry) = self.bibliography.entries.get(&key) {
+                let number = self.number(&key);
+                let style = self.bibliography.style;
+                content.extend(style.citation(entry, number, &citation.citationSuffix));
+            }
+        }
+
+        content
+    }
+}
+
+impl ir::MutVisitor for Citer<'_> {
+    fn visit_inline(&mut self, inline: &mut ir::Inline) {
+        if let ir::Inline::Link((_, classes, _), content, (href, _)) = inline {
+            let key = href
+                .strip_prefix('#')
+                .filter(|_| classes.iter().any(|class| class == REF_CLASS))
+                .filter(|key| self.labels.get(key).is_none() && self.bibliography.contains(key));
+
+            if let Some(key) = key {
+                let locator = std::mem::take(content);
+                *inline = ir::Inline::Cite(vec![citation(key.into(), locator)], Vec::new());
+            }
+        }
+
+        match inline {
+            ir::Inline::Cite(citations, content) if content.is_empty() => {
+                *content = self.format(citations);
+            }
+            _ => {}
+        }
+
+        self.walk_inline(inline)
+    }
+}
+
+/// Fills the placeholder of the reference list.
+struct Lister<'a> {
+    /// The bibliography to list
+    bibliography: &'a Bibliography,
+    /// The cited keys in the order of their first citation
+    order: Vec<EcoString>,
+}
+
+impl Lister<'_> {
+    /// Renders the reference list.
+    fn render(&self, options: &Options) -> Vec<ir::Block> {
+        let Bibliography { entries, style, .. } = self.bibliography;
+
+        let mut listed = self
+            .order
+            .iter()
+            .filter_map(|key| entries.get(key))
+            .collect::<Vec<_>>();
+
+        if options.full {
+            let uncited = entries
+                .values()
+                .filter(|entry| !self.order.contains(&entry.key));
+            listed.extend(uncited);
+        }
+
+        if !style.is_numbered() {
+            listed.sort_by_key(|entry| {
+                (
+                    entry.lead().cloned(),
+                    entry.year.clone(),
+                    entry.title.clone(),
+                )
+            });
+        }
+
+        let mut blocks = Vec::new();
+
+        if !options.title.is_empty() {
+            let attr = ir::AttrBuilder::new().class("unnumbered").build();
+            let title = vec![ir::Inline::Str(options.title.to_string())];
+            blocks.push(ir::Block::Header(1, attr, title));
+        }
+
+        for (i, entry) in listed.into_iter().enumerate() {
+            let attr = ir::AttrBuilder::new()
+                .ident(format!("ref-{}", entry.key))
+                .build();
+            let reference = style.reference(entry, i + 1);
+            blocks.push(ir::Block::Div(attr, vec![ir::Block::Para(reference)]));
+        }
+
+        blocks
+    }
+}
+
+impl ir::MutVisitor for Lister<'_> {
+    fn visit_block(&mut self, block: &mut ir::Block) {
+        if let ir::Block::Div((_, classes, _), content) = block {
+            let options = self
+                .bibliography
+                .options
+                .as_ref()
+                .filter(|_| classes.iter().any(|class| class == BIBLIOGRAPHY_CLASS));
+
+            if let Some(options) = options {
+                *content = self.render(options);
+                return;
+            }
+        }
+
+        self.walk_block(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tyd_syntax::{source::Source, Spans};
+
+    use super::*;
+
+    fn entry(key: &str, family: &str, year: &str) -> Entry {
+        Entry {
+            key: key.into(),
+            kind: "article".into(),
+            authors: vec![Person::parse(family)],
+            title: Some(format!("Title of {key}").into()),
+            year: Some(year.into()),
+            container: None,
+            publisher: None,
+            volume: None,
+            issue: None,
+            pages: None,
+            url: None,
+            doi: None,
+        }
+    }
+
+    fn span() -> Span {
+        Source::new("test.tyd", "test.tyd", "").end_of_input()
+    }
+
+    fn bibliography(style: Style, full: bool) -> (Bibliography, ir::Block) {
+        let mut bibliography = Bibliography::default();
+        let entries = vec![entry("b", "Zed", "2001"), entry("a", "Adams", "2010")];
+        let options = Options {
+            title: EcoString::new(),
+            full,
+        };
+        let placeholder = bibliography.add(entries, style, options);
+        (bibliography, placeholder)
+    }
+
+    /// Returns the plain text of the citations and the identifiers of the listed references.
+    fn resolve(
+        bibliography: &Bibliography,
+        citations: Vec<ir::Inline>,
+        placeholder: ir::Block,
+    ) -> (String, Vec<String>) {
+        let mut blocks = vec![ir::Block::Para(citations), placeholder];
+        bibliography.resolve(&mut blocks, &Labels::default());
+
+        let ir::Block::Para(inlines) = &blocks[0] else {
+            panic!("unexpected {:?}", blocks[0]);
+        };
+        let ir::Block::Div(_, references) = &blocks[1] else {
+            panic!("unexpected {:?}", blocks[1]);
+        };
+        let idents = references
+            .iter()
+            .filter_map(|block| match block {
+                ir::Block::Div((ident, ..), _) => Some(ident.clone()),
+                _ => None,
+            })
+            .collect();
+
+        (label::plain_text(inlines).to_string(), idents)
+    }
+
+    #[test]
+    fn person_names() {
+        let person = |family: &str, given: &str| Person {
+            family: family.into(),
+            given: given.into(),
+        };
+
+        assert_eq!(Person::parse("Doe, Jane"), person("Doe", "Jane"));
+        assert_eq!(
+            Person::parse(" John Ronald Tolkien "),
+            person("Tolkien", "John Ronald")
+        );
+        assert_eq!(Person::parse("Plato"), person("Plato", ""));
+        assert_eq!(person("Tolkien", "John  Ronald").initials(), "J. R.");
+        assert_eq!(person("Plato", "").initials(), "");
+    }
+
+    #[test]
+    fn formats_by_extension() {
+        assert!(parse(Path::new("refs.bib"), "").unwrap().is_empty());
+        assert!(parse(Path::new("refs.yaml"), "").unwrap().is_empty());
+        assert!(parse(Path::new("refs.json"), "").is_err());
+        assert!(parse(Path::new("refs"), "").is_err());
+    }
+
+    #[test]
+    fn numbered_in_citation_order() {
+        let (mut bibliography, placeholder) = bibliography(Style::Ieee, false);
+        let citations = vec![
+            bibliography.cite("missing".into(), Vec::new(), span()),
+            bibliography.cite("a".into(), Vec::new(), span()),
+            bibliography.cite("b".into(), Vec::new(), span()),
+            bibliography.cite("a".into(), Vec::new(), span()),
+        ];
+
+        let (text, idents) = resolve(&bibliography, citations, placeholder);
+
+        assert_eq!(text, "[1][2][1]");
+        assert_eq!(idents, ["ref-a", "ref-b"]);
+    }
+
+    #[test]
+    fn sorted_by_author() {
+        let (mut bibliography, placeholder) = bibliography(Style::Apa, true);
+        let citations = vec![bibliography.cite("b".into(), Vec::new(), span())];
+
+        let (text, idents) = resolve(&bibliography, citations, placeholder);
+
+        assert_eq!(text, "(Zed, 2001)");
+        assert_eq!(idents, ["ref-a", "ref-b"]);
+    }
+
+    #[test]
+    fn entries_without_authors_are_sorted_by_title() {
+        let mut untitled = entry("none", "", "2000");
+        untitled.authors.clear();
+        untitled.title = Some("No author".into());
+
+        let mut bibliography = Bibliography::default();
+        let options = Options {
+            title: EcoString::new(),
+            full: true,
+        };
+        let entries = vec![untitled, entry("knuth", "Knuth", "1984")];
+        let placeholder = bibliography.add(entries, Style::Apa, options);
+        let citations = vec![bibliography.cite("none".into(), Vec::new(), span())];
+
+        let (text, idents) = resolve(&bibliography, citations, placeholder);
+
+        assert_eq!(text, "(No author, 2000)");
+        assert_eq!(idents, ["ref-knuth", "ref-none"]);
+    }
+
+    #[test]
+    fn references_to_keys_are_citations() {
+        let (bibliography, placeholder) = bibliography(Style::Ieee, false);
+        let mut labels = Labels::default();
+        let locator = vec![ir::Inline::Str("p. 5".into())];
+        let citations = vec![labels.reference("b".into(), locator, span())];
+
+        let (text, idents) = resolve(&bibliography, citations, placeholder);
+
+        assert_eq!(text, "[1, p. 5]");
+        assert_eq!(idents, ["ref-b"]);
+    }
+
+    #[test]
+    fn unknown_citations() {
+        let source = Source::new("test.tyd", "test.tyd", "");
+        let mut tracer = Tracer::new(source, Spans::from(Vec::new()));
+
+        let mut empty = Bibliography::default();
+        empty.cite("a".into(), Vec::new(), span());
+        empty.check(&mut tracer);
+
+        let (mut bibliography, _) = bibliography(Style::Ieee, false);
+        bibliography.cite("b".into(), Vec::new(), span());
+        bibliography.cite("bb".into(), Vec::new(), span());
+        bibliography.check(&mut tracer);
+
+        let (diags, _) = tracer.into_inner();
+        assert_eq!(diags.len(), 2);
+        assert!(diags[0].help.as_ref().unwrap().contains("#bibliography"));
+        assert_eq!(diags[1].help.as_deref(), Some("Did you mean 'b'?"));
+    }
+}
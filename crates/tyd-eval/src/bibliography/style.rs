@@ -0,0 +1,432 @@
+use ecow::{eco_format, EcoString};
+
+use super::Entry;
+use crate::ir;
+
+/// A citation style, which formats citations and the reference list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Style {
+    /// Numbered citations like `[1]`, references in the order they are cited
+    #[default]
+    Ieee,
+    /// Citations like `(Doe & Smith, 2020)`, references sorted by author
+    Apa,
+    /// Citations like `(Doe and Smith 2020)`, references sorted by author
+    AuthorYear,
+}
+
+impl Style {
+    /// The names of all styles, as accepted by `Style::parse`
+    pub const NAMES: &[&str] = &["ieee", "apa", "author-year"];
+
+    /// Returns the style with the given name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ieee" => Some(Self::Ieee),
+            "apa" => Some(Self::Apa),
+            "author-year" => Some(Self::AuthorYear),
+            _ => None,
+        }
+    }
+
+    /// Returns whether references are numbered in the order they are first cited,
+    /// otherwise they are sorted by author, year and title.
+    pub fn is_numbered(self) -> bool {
+        self == Self::Ieee
+    }
+
+    /// Formats an in-text citation.
+    ///
+    /// # Arguments
+    /// * `entry` - The cited entry
+    /// * `number` - The number of the entry in the reference list
+    /// * `locator` - The cited part of the entry, e.g. `p. 5`, which may be empty
+    pub fn citation(self, entry: &Entry, number: usize, locator: &[ir::Inline]) -> ir::Content {
+        let (open, close, mut content) = match self {
+            Self::Ieee => ("[", "]", text(&number.to_string())),
+            Self::Apa => {
+                let authors = short_authors(entry, "&");
+                ("(", ")", text(&format!("{authors}, {}", year(entry))))
+            }
+            Self::AuthorYear => {
+                let authors = short_authors(entry, "and");
+                ("(", ")", text(&format!("{authors} {}", year(entry))))
+            }
+        };
+
+        if !locator.is_empty() {
+            content.push(ir::Inline::Str(",".to_owned()));
+            content.push(ir::Inline::Space);
+            content.extend(locator.iter().cloned());
+        }
+
+        let mut out = vec![ir::Inline::Str(open.to_owned())];
+        out.extend(content);
+        out.push(ir::Inline::Str(close.to_owned()));
+        out
+    }
+
+    /// Formats an entry of the reference list.
+    ///
+    /// # Arguments
+    /// * `entry` - The entry to format
+    /// * `number` - The number of the entry in the reference list
+    pub fn reference(self, entry: &Entry, number: usize) -> ir::Content {
+        let mut out = Vec::new();
+
+        match self {
+            Self::Ieee => {
+                let mut parts = Vec::new();
+
+                if !entry.authors.is_empty() {
+                    let names = entry.authors.iter().map(|person| {
+                        let initials = person.initials();
+                        if initials.is_empty() {
+                            person.family.clone()
+                        } else {
+                            eco_format!("{initials} {}", person.family)
+                        }
+                    });
+                    parts.push(text(&join(names.collect(), "and")));
+                }
+                if let Some(title) = &entry.title {
+                    parts.push(quoted(title));
+                }
+                if let Some(container) = &entry.container {
+                    parts.push(vec![ir::Inline::Emph(text(container))]);
+                }
+                if let Some(volume) = &entry.volume {
+                    parts.push(text(&format!("vol. {volume}")));
+                }
+                if let Some(issue) = &entry.issue {
+                    parts.push(text(&format!("no. {issue}")));
+                }
+                if let Some(pages) = &entry.pages {
+                    parts.push(text(&format!("pp. {pages}")));
+                }
+                if let Some(publisher) = &entry.publisher {
+                    parts.push(text(publisher));
+                }
+                if let Some(year) = &entry.year {
+                    parts.push(text(year));
+                }
+
+                out.extend(text(&format!("[{number}]")));
+                out.push(ir::Inline::Space);
+                out.extend(join_inlines(parts, ","));
+                out.push(ir::Inline::Str(".".to_owned()));
+            }
+            Self::Apa => {
+                let names = entry
+                    .authors
+                    .iter()
+                    .map(|person| {
+                        let initials = person.initials();
+                        if initials.is_empty() {
+                            person.family.clone()
+                        } else {
+                            eco_format!("{}, {initials}", person.family)
+                        }
+                    })
+                    .collect();
+
+                // Works without authors start with their title instead
+                if !entry.authors.is_empty() {
+                    out.extend(text(&join(names, "&")));
+                    out.push(ir::Inline::Space);
+                } else if let Some(title) = &entry.title {
+                    out.extend(text(&sentence(title)));
+                    out.push(ir::Inline::Space);
+                }
+
+                out.extend(text(&format!("({}).", year(entry))));
+
+                if let Some(title) = entry.title.as_ref().filter(|_| !entry.authors.is_empty()) {
+                    out.push(ir::Inline::Space);
+                    out.extend(text(&sentence(title)));
+                }
+
+                self.container(entry, &mut out);
+            }
+            Self::AuthorYear => {
+                let names = entry
+                    .authors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, person)| match (i, person.given.is_empty()) {
+                        (_, true) => person.family.clone(),
+                        (0, false) => eco_format!("{}, {}", person.family, person.given),
+                        (_, false) => eco_format!("{} {}", person.given, person.family),
+                    })
+                    .collect();
+
+                // Works without authors start with their title instead
+                if !entry.authors.is_empty() {
+                    out.extend(text(&sentence(&join(names, "and"))));
+                    out.push(ir::Inline::Space);
+                } else if let Some(title) = &entry.title {
+                    out.extend(quoted(&sentence(title)));
+                    out.push(ir::Inline::Space);
+                }
+
+                out.extend(text(&format!("{}.", year(entry))));
+
+                if let Some(title) = entry.title.as_ref().filter(|_| !entry.authors.is_empty()) {
+                    out.push(ir::Inline::Space);
+                    out.extend(quoted(&sentence(title)));
+                }
+
+                self.container(entry, &mut out);
+            }
+        }
+
+        if let Some(link) = entry
+            .doi
+            .as_ref()
+            .map(|doi| eco_format!("https://doi.org/{doi}"))
+        {
+            out.push(ir::Inline::Space);
+            out.push(link_inline(&link));
+        } else if let Some(url) = &entry.url {
+            out.push(ir::Inline::Space);
+            out.push(link_inline(url));
+        }
+
+        out
+    }
+
+    /// Appends the container, volume, issue, pages and publisher of an entry.
+    fn container(self, entry: &Entry, out: &mut ir::Content) {
+        if let Some(container) = &entry.container {
+            out.push(ir::Inline::Space);
+            out.push(ir::Inline::Emph(text(container)));
+
+            let mut details = String::new();
+
+            if let Some(volume) = &entry.volume {
+                details.push_str(&format!(" {volume}"));
+            }
+            if let Some(issue) = &entry.issue {
+                details.push_str(&format!(" ({issue})"));
+            }
+            if let Some(pages) = &entry.pages {
+                let separator = if self == Self::Apa { "," } else { ":" };
+                details.push_str(&format!("{separator} {pages}"));
+            }
+
+            if self == Self::Apa && entry.volume.is_some() {
+                details.insert(0, ',');
+            }
+
+            out.extend(text_after(&format!("{details}.")));
+        }
+
+        if let Some(publisher) = &entry.publisher {
+            out.push(ir::Inline::Space);
+            out.extend(text(&format!("{publisher}.")));
+        }
+    }
+}
+
+/// Returns the year of an entry, or `n.d.` if it has none.
+fn year(entry: &Entry) -> EcoString {
+    entry.year.clone().unwrap_or_else(|| "n.d.".into())
+}
+
+/// Formats the authors of an in-text citation, e.g. `Doe & Smith` or `Doe et al.`.
+///
+/// Works without authors are cited by their title, or as `Anonymous` without a title.
+fn short_authors(entry: &Entry, and: &str) -> EcoString {
+    match entry.authors.as_slice() {
+        [] => entry.lead().cloned().unwrap_or_else(|| "Anonymous".into()),
+        [one] => one.family.clone(),
+        [first, second] => eco_format!("{} {and} {}", first.family, second.family),
+        [first, ..] => eco_format!("{} et al.", first.family),
+    }
+}
+
+/// Joins names, e.g. `A, B, and C`, or `A and B` for two names.
+fn join(names: Vec<EcoString>, and: &str) -> EcoString {
+    match names.as_slice() {
+        [] => EcoString::new(),
+        [one] => one.clone(),
+        [first, second] => eco_format!("{first} {and} {second}"),
+        [rest @ .., last] => eco_format!("{}, {and} {last}", rest.join(", ")),
+    }
+}
+
+/// Ends a text with a period, unless it already ends with punctuation.
+fn sentence(text: &str) -> EcoString {
+    if text.ends_with(['.', '?', '!']) {
+        text.into()
+    } else {
+        eco_format!("{text}.")
+    }
+}
+
+/// Converts text to inlines, separating words by spaces.
+fn text(text: &str) -> ir::Content {
+    let mut out = Vec::new();
+
+    for (i, word) in text.split(' ').filter(|word| !word.is_empty()).enumerate() {
+        if i > 0 {
+            out.push(ir::Inline::Space);
+        }
+        out.push(ir::Inline::Str(word.to_owned()));
+    }
+
+    out
+}
+
+/// Converts text to inlines which directly follow the previous inline,
+/// keeping a leading space.
+fn text_after(value: &str) -> ir::Content {
+    let mut out = Vec::new();
+
+    if value.starts_with(' ') {
+        out.push(ir::Inline::Space);
+    }
+
+    out.extend(text(value));
+    out
+}
+
+/// Encloses text in double quotes.
+fn quoted(value: &str) -> ir::Content {
+    vec![ir::Inline::Quoted(ir::QuoteType::DoubleQuote, text(value))]
+}
+
+/// Creates a link displaying its target.
+fn link_inline(url: &str) -> ir::Inline {
+    let target = (url.to_owned(), String::new());
+    ir::Inline::Link(ir::AttrBuilder::empty(), text(url), target)
+}
+
+/// Joins parts with a separator followed by a space.
+fn join_inlines(parts: Vec<ir::Content>, separator: &str) -> ir::Content {
+    let mut out = Vec::new();
+
+    for (i, part) in parts.into_iter().enumerate() {
+        if i > 0 {
+            out.push(ir::Inline::Str(separator.to_owned()));
+            out.push(ir::Inline::Space);
+        }
+        out.extend(part);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bibliography::Person, label::plain_text};
+
+    fn entry(authors: &[&str]) -> Entry {
+        Entry {
+            key: "key".into(),
+            kind: "article".into(),
+            authors: authors.iter().map(|name| Person::parse(name)).collect(),
+            title: Some("A Study".into()),
+            year: Some("2020".into()),
+            container: Some("Journal".into()),
+            publisher: None,
+            volume: Some("3".into()),
+            issue: Some("2".into()),
+            pages: Some("1-10".into()),
+            url: Some("https://example.com".into()),
+            doi: None,
+        }
+    }
+
+    fn citation(style: Style, entry: &Entry, locator: &str) -> String {
+        let locator = text(locator);
+        plain_text(&style.citation(entry, 4, &locator)).to_string()
+    }
+
+    fn reference(style: Style, entry: &Entry) -> String {
+        plain_text(&style.reference(entry, 4)).to_string()
+    }
+
+    #[test]
+    fn names() {
+        for name in Style::NAMES {
+            assert!(Style::parse(name).is_some());
+        }
+        assert_eq!(Style::parse("IEEE"), None);
+        assert!(Style::Ieee.is_numbered());
+        assert!(!Style::Apa.is_numbered());
+    }
+
+    #[test]
+    fn citations() {
+        let two = entry(&["Doe, Jane", "John Smith"]);
+
+        assert_eq!(citation(Style::Ieee, &two, ""), "[4]");
+        assert_eq!(citation(Style::Ieee, &two, "p. 5"), "[4, p. 5]");
+        assert_eq!(citation(Style::Apa, &two, ""), "(Doe & Smith, 2020)");
+        assert_eq!(
+            citation(Style::AuthorYear, &two, ""),
+            "(Doe and Smith 2020)"
+        );
+
+        let three = entry(&["A, B", "C, D", "E, F"]);
+        assert_eq!(citation(Style::Apa, &three, ""), "(A et al., 2020)");
+
+        let mut untitled = entry(&[]);
+        assert_eq!(citation(Style::Apa, &untitled, ""), "(A Study, 2020)");
+
+        untitled.title = None;
+        untitled.year = None;
+        assert_eq!(citation(Style::Apa, &untitled, ""), "(Anonymous, n.d.)");
+    }
+
+    #[test]
+    fn references() {
+        let entry = entry(&["Doe, Jane Ann", "John Smith", "Plato"]);
+
+        assert_eq!(
+            reference(Style::Ieee, &entry),
+            "[4] J. A. Doe, J. Smith, and Plato, A Study, Journal, vol. 3, no. 2, pp. 1-10, 2020. https://example.com"
+        );
+        assert_eq!(
+            reference(Style::Apa, &entry),
+            "Doe, J. A., Smith, J., & Plato (2020). A Study. Journal, 3 (2), 1-10. https://example.com"
+        );
+        assert_eq!(
+            reference(Style::AuthorYear, &entry),
+            "Doe, Jane Ann, John Smith, and Plato. 2020. A Study. Journal 3 (2): 1-10. https://example.com"
+        );
+    }
+
+    #[test]
+    fn references_without_authors_start_with_the_title() {
+        let entry = entry(&[]);
+
+        assert_eq!(
+            reference(Style::Apa, &entry),
+            "A Study. (2020). Journal, 3 (2), 1-10. https://example.com"
+        );
+        assert_eq!(
+            reference(Style::AuthorYear, &entry),
+            "A Study. 2020. Journal 3 (2): 1-10. https://example.com"
+        );
+    }
+
+    #[test]
+    fn doi_is_preferred_over_url() {
+        let mut entry = entry(&[]);
+        entry.doi = Some("10.1000/xyz".into());
+
+        assert!(reference(Style::Ieee, &entry).ends_with(" https://doi.org/10.1000/xyz"));
+    }
+
+    #[test]
+    fn helpers() {
+        assert_eq!(join(Vec::new(), "and"), "");
+        assert_eq!(join(vec!["A".into(), "B".into()], "&"), "A & B");
+        assert_eq!(sentence("Why?"), "Why?");
+        assert_eq!(sentence("Title"), "Title.");
+        assert_eq!(text("  a  b "), text("a b"));
+    }
+}
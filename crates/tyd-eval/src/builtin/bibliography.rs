@@ -0,0 +1,130 @@
+use ecow::EcoString;
+use std::fs;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    bibliography::{self, Options, Style},
+    error::{EngineError, ReferenceError},
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'bibliography' function which loads entries to cite.
+///
+/// This struct is used as a type to represent the bibliography function.
+#[derive(Debug, Clone, Copy)]
+pub struct Bibliography;
+
+impl From<Bibliography> for Value {
+    /// Converts the Bibliography struct into a function Value.
+    fn from(_: Bibliography) -> Self {
+        let signature = Signature::new(
+            "Loads a BibTeX or Hayagriva file and displays the list of cited references.",
+            Type::Block,
+        )
+        .positional(
+            "path",
+            Type::Str,
+            "Path to the `.bib` or `.yml` file, relative to the current document",
+        )
+        .named_or(
+            "style",
+            Type::Str,
+            "ieee",
+            "The citation style, one of `ieee`, `apa` or `author-year`",
+        )
+        .named_or(
+            "title",
+            Type::Str,
+            "References",
+            "The title of the reference list, empty for none",
+        )
+        .named_or(
+            "full",
+            Type::Bool,
+            false,
+            "Whether all entries are listed, not only the cited ones",
+        );
+
+        Value::Func(Func::new("bibliography", signature, bibliography))
+    }
+}
+
+/// Loads a bibliography and displays the list of cited references.
+///
+/// The reference list contains the entries cited anywhere in the document,
+/// as it is filled after the whole document was evaluated.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `path` - Path to the bibliography file, relative to the current document
+///   * `style` - The citation style, defaults to `ieee`
+///   * `title` - The title of the reference list, defaults to `References`
+///   * `full` - Whether uncited entries are listed, defaults to false
+/// * `source` - Source information for resolving the path
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting issues
+/// * `state` - Document state holding the bibliography
+///
+/// # Returns
+///
+/// A Value::Block containing the reference list or Value::None if an error occurred.
+pub fn bibliography(
+    mut args: Args,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let path = args.take::<EcoString>("path");
+    let style = args.take::<EcoString>("style");
+    let title = args.take::<EcoString>("title");
+    let full = args.take::<bool>("full");
+
+    if state.bibliography.is_added() {
+        tracer.source_error(span, ReferenceError::MultipleBibliographies);
+        return Value::None;
+    }
+
+    let Some(style) = Style::parse(&style) else {
+        let expected = Style::NAMES.join(", ");
+        tracer.source_error(
+            span,
+            format!("Unknown citation style '{style}', expected one of: {expected}"),
+        );
+        return Value::None;
+    };
+
+    let path = source.work_path().join(path.as_str());
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) => {
+            let error = EngineError::ReadFile {
+                path: path.display().to_string(),
+                message: err.to_string(),
+            };
+            tracer.source_error(span, error);
+            return Value::None;
+        }
+    };
+
+    let entries = match bibliography::parse(&path, &text) {
+        Ok(entries) => entries,
+        Err(message) => {
+            let error = EngineError::ParseFile {
+                path: path.display().to_string(),
+                message,
+            };
+            tracer.source_error(span, error);
+            return Value::None;
+        }
+    };
+
+    let options = Options { title, full };
+    Value::Block(state.bibliography.add(entries, style, options))
+}
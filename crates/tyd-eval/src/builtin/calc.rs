@@ -0,0 +1,697 @@
+use ecow::{eco_format, EcoString};
+use std::cmp::Ordering;
+use tyd_syntax::{source::Source, Span};
+
+use super::{lists::compare, module_func};
+use crate::{
+    counter, ir,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'calc' module, a map of functions working with numbers.
+///
+/// Functions accept integers and floats alike. Integer overflow and division by zero
+/// are reported as errors and return `none`.
+#[derive(Debug, Clone, Copy)]
+pub struct Calc;
+
+impl From<Calc> for Value {
+    /// Converts the Calc struct into a map of function Values.
+    fn from(_: Calc) -> Self {
+        let x = "The number";
+
+        let functions = [
+            module_func(
+                "calc",
+                "abs",
+                Signature::new("Returns the absolute value of a number.", Type::Any).positional(
+                    "x",
+                    Type::Any,
+                    x,
+                ),
+                abs,
+            ),
+            module_func(
+                "calc",
+                "min",
+                Signature::new("Returns the smallest of the values.", Type::Any).rest(
+                    "values",
+                    Type::Any,
+                    "The compared values",
+                ),
+                min,
+            ),
+            module_func(
+                "calc",
+                "max",
+                Signature::new("Returns the largest of the values.", Type::Any).rest(
+                    "values",
+                    Type::Any,
+                    "The compared values",
+                ),
+                max,
+            ),
+            module_func(
+                "calc",
+                "round",
+                Signature::new(
+                    "Rounds a number to the nearest integer, or to a number of decimal digits.",
+                    Type::Any,
+                )
+                .positional("x", Type::Any, x)
+                .named_or(
+                    "digits",
+                    Type::Int,
+                    0,
+                    "The number of decimal digits kept, which returns a float if not zero",
+                ),
+                round,
+            ),
+            module_func(
+                "calc",
+                "floor",
+                Signature::new("Rounds a number down to an integer.", Type::Int).positional(
+                    "x",
+                    Type::Any,
+                    x,
+                ),
+                floor,
+            ),
+            module_func(
+                "calc",
+                "ceil",
+                Signature::new("Rounds a number up to an integer.", Type::Int).positional(
+                    "x",
+                    Type::Any,
+                    x,
+                ),
+                ceil,
+            ),
+            module_func(
+                "calc",
+                "pow",
+                Signature::new("Raises a number to a power.", Type::Any)
+                    .positional("base", Type::Any, "The base")
+                    .positional("exponent", Type::Any, "The exponent"),
+                pow,
+            ),
+            module_func(
+                "calc",
+                "sqrt",
+                Signature::new("Returns the square root of a number.", Type::Float).positional(
+                    "x",
+                    Type::Any,
+                    x,
+                ),
+                sqrt,
+            ),
+            module_func(
+                "calc",
+                "clamp",
+                Signature::new("Restricts a number to a range.", Type::Any)
+                    .positional("x", Type::Any, x)
+                    .positional("min", Type::Any, "The smallest allowed value")
+                    .positional("max", Type::Any, "The largest allowed value"),
+                clamp,
+            ),
+            module_func(
+                "calc",
+                "rem",
+                Signature::new("Returns the remainder of a division.", Type::Any)
+                    .positional("dividend", Type::Any, "The dividend")
+                    .positional("divisor", Type::Any, "The divisor"),
+                rem,
+            ),
+            module_func(
+                "calc",
+                "quo",
+                Signature::new(
+                    "Returns the quotient of a division, rounded down.",
+                    Type::Int,
+                )
+                .positional("dividend", Type::Any, "The dividend")
+                .positional("divisor", Type::Any, "The divisor"),
+                quo,
+            ),
+            module_func(
+                "calc",
+                "int",
+                Signature::new(
+                    "Converts a float, string or boolean to an integer, floats are truncated.",
+                    Type::Int,
+                )
+                .positional("value", Type::Any, "The converted value"),
+                int,
+            ),
+            module_func(
+                "calc",
+                "float",
+                Signature::new(
+                    "Converts an integer, string or boolean to a float.",
+                    Type::Float,
+                )
+                .positional("value", Type::Any, "The converted value"),
+                float,
+            ),
+            module_func(
+                "calc",
+                "fmt",
+                Signature::new(
+                    "Formats a number, e.g. `calc.fmt(1234.5, decimals: 2, thousands: \",\")`.",
+                    Type::Str,
+                )
+                .positional("x", Type::Any, x)
+                .named_or(
+                    "decimals",
+                    Type::Int,
+                    Value::None,
+                    "The number of decimal digits, all digits of floats if not given",
+                )
+                .named_or(
+                    "thousands",
+                    Type::Str,
+                    "",
+                    "The separator between groups of three digits",
+                ),
+                fmt,
+            ),
+            module_func(
+                "calc",
+                "percent",
+                Signature::new(
+                    "Formats a ratio as a percentage, e.g. `0.25` as `25%`.",
+                    Type::Str,
+                )
+                .positional("x", Type::Any, "The ratio")
+                .named_or("decimals", Type::Int, 0, "The number of decimal digits"),
+                percent,
+            ),
+            module_func(
+                "calc",
+                "roman",
+                Signature::new("Formats an integer as roman numerals.", Type::Str)
+                    .positional("x", Type::Int, "The integer, from 1 up to 3999")
+                    .named_or(
+                        "upper",
+                        Type::Bool,
+                        true,
+                        "Whether to use uppercase numerals",
+                    ),
+                roman,
+            ),
+        ];
+
+        Value::Map(functions.into_iter().collect())
+    }
+}
+
+/// A number passed to a function of the module.
+#[derive(Debug, Clone, Copy)]
+enum Num {
+    /// An integer
+    Int(i64),
+    /// A float
+    Float(f64),
+}
+
+impl Num {
+    /// Returns the number as a float.
+    fn float(self) -> f64 {
+        match self {
+            Self::Int(i) => i as f64,
+            Self::Float(f) => f,
+        }
+    }
+}
+
+/// Takes a number argument, reporting an error if the value is not a number.
+fn take_num(args: &mut Args, name: &str, span: Span, tracer: &mut Tracer) -> Option<Num> {
+    match args.take::<Value>(name) {
+        Value::Int(i) => Some(Num::Int(i)),
+        Value::Float(f) => Some(Num::Float(f)),
+        other => {
+            tracer.source_error(
+                span,
+                format!("Wrong type of {}, expected: Int or Float", other.ty()),
+            );
+            None
+        }
+    }
+}
+
+/// Converts a float to an integer, reporting an error if it is out of range.
+fn to_int(f: f64, span: Span, tracer: &mut Tracer) -> Value {
+    if f.is_finite() && f >= i64::MIN as f64 && f < i64::MAX as f64 {
+        Value::Int(f as i64)
+    } else {
+        tracer.source_error(span, format!("Number {f} is out of the range of integers"));
+        Value::None
+    }
+}
+
+/// Reports an integer overflow.
+fn overflow(span: Span, tracer: &mut Tracer) -> Value {
+    tracer.source_error(span, "Integer overflow");
+    Value::None
+}
+
+/// Reports a division by zero.
+fn division_by_zero(span: Span, tracer: &mut Tracer) -> Value {
+    tracer.source_error(span, "Division by zero");
+    Value::None
+}
+
+/// Returns the absolute value of a number.
+fn abs(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_num(&mut args, "x", span, tracer) {
+        Some(Num::Int(i)) => i
+            .checked_abs()
+            .map_or_else(|| overflow(span, tracer), Value::Int),
+        Some(Num::Float(f)) => Value::Float(f.abs()),
+        None => Value::None,
+    }
+}
+
+/// Returns the value which is first in the ordering among the values.
+fn extreme(values: ir::List, wanted: Ordering, span: Span, tracer: &mut Tracer) -> Value {
+    let mut values = values.into_iter();
+
+    let Some(mut extreme) = values.next() else {
+        tracer.source_error(span, "Expected at least one value");
+        return Value::None;
+    };
+
+    for value in values {
+        match compare(&value, &extreme) {
+            Some(ordering) if ordering == wanted => extreme = value,
+            Some(_) => {}
+            None => {
+                tracer.source_error(
+                    span,
+                    format!(
+                        "Cannot compare values of type {} and {}",
+                        value.ty(),
+                        extreme.ty()
+                    ),
+                );
+                return Value::None;
+            }
+        }
+    }
+
+    extreme
+}
+
+/// Returns the smallest of the values.
+fn min(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    extreme(args.take("values"), Ordering::Less, span, tracer)
+}
+
+/// Returns the largest of the values.
+fn max(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    extreme(args.take("values"), Ordering::Greater, span, tracer)
+}
+
+/// Rounds a number to the nearest integer, or to a number of decimal digits.
+fn round(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let Some(x) = take_num(&mut args, "x", span, tracer) else {
+        return Value::None;
+    };
+    let digits = args.take::<i64>("digits");
+
+    match x {
+        Num::Int(i) => Value::Int(i),
+        Num::Float(f) if digits == 0 => to_int(f.round(), span, tracer),
+        Num::Float(f) => {
+            let factor = 10f64.powi(digits.clamp(-308, 308) as i32);
+            Value::Float((f * factor).round() / factor)
+        }
+    }
+}
+
+/// Rounds a number down to an integer.
+fn floor(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_num(&mut args, "x", span, tracer) {
+        Some(Num::Int(i)) => Value::Int(i),
+        Some(Num::Float(f)) => to_int(f.floor(), span, tracer),
+        None => Value::None,
+    }
+}
+
+/// Rounds a number up to an integer.
+fn ceil(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_num(&mut args, "x", span, tracer) {
+        Some(Num::Int(i)) => Value::Int(i),
+        Some(Num::Float(f)) => to_int(f.ceil(), span, tracer),
+        None => Value::None,
+    }
+}
+
+/// Raises a number to a power.
+///
+/// Integers raised to non-negative integer powers stay integers, any other power is a float.
+fn pow(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let (Some(base), Some(exponent)) = (
+        take_num(&mut args, "base", span, tracer),
+        take_num(&mut args, "exponent", span, tracer),
+    ) else {
+        return Value::None;
+    };
+
+    match (base, exponent) {
+        (Num::Int(base), Num::Int(exponent)) if exponent >= 0 => u32::try_from(exponent)
+            .ok()
+            .and_then(|exponent| base.checked_pow(exponent))
+            .map_or_else(|| overflow(span, tracer), Value::Int),
+        _ if base.float() == 0.0 && exponent.float() < 0.0 => division_by_zero(span, tracer),
+        _ => Value::Float(base.float().powf(exponent.float())),
+    }
+}
+
+/// Returns the square root of a number.
+fn sqrt(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let Some(x) = take_num(&mut args, "x", span, tracer) else {
+        return Value::None;
+    };
+
+    if x.float() < 0.0 {
+        tracer.source_error(span, "Cannot take the square root of a negative number");
+        return Value::None;
+    }
+
+    Value::Float(x.float().sqrt())
+}
+
+/// Restricts a number to the range from `min` to `max`.
+fn clamp(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let (Some(x), Some(min), Some(max)) = (
+        take_num(&mut args, "x", span, tracer),
+        take_num(&mut args, "min", span, tracer),
+        take_num(&mut args, "max", span, tracer),
+    ) else {
+        return Value::None;
+    };
+
+    if min.float() > max.float() {
+        tracer.source_error(span, "Minimum of clamp must not be larger than its maximum");
+        return Value::None;
+    }
+
+    match (x, min, max) {
+        (Num::Int(x), Num::Int(min), Num::Int(max)) => Value::Int(x.clamp(min, max)),
+        _ => Value::Float(x.float().clamp(min.float(), max.float())),
+    }
+}
+
+/// Returns the remainder of a division, which has the sign of the dividend.
+fn rem(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let (Some(dividend), Some(divisor)) = (
+        take_num(&mut args, "dividend", span, tracer),
+        take_num(&mut args, "divisor", span, tracer),
+    ) else {
+        return Value::None;
+    };
+
+    if divisor.float() == 0.0 {
+        return division_by_zero(span, tracer);
+    }
+
+    match (dividend, divisor) {
+        (Num::Int(a), Num::Int(b)) => a
+            .checked_rem(b)
+            .map_or_else(|| overflow(span, tracer), Value::Int),
+        (a, b) => Value::Float(a.float() % b.float()),
+    }
+}
+
+/// Returns the quotient of a division, rounded down.
+fn quo(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let (Some(dividend), Some(divisor)) = (
+        take_num(&mut args, "dividend", span, tracer),
+        take_num(&mut args, "divisor", span, tracer),
+    ) else {
+        return Value::None;
+    };
+
+    if divisor.float() == 0.0 {
+        return division_by_zero(span, tracer);
+    }
+
+    match (dividend, divisor) {
+        (Num::Int(a), Num::Int(b)) => a
+            .checked_div_euclid(b)
+            .map(|q| {
+                if b < 0 && a.rem_euclid(b) != 0 {
+                    q - 1
+                } else {
+                    q
+                }
+            })
+            .map_or_else(|| overflow(span, tracer), Value::Int),
+        (a, b) => to_int((a.float() / b.float()).floor(), span, tracer),
+    }
+}
+
+/// Converts a value to an integer.
+fn int(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match args.take::<Value>("value") {
+        Value::Int(i) => Value::Int(i),
+        Value::Float(f) => to_int(f.trunc(), span, tracer),
+        Value::Bool(b) => Value::Int(b as i64),
+        Value::Str(s) => match s.trim().parse::<i64>() {
+            Ok(i) => Value::Int(i),
+            Err(_) => {
+                tracer.source_error(span, format!("Cannot convert '{s}' to an integer"));
+                Value::None
+            }
+        },
+        other => {
+            tracer.source_error(
+                span,
+                format!("Wrong type of {}, expected: Float, Str or Bool", other.ty()),
+            );
+            Value::None
+        }
+    }
+}
+
+/// Converts a value to a float.
+fn float(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match args.take::<Value>("value") {
+        Value::Int(i) => Value::Float(i as f64),
+        Value::Float(f) => Value::Float(f),
+        Value::Bool(b) => Value::Float(b as i64 as f64),
+        Value::Str(s) => match s.trim().parse::<f64>() {
+            Ok(f) => Value::Float(f),
+            Err(_) => {
+                tracer.source_error(span, format!("Cannot convert '{s}' to a float"));
+                Value::None
+            }
+        },
+        other => {
+            tracer.source_error(
+                span,
+                format!("Wrong type of {}, expected: Int, Str or Bool", other.ty()),
+            );
+            Value::None
+        }
+    }
+}
+
+/// Formats a number with a number of decimal digits and a thousands separator.
+///
+/// # Arguments
+/// * `x` - The formatted number
+/// * `decimals` - The number of decimal digits, all digits of floats if None
+/// * `thousands` - The separator between groups of three digits of the integer part
+fn format_num(x: Num, decimals: Option<usize>, thousands: &str) -> EcoString {
+    let formatted = match (x, decimals) {
+        (Num::Int(i), None) => eco_format!("{i}"),
+        (Num::Float(f), None) => eco_format!("{f}"),
+        (x, Some(decimals)) => eco_format!("{:.decimals$}", x.float()),
+    };
+
+    if thousands.is_empty() {
+        return formatted;
+    }
+
+    let (sign, digits) = match formatted.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", formatted.as_str()),
+    };
+    let (integer, fraction) = match digits.find('.') {
+        Some(dot) => digits.split_at(dot),
+        None => (digits, ""),
+    };
+
+    let mut out = EcoString::from(sign);
+
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            out.push_str(thousands);
+        }
+        out.push(digit);
+    }
+
+    out.push_str(fraction);
+    out
+}
+
+/// Takes the number of decimal digits, reporting an error if it is negative.
+fn take_decimals(args: &mut Args, span: Span, tracer: &mut Tracer) -> Result<Option<usize>, ()> {
+    match args.take::<Value>("decimals") {
+        Value::Int(decimals) => match usize::try_from(decimals) {
+            Ok(decimals) => Ok(Some(decimals.min(64))),
+            Err(_) => {
+                tracer.source_error(span, "Number of decimal digits must not be negative");
+                Err(())
+            }
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Formats a number.
+fn fmt(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let Some(x) = take_num(&mut args, "x", span, tracer) else {
+        return Value::None;
+    };
+    let Ok(decimals) = take_decimals(&mut args, span, tracer) else {
+        return Value::None;
+    };
+    let thousands = args.take::<EcoString>("thousands");
+
+    Value::Str(format_num(x, decimals, &thousands))
+}
+
+/// Formats a ratio as a percentage.
+fn percent(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let Some(x) = take_num(&mut args, "x", span, tracer) else {
+        return Value::None;
+    };
+    let Ok(decimals) = take_decimals(&mut args, span, tracer) else {
+        return Value::None;
+    };
+
+    let percentage = Num::Float(x.float() * 100.0);
+    Value::Str(eco_format!("{}%", format_num(percentage, decimals, "")))
+}
+
+/// Formats an integer as roman numerals.
+fn roman(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let x = args.take::<i64>("x");
+    let upper = args.take::<bool>("upper");
+
+    if !(1..=3999).contains(&x) {
+        tracer.source_error(
+            span,
+            format!("Roman numerals only exist from 1 up to 3999, got {x}"),
+        );
+        return Value::None;
+    }
+
+    let numerals = counter::roman(x as usize);
+
+    if upper {
+        Value::Str(numerals)
+    } else {
+        Value::Str(numerals.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::tests::{call, field};
+
+    fn calc(name: &str, args: Vec<Value>) -> (Value, bool) {
+        call(field(Calc, name), args)
+    }
+
+    fn int(value: Value) -> i64 {
+        match value {
+            Value::Int(i) => i,
+            other => panic!("{other:?} is not an integer"),
+        }
+    }
+
+    #[test]
+    fn abs_of_negative_numbers() {
+        assert_eq!(int(calc("abs", vec![Value::Int(-5)]).0), 5);
+        assert!(matches!(calc("abs", vec![Value::Float(-0.5)]).0, Value::Float(f) if f == 0.5));
+
+        let (value, failed) = calc("abs", vec![Value::Int(i64::MIN)]);
+        assert!(failed);
+        assert!(matches!(value, Value::None));
+    }
+
+    #[test]
+    fn pow_with_negative_exponent() {
+        assert_eq!(
+            int(calc("pow", vec![Value::Int(2), Value::Int(10)]).0),
+            1024
+        );
+        assert!(
+            matches!(calc("pow", vec![Value::Int(2), Value::Int(-1)]).0, Value::Float(f) if f == 0.5)
+        );
+
+        let (_, failed) = calc("pow", vec![Value::Int(0), Value::Int(-1)]);
+        assert!(failed);
+
+        let (_, failed) = calc("pow", vec![Value::Int(2), Value::Int(64)]);
+        assert!(failed);
+    }
+
+    #[test]
+    fn sqrt_of_negative_number() {
+        let (value, failed) = calc("sqrt", vec![Value::Int(-1)]);
+        assert!(failed);
+        assert!(matches!(value, Value::None));
+    }
+
+    #[test]
+    fn quo_rounds_down() {
+        let quo = |a, b| int(calc("quo", vec![Value::Int(a), Value::Int(b)]).0);
+
+        assert_eq!(quo(7, 2), 3);
+        assert_eq!(quo(7, -2), -4);
+        assert_eq!(quo(-7, 2), -4);
+        assert_eq!(quo(-7, -2), 3);
+        assert_eq!(quo(-6, -2), 3);
+    }
+
+    #[test]
+    fn rem_has_sign_of_dividend() {
+        let rem = |a, b| int(calc("rem", vec![Value::Int(a), Value::Int(b)]).0);
+
+        assert_eq!(rem(-7, 2), -1);
+        assert_eq!(rem(7, -2), 1);
+
+        let (_, failed) = calc("rem", vec![Value::Int(1), Value::Int(0)]);
+        assert!(failed);
+    }
+
+    #[test]
+    fn clamp_rejects_inverted_bounds() {
+        let args = vec![Value::Int(-5), Value::Int(-3), Value::Int(3)];
+        assert_eq!(int(calc("clamp", args).0), -3);
+
+        let (_, failed) = calc("clamp", vec![Value::Int(0), Value::Int(3), Value::Int(-3)]);
+        assert!(failed);
+    }
+
+    #[test]
+    fn roman_out_of_range() {
+        let (value, failed) = calc("roman", vec![Value::Int(14)]);
+        assert!(!failed);
+        assert!(matches!(value, Value::Str(s) if s == "XIV"));
+
+        for x in [0, -1, 4000] {
+            let (_, failed) = calc("roman", vec![Value::Int(x)]);
+            assert!(failed, "{x}");
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use ecow::{eco_format, EcoString};
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    ir,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'cite' function which cites an entry of the bibliography.
+///
+/// This struct is used as a type to represent the cite function.
+#[derive(Debug, Clone, Copy)]
+pub struct Cite;
+
+impl From<Cite> for Value {
+    /// Converts the Cite struct into a function Value.
+    fn from(_: Cite) -> Self {
+        let signature = Signature::new("Cites an entry of the bibliography.", Type::Inline)
+            .positional("key", Type::Str, "The key of the cited entry")
+            .named_or(
+                "page",
+                Type::Int,
+                Value::None,
+                "The cited page of the entry",
+            );
+
+        Value::Func(Func::new("cite", signature, cite))
+    }
+}
+
+/// Cites an entry of the bibliography.
+///
+/// The citation is formatted with the style of the bibliography after the whole
+/// document was evaluated, so entries can be cited before the bibliography is added.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `key` - The key of the cited entry
+///   * `page` - (optional) The cited page of the entry
+/// * `_source` - Source information (unused)
+/// * `span` - Source span of the citation
+/// * `_tracer` - Error tracer (unused)
+/// * `state` - Document state holding the bibliography
+///
+/// # Returns
+///
+/// A Value::Inline containing the citation.
+pub fn cite(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    _tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let key = args.take::<EcoString>("key");
+    let page = args.take::<Value>("page");
+
+    let locator = match page {
+        Value::Int(page) => vec![ir::Inline::Str(eco_format!("p.\u{a0}{page}").to_string())],
+        _ => Vec::new(),
+    };
+
+    Value::Inline(state.bibliography.cite(key, locator, span))
+}
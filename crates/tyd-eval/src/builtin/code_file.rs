@@ -0,0 +1,409 @@
+use ecow::EcoString;
+use std::path::Path;
+use tyd_syntax::{error::SourceDiagnostic, source::Source, Span};
+
+use super::read::read_file;
+use crate::{
+    func::Func,
+    ir, label,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// The languages of file extensions which differ from the extension
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("py", "python"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("ts", "typescript"),
+    ("rb", "ruby"),
+    ("sh", "bash"),
+    ("h", "c"),
+    ("hpp", "cpp"),
+    ("cc", "cpp"),
+    ("cs", "csharp"),
+    ("kt", "kotlin"),
+    ("hs", "haskell"),
+    ("yml", "yaml"),
+    ("md", "markdown"),
+    ("tex", "latex"),
+    ("htm", "html"),
+];
+
+/// Represents the 'code_file' function which displays a source file as a code block.
+///
+/// This struct is used as a type to represent the code_file function.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeFile;
+
+impl From<CodeFile> for Value {
+    /// Converts the CodeFile struct into a function Value.
+    fn from(_: CodeFile) -> Self {
+        let signature = Signature::new(
+            "Displays a source file, a range of its lines or a marked region as a code block.",
+            Type::Block,
+        )
+        .positional(
+            "path",
+            Type::Str,
+            "Path to the file, relative to the current document",
+        )
+        .named_or(
+            "lang",
+            Type::Str,
+            "",
+            "The language of the code, derived from the file extension if empty",
+        )
+        .named_or(
+            "lines",
+            Type::Str,
+            "",
+            "The displayed lines, e.g. `10-42`, `10-` or `7`, starting at one",
+        )
+        .named_or(
+            "region",
+            Type::Str,
+            "",
+            "The name of the displayed region, marked by `region: <name>` and `endregion` lines",
+        );
+
+        Value::Func(Func::new("code_file", signature, code_file))
+    }
+}
+
+/// Displays a source file as a code block.
+///
+/// Regions are marked by comment lines in the file, e.g. `// region: setup`
+/// and `// endregion`, and may be nested, the marker lines are not displayed.
+/// The displayed lines are dedented by their common indentation.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `path` - Path to the file, relative to the current document
+///   * `lang` - The language of the code, derived from the file extension if empty
+///   * `lines` - The range of displayed lines, all lines if empty
+///   * `region` - The name of the displayed region, the whole file if empty
+/// * `source` - Source information for resolving the path
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting unreadable files, invalid ranges and missing regions
+/// * `_state` - Document state (unused)
+///
+/// # Returns
+///
+/// A Value::Block containing the code block or Value::None if an error occurred.
+pub fn code_file(
+    mut args: Args,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _state: &mut State,
+) -> Value {
+    let path = args.take::<EcoString>("path");
+    let lang = args.take::<EcoString>("lang");
+    let range = args.take::<EcoString>("lines");
+    let region = args.take::<EcoString>("region");
+
+    if !range.is_empty() && !region.is_empty() {
+        tracer.source_error(span, "Only one of `lines` and `region` can be given");
+        return Value::None;
+    }
+
+    let Some((path, text)) = read_file(&path, source, span, tracer) else {
+        return Value::None;
+    };
+
+    let lines: Vec<&str> = text.lines().collect();
+
+    let selected = if !range.is_empty() {
+        match select_lines(&lines, &range) {
+            Ok(selected) => selected,
+            Err(message) => {
+                tracer.source_error(span, message);
+                return Value::None;
+            }
+        }
+    } else if !region.is_empty() {
+        match select_region(&lines, &region, &path, span) {
+            Ok(selected) => selected,
+            Err(diag) => {
+                tracer.diagnose_source(diag);
+                return Value::None;
+            }
+        }
+    } else {
+        lines
+    };
+
+    let lang = match lang.is_empty() {
+        true => language(&path),
+        false => Some(lang.to_string()),
+    };
+    let attr = ir::AttrBuilder::new().class_opt(lang).build();
+
+    Value::Block(ir::Block::CodeBlock(attr, dedent(&selected)))
+}
+
+/// Returns the language of a file, derived from its extension.
+fn language(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?;
+
+    let lang = LANGUAGES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map_or(extension, |(_, lang)| lang);
+
+    Some(lang.to_owned())
+}
+
+/// Selects a range of lines, e.g. `10-42`, `10-`, `-42` or `7`, starting at one.
+///
+/// # Returns
+/// The selected lines, or a message describing the invalid range
+fn select_lines<'a>(lines: &[&'a str], range: &str) -> Result<Vec<&'a str>, String> {
+    let invalid = || format!("Invalid line range '{range}', expected e.g. `10-42`, `10-` or `7`");
+    let number = |text: &str| text.trim().parse::<usize>().map_err(|_| invalid());
+
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => {
+            let start = if start.trim().is_empty() {
+                1
+            } else {
+                number(start)?
+            };
+            let end = if end.trim().is_empty() {
+                lines.len()
+            } else {
+                number(end)?
+            };
+            (start, end)
+        }
+        None => {
+            let line = number(range)?;
+            (line, line)
+        }
+    };
+
+    if start == 0 {
+        return Err(invalid());
+    }
+
+    // An open range starting after the last line, e.g. `10-`, is out of bounds too
+    if start > lines.len() || end > lines.len() {
+        return Err(format!(
+            "Line range '{range}' is out of bounds, the file has {} lines",
+            lines.len()
+        ));
+    }
+
+    if start > end {
+        return Err(invalid());
+    }
+
+    Ok(lines[start - 1..end].to_vec())
+}
+
+/// A line marking the start or end of a region.
+enum Marker<'a> {
+    /// The start of the region with the name
+    Start(&'a str),
+    /// The end of the innermost region
+    End,
+}
+
+/// Returns the region marker of a line, e.g. `// region: setup` or `# endregion`.
+///
+/// Markers are written in comments, so the comment syntax before them is ignored,
+/// as well as the end of block comments after them, e.g. `<!-- region: setup -->`.
+fn marker(line: &str) -> Option<Marker<'_>> {
+    let text = line
+        .trim()
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim_end_matches("-->")
+        .trim_end_matches("*/")
+        .trim_end();
+
+    if let Some(name) = text.strip_prefix("region:") {
+        return Some(Marker::Start(name.trim()));
+    }
+
+    (text == "endregion").then_some(Marker::End)
+}
+
+/// Selects the lines of the region with the name, without the lines of region markers.
+///
+/// # Arguments
+/// * `lines` - The lines of the file
+/// * `name` - The name of the region
+/// * `path` - The path of the file for error reporting
+/// * `span` - Source span for error reporting
+///
+/// # Returns
+/// The selected lines, or an error if the region is missing or not closed
+fn select_region<'a>(
+    lines: &[&'a str],
+    name: &str,
+    path: &Path,
+    span: Span,
+) -> Result<Vec<&'a str>, SourceDiagnostic> {
+    let file = path.display();
+
+    let Some(start) = lines
+        .iter()
+        .position(|line| matches!(marker(line), Some(Marker::Start(start)) if start == name))
+    else {
+        let message = format!("Region '{name}' not found in '{file}'");
+        let mut diag = SourceDiagnostic::error(span, message);
+
+        let regions: Vec<EcoString> = lines
+            .iter()
+            .filter_map(|line| match marker(line) {
+                Some(Marker::Start(name)) => Some(name.into()),
+                _ => None,
+            })
+            .collect();
+
+        if let Some(similar) = label::similar(name, &regions) {
+            diag.set_help(format!("Did you mean '{similar}'?"));
+        }
+
+        return Err(diag);
+    };
+
+    let mut depth = 0;
+    let mut selected = Vec::new();
+
+    for line in &lines[start + 1..] {
+        match marker(line) {
+            Some(Marker::Start(_)) => depth += 1,
+            Some(Marker::End) if depth == 0 => return Ok(selected),
+            Some(Marker::End) => depth -= 1,
+            None => selected.push(*line),
+        }
+    }
+
+    Err(SourceDiagnostic::error(
+        span,
+        format!("Region '{name}' in '{file}' is not closed by an `endregion` line"),
+    ))
+}
+
+/// Joins the lines, removing the indentation they have in common.
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &[&str] = &[
+        "fn main() {",
+        "    // region: setup",
+        "    let x = 1;",
+        "    /* region: inner */",
+        "    let y = 2;",
+        "    /* endregion */",
+        "    // endregion",
+        "}",
+    ];
+
+    fn region(name: &str) -> Result<Vec<&'static str>, SourceDiagnostic> {
+        let span = Source::new("main.tyd", "main.tyd", "").end_of_input();
+        select_region(FILE, name, Path::new("main.rs"), span)
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(select_lines(FILE, "1").unwrap(), ["fn main() {"]);
+        assert_eq!(select_lines(FILE, " 7 - 8 ").unwrap(), &FILE[6..]);
+        assert_eq!(select_lines(FILE, "7-").unwrap(), &FILE[6..]);
+        assert_eq!(select_lines(FILE, "-2").unwrap(), &FILE[..2]);
+        assert_eq!(select_lines(FILE, "-").unwrap(), FILE);
+    }
+
+    #[test]
+    fn invalid_lines() {
+        for range in ["0", "3-2", "a", "1-b", "1-2-3", "-0"] {
+            let message = select_lines(FILE, range).unwrap_err();
+            assert!(
+                message.starts_with("Invalid line range"),
+                "{range}: {message}"
+            );
+        }
+
+        for range in ["9", "2-9", "9-"] {
+            let message = select_lines(FILE, range).unwrap_err();
+            assert!(
+                message.contains("out of bounds, the file has 8 lines"),
+                "{range}"
+            );
+        }
+
+        assert!(select_lines(&[], "1-").is_err());
+    }
+
+    #[test]
+    fn regions() {
+        assert_eq!(
+            region("setup").unwrap(),
+            ["    let x = 1;", "    let y = 2;"]
+        );
+        assert_eq!(region("inner").unwrap(), ["    let y = 2;"]);
+        assert_eq!(dedent(&region("setup").unwrap()), "let x = 1;\nlet y = 2;");
+    }
+
+    #[test]
+    fn missing_regions() {
+        let diag = region("setpu").unwrap_err();
+        assert_eq!(diag.message, "Region 'setpu' not found in 'main.rs'");
+        assert_eq!(diag.help.as_deref(), Some("Did you mean 'setup'?"));
+
+        assert_eq!(region("other").unwrap_err().help, None);
+
+        let span = Source::new("main.tyd", "main.tyd", "").end_of_input();
+        let diag = select_region(&FILE[..5], "setup", Path::new("main.rs"), span).unwrap_err();
+        assert!(diag.message.contains("is not closed"));
+    }
+
+    #[test]
+    fn markers() {
+        assert!(matches!(
+            marker("# region: a b"),
+            Some(Marker::Start("a b"))
+        ));
+        assert!(matches!(
+            marker("<!-- region: html -->"),
+            Some(Marker::Start("html"))
+        ));
+        assert!(matches!(marker("  -- endregion"), Some(Marker::End)));
+        assert!(marker("let region = 1;").is_none());
+    }
+
+    #[test]
+    fn languages() {
+        assert_eq!(language(Path::new("a/main.rs")).as_deref(), Some("rust"));
+        assert_eq!(language(Path::new("main.go")).as_deref(), Some("go"));
+        assert_eq!(language(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn dedent_keeps_relative_indentation() {
+        assert_eq!(dedent(&["  a", "", "    b  "]), "a\n\n  b");
+        assert_eq!(dedent(&[]), "");
+    }
+}
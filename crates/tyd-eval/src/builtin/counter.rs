@@ -0,0 +1,117 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    counter::Numbering,
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'counter' function which steps and displays custom counters.
+///
+/// This struct is used as a type to represent the counter function.
+#[derive(Debug, Clone, Copy)]
+pub struct Counter;
+
+impl From<Counter> for Value {
+    /// Converts the Counter struct into a function Value.
+    fn from(_: Counter) -> Self {
+        let signature = Signature::new("Steps a counter and displays its value.", Type::Str)
+            .positional("name", Type::Str, "The name of the counter")
+            .named_or("level", Type::Int, 1, "The level of the counter to step")
+            .named_or(
+                "step",
+                Type::Bool,
+                true,
+                "Whether to step the counter before displaying it",
+            )
+            .named_or(
+                "value",
+                Type::Int,
+                Value::None,
+                "Sets the level to this value instead of stepping it",
+            )
+            .named_or(
+                "numbering",
+                Type::Str,
+                "",
+                "The numbering pattern, defaults to the numbering of the counter or `1`",
+            );
+
+        Value::Func(Func::new("counter", signature, counter))
+    }
+}
+
+/// Steps a counter and displays its value.
+///
+/// The builtin counters `heading`, `figure`, `table` and `equation` can be read as well,
+/// e.g. `counter("heading", step: false)` displays the number of the current heading.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `name` - The name of the counter
+///   * `level` - The level of the counter to step, defaults to 1
+///   * `step` - Whether to step the counter, defaults to true
+///   * `value` - (optional) Sets the level to this value instead of stepping it
+///   * `numbering` - The numbering pattern, defaults to the numbering of the counter
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting issues
+/// * `state` - Document state holding the counters
+///
+/// # Returns
+///
+/// A Value::Str with the displayed counter or Value::None if an error occurred.
+pub fn counter(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let name = args.take::<EcoString>("name");
+    let level = args.take::<i64>("level");
+    let step = args.take::<bool>("step");
+    let value = args.take::<Value>("value");
+    let pattern = args.take::<EcoString>("numbering");
+
+    let Ok(level) = usize::try_from(level) else {
+        tracer.source_error(span, "Level of counter must not be negative");
+        return Value::None;
+    };
+
+    let numbering = if pattern.is_empty() {
+        state
+            .counters
+            .numbering(&name)
+            .cloned()
+            .or_else(|| Numbering::parse("1"))
+    } else {
+        Numbering::parse(&pattern)
+    };
+
+    let Some(numbering) = numbering else {
+        tracer.source_error(span, format!("Invalid numbering pattern '{pattern}'"));
+        return Value::None;
+    };
+
+    match value {
+        Value::Int(value) => match usize::try_from(value) {
+            Ok(value) => state.counters.set(&name, level, value),
+            Err(_) => {
+                tracer.source_error(span, "Value of counter must not be negative");
+                return Value::None;
+            }
+        },
+        _ if step => {
+            state.counters.step(&name, level);
+        }
+        _ => {}
+    }
+
+    Value::Str(numbering.apply(state.counters.get(&name)))
+}
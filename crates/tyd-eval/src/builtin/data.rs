@@ -0,0 +1,195 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use super::read::read_file;
+use crate::{
+    data,
+    error::EngineError,
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'json' function which loads a JSON file.
+#[derive(Debug, Clone, Copy)]
+pub struct Json;
+
+/// Represents the 'csv' function which loads a CSV file.
+#[derive(Debug, Clone, Copy)]
+pub struct Csv;
+
+/// Represents the 'toml' function which loads a TOML file.
+#[derive(Debug, Clone, Copy)]
+pub struct Toml;
+
+/// Represents the 'yaml' function which loads a YAML file.
+#[derive(Debug, Clone, Copy)]
+pub struct Yaml;
+
+/// The documentation of the path parameter shared by all loaders
+const PATH_DOC: &str = "Path to the file, relative to the current document";
+
+impl From<Json> for Value {
+    /// Converts the Json struct into a function Value.
+    fn from(_: Json) -> Self {
+        let signature = Signature::new(
+            "Loads a JSON file as maps, lists, strings, numbers, booleans and `none`.",
+            Type::Any,
+        )
+        .positional("path", Type::Str, PATH_DOC);
+
+        Value::Func(Func::new("json", signature, json))
+    }
+}
+
+impl From<Csv> for Value {
+    /// Converts the Csv struct into a function Value.
+    fn from(_: Csv) -> Self {
+        let signature = Signature::new(
+            "Loads a CSV file as a list of rows, whose fields are strings.",
+            Type::list(Type::Any),
+        )
+        .positional("path", Type::Str, PATH_DOC)
+        .named_or(
+            "delimiter",
+            Type::Str,
+            ",",
+            "The character separating the fields of a row",
+        )
+        .named_or(
+            "header",
+            Type::Bool,
+            true,
+            "Whether the first row names the columns, rows are maps if so and lists otherwise",
+        );
+
+        Value::Func(Func::new("csv", signature, csv))
+    }
+}
+
+impl From<Toml> for Value {
+    /// Converts the Toml struct into a function Value.
+    fn from(_: Toml) -> Self {
+        let signature = Signature::new(
+            "Loads a TOML file as a map, with dates as datetimes.",
+            Type::Any,
+        )
+        .positional("path", Type::Str, PATH_DOC);
+
+        Value::Func(Func::new("toml", signature, toml))
+    }
+}
+
+impl From<Yaml> for Value {
+    /// Converts the Yaml struct into a function Value.
+    fn from(_: Yaml) -> Self {
+        let signature = Signature::new(
+            "Loads a YAML file as maps, lists, strings, numbers, booleans and `none`.",
+            Type::Any,
+        )
+        .positional("path", Type::Str, PATH_DOC);
+
+        Value::Func(Func::new("yaml", signature, yaml))
+    }
+}
+
+/// Reads a file relative to the current document and converts its content.
+///
+/// # Arguments
+/// * `path` - Path to the file, relative to the current document
+/// * `source` - Source information for resolving the path
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting unreadable or malformed files
+/// * `convert` - Converts the content of the file, or returns a message describing the error
+///
+/// # Returns
+/// The converted value, or Value::None if an error occurred
+fn load(
+    path: &str,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    convert: impl FnOnce(&str) -> Result<Value, String>,
+) -> Value {
+    let Some((path, text)) = read_file(path, source, span, tracer) else {
+        return Value::None;
+    };
+
+    match convert(&text) {
+        Ok(value) => value,
+        Err(message) => {
+            let error = EngineError::ParseFile {
+                path: path.display().to_string(),
+                message,
+            };
+            tracer.source_error(span, error);
+            Value::None
+        }
+    }
+}
+
+/// Loads a JSON file.
+pub fn json(
+    mut args: Args,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _: &mut State,
+) -> Value {
+    let path = args.take::<EcoString>("path");
+    load(&path, source, span, tracer, data::from_json)
+}
+
+/// Loads a CSV file, reporting an error if the delimiter is not a single ASCII character.
+pub fn csv(
+    mut args: Args,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _: &mut State,
+) -> Value {
+    let path = args.take::<EcoString>("path");
+    let delimiter = args.take::<EcoString>("delimiter");
+    let header = args.take::<bool>("header");
+
+    let delimiter = match delimiter.as_bytes() {
+        [byte] if byte.is_ascii() => *byte,
+        _ => {
+            tracer.source_error(
+                span,
+                format!("Delimiter '{delimiter}' must be a single ASCII character"),
+            );
+            return Value::None;
+        }
+    };
+
+    load(&path, source, span, tracer, |text| {
+        data::from_csv(text, delimiter, header)
+    })
+}
+
+/// Loads a TOML file.
+pub fn toml(
+    mut args: Args,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _: &mut State,
+) -> Value {
+    let path = args.take::<EcoString>("path");
+    load(&path, source, span, tracer, data::from_toml)
+}
+
+/// Loads a YAML file.
+pub fn yaml(
+    mut args: Args,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _: &mut State,
+) -> Value {
+    let path = args.take::<EcoString>("path");
+    load(&path, source, span, tracer, data::from_yaml)
+}
@@ -0,0 +1,110 @@
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    datetime,
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'datetime' function which creates dates and datetimes.
+///
+/// Dates are either parsed from ISO 8601 strings, e.g. `datetime("2024-05-01")`,
+/// or given by their components, e.g. `datetime(year: 2024, month: 5, day: 1)`.
+#[derive(Debug, Clone, Copy)]
+pub struct Datetime;
+
+impl From<Datetime> for Value {
+    /// Converts the Datetime struct into a function Value.
+    fn from(_: Datetime) -> Self {
+        let components = [
+            ("year", "The year"),
+            ("month", "The month, starting at one"),
+            ("day", "The day of the month, starting at one"),
+            (
+                "hour",
+                "The hour of the day, which makes the date a datetime",
+            ),
+            ("minute", "The minute of the hour"),
+            ("second", "The second of the minute"),
+        ];
+
+        let signature = components.into_iter().fold(
+            Signature::new(
+                "Creates a date, optionally with a time of day.",
+                Type::Datetime,
+            )
+            .positional_or(
+                "text",
+                Type::Str,
+                Value::None,
+                "An ISO 8601 date or datetime, e.g. `2024-05-01` or `2024-05-01T12:30:00`",
+            ),
+            |signature, (name, doc)| signature.named_or(name, Type::Int, Value::None, doc),
+        );
+
+        Value::Func(Func::new("datetime", signature, datetime))
+    }
+}
+
+/// Creates a date or datetime from an ISO 8601 string or from its components.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `text` - An ISO 8601 string, `none` to use the components
+///   * `year`, `month`, `day` - The date, required without a string
+///   * `hour`, `minute`, `second` - The time of day, which defaults to zero if any is given
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting invalid dates
+/// * `_state` - Document state (unused)
+///
+/// # Returns
+///
+/// * `Value::Datetime` containing the date, or `Value::None` if it is invalid
+pub fn datetime(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _state: &mut State,
+) -> Value {
+    let mut take = |name| match args.take::<Value>(name) {
+        Value::Int(i) => Some(i),
+        _ => None,
+    };
+
+    let (year, month, day) = (take("year"), take("month"), take("day"));
+    let (hour, minute, second) = (take("hour"), take("minute"), take("second"));
+    let has_time = hour.is_some() || minute.is_some() || second.is_some();
+
+    let result = match (args.take::<Value>("text"), year, month, day) {
+        (Value::Str(text), None, None, None) if !has_time => datetime::Datetime::parse(&text),
+        (Value::Str(_), ..) => {
+            Err("Expected either an ISO 8601 string or the components of a date".to_owned())
+        }
+        (_, Some(year), Some(month), Some(day)) => {
+            let time = has_time.then(|| {
+                (
+                    hour.unwrap_or_default(),
+                    minute.unwrap_or_default(),
+                    second.unwrap_or_default(),
+                )
+            });
+
+            datetime::Datetime::from_parts(year, month, day, time)
+        }
+        _ => Err("Expected an ISO 8601 string or a year, month and day".to_owned()),
+    };
+
+    match result {
+        Ok(datetime) => Value::Datetime(datetime),
+        Err(message) => {
+            tracer.source_error(span, message);
+            Value::None
+        }
+    }
+}
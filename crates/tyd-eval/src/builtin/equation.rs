@@ -0,0 +1,76 @@
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    counter,
+    func::Func,
+    ir,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents a displayed equation, which is numbered like figures and tables.
+///
+/// This struct is used as a type to represent the equation function.
+#[derive(Debug, Clone, Copy)]
+pub struct Equation;
+
+impl From<Equation> for Value {
+    /// Converts the Equation struct into a function Value.
+    fn from(_: Equation) -> Self {
+        let signature = Signature::new("Displays math as a numbered equation.", Type::Block)
+            .content("content", "The content containing the math of the equation");
+
+        Value::Func(Func::new("equation", signature, equation))
+    }
+}
+
+/// Displays the math of the content as a block equation.
+///
+/// Inline math in the content is turned into display math, and if equations are
+/// numbered, the number of the equation is placed after it.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `content` - The content containing the math of the equation
+/// * `_source` - Source information (unused)
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `state` - Document state, used to number the equation
+///
+/// # Returns
+///
+/// A Value::Block containing the equation.
+pub fn equation(
+    mut args: Args,
+    _source: Source,
+    _span: Span,
+    _tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let content = args.take::<ir::Content>("content");
+
+    let mut inlines: Vec<ir::Inline> = content
+        .into_iter()
+        .map(|inline| match inline {
+            ir::Inline::Math(_, math) => ir::Inline::Math(ir::MathType::DisplayMath, math),
+            other => other,
+        })
+        .collect();
+
+    state.counters.step(counter::EQUATION, 1);
+
+    if let Some(number) = state.counters.display(counter::EQUATION) {
+        let attr = ir::AttrBuilder::new().class("equation-number").build();
+
+        inlines.push(ir::Inline::Space);
+        inlines.push(ir::Inline::Span(
+            attr,
+            vec![ir::Inline::Str(number.to_string())],
+        ));
+    }
+
+    Value::Block(ir::Block::Para(inlines))
+}
@@ -1,11 +1,15 @@
+use ecow::EcoString;
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
+    counter,
+    func::Func,
     ir,
-    scope::Scope,
-    stack::Stack,
+    label::Target,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents a figure element that can contain content with a caption.
@@ -18,54 +22,77 @@ pub struct Figure;
 impl Into<Value> for Figure {
     /// Converts this Figure into a Value containing the figure function.
     fn into(self) -> Value {
-        Value::Func(figure)
+        let signature = Signature::new("Creates a figure with a caption.", Type::Block)
+            .content("content", "The content to display in the figure")
+            .named("caption", Type::Content, "The caption of the figure")
+            .named_or(
+                "label",
+                Type::Str,
+                "",
+                "A label to reference the figure with, e.g. `@label`",
+            );
+
+        Value::Func(Func::new("figure", signature, figure))
     }
 }
 
 /// Creates a figure block with the provided content and caption.
 ///
+/// If figures are numbered, the caption is prefixed with the number of the figure.
+///
 /// # Arguments
 ///
-/// * `stack` - Stack containing the figure's main content
-/// * `scope` - Scope containing named parameters like caption
+/// * `args` - The bound arguments:
+///   * `content` - The content to display in the figure
+///   * `caption` - The caption of the figure
+///   * `label` - A label to reference the figure with, empty for none
 /// * `_source` - Source information (unused)
-/// * `span` - Span information for error reporting
-/// * `tracer` - Error tracer for reporting issues
+/// * `span` - Source span of the label
+/// * `tracer` - Error tracer for reporting duplicate labels
+/// * `state` - Document state, used to number the figure
 ///
 /// # Returns
 ///
-/// * `Value::Block` containing the figure if successful
-/// * `Value::None` if required parameters are missing or have wrong types
-///
-/// # Expected Parameters
-///
-/// * First positional argument: The content to display in the figure (required)
-/// * `caption`: Named parameter for the figure caption (required)
+/// * `Value::Block` containing the figure
 pub fn figure(
-    mut stack: Stack,
-    mut scope: Scope,
+    mut args: Args,
     _source: Source,
     span: Span,
     tracer: &mut Tracer,
+    state: &mut State,
 ) -> Value {
-    let mut type_checker = TypeChecker::new(tracer, span);
+    let mut caption = args.take::<ir::Content>("caption");
+    let content = args.take::<ir::Content>("content");
+    let label = args.take::<EcoString>("label");
 
-    let caption = match type_checker.remove_from_scope::<ir::Content>(&mut scope, "caption") {
-        Some(c) => c,
-        None => return Value::None,
-    };
+    state.counters.step(counter::FIGURE, 1);
 
-    let content = match type_checker.pop_from_stack::<ir::Content>(&mut stack, 0) {
-        Some(c) => c,
-        None => return Value::None,
-    };
+    let number = state.counters.display(counter::FIGURE);
 
-    type_checker.warn_unknown_named(scope);
-    type_checker.warn_unknown_positional(stack, 1);
+    if !label.is_empty() {
+        let target = Target {
+            supplement: "Figure".into(),
+            number: number.clone(),
+            text: label.clone(),
+            span,
+        };
+        state.labels.define(label.clone(), target, tracer);
+    }
+
+    if let Some(number) = number {
+        let prefix = [
+            ir::Inline::Str("Figure".to_owned()),
+            ir::Inline::Space,
+            ir::Inline::Str(format!("{number}:")),
+            ir::Inline::Space,
+        ];
+        caption.splice(0..0, prefix);
+    }
 
     let caption = (None, vec![ir::Block::Plain(caption)]);
     let content = ir::Block::Plain(content);
-    let block = ir::Block::Figure(ir::AttrBuilder::empty(), caption, vec![content]);
+    let attr = ir::AttrBuilder::new().ident(label.to_string()).build();
+    let block = ir::Block::Figure(attr, caption, vec![content]);
 
     Value::Block(block)
 }
@@ -0,0 +1,73 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    glossary::Options,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'glossary' function which defines the terms of the following terms block.
+///
+/// This struct is used as a type to represent the glossary function.
+#[derive(Debug, Clone, Copy)]
+pub struct Glossary;
+
+impl From<Glossary> for Value {
+    /// Converts the Glossary struct into a function Value.
+    fn from(_: Glossary) -> Self {
+        let signature = Signature::new(
+            "Defines the terms of the following terms block and displays them as a glossary.",
+            Type::Block,
+        )
+        .named_or(
+            "title",
+            Type::Str,
+            "Glossary",
+            "The title of the glossary, empty for none",
+        )
+        .named_or(
+            "link",
+            Type::Bool,
+            true,
+            "Whether later occurrences of the terms are linked to their definition",
+        );
+
+        Value::Func(Func::new("glossary", signature, glossary))
+    }
+}
+
+/// Defines the terms of the following terms block and displays them as a glossary.
+///
+/// The terms are sorted alphabetically. A term like `API (Application Programming Interface)`
+/// is an abbreviation, which is expanded on its first use.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `title` - The title of the glossary, defaults to `Glossary`
+///   * `link` - Whether later occurrences of the terms are linked, defaults to true
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `_tracer` - Error tracer (unused)
+/// * `state` - Document state collecting the terms
+///
+/// # Returns
+///
+/// A Value::Block containing the glossary.
+pub fn glossary(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    _tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let title = args.take::<EcoString>("title");
+    let link = args.take::<bool>("link");
+
+    let options = Options { title, link };
+    Value::Block(state.glossary.placeholder(options, span))
+}
@@ -0,0 +1,62 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'gls' function which uses a term of a glossary.
+///
+/// This struct is used as a type to represent the gls function.
+#[derive(Debug, Clone, Copy)]
+pub struct Gls;
+
+impl From<Gls> for Value {
+    /// Converts the Gls struct into a function Value.
+    fn from(_: Gls) -> Self {
+        let signature = Signature::new(
+            "Uses a term of a glossary, linking to its definition.",
+            Type::Inline,
+        )
+        .positional(
+            "key",
+            Type::Str,
+            "The term, or the abbreviation of an abbreviation",
+        );
+
+        Value::Func(Func::new("gls", signature, gls))
+    }
+}
+
+/// Uses a term of a glossary, linking to its definition.
+///
+/// The first use of an abbreviation displays its long form, e.g.
+/// `Application Programming Interface (API)`, and later uses the abbreviation.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `key` - The term, or the abbreviation of an abbreviation
+/// * `_source` - Source information (unused)
+/// * `span` - Source span of the use
+/// * `_tracer` - Error tracer (unused)
+/// * `state` - Document state holding the glossary
+///
+/// # Returns
+///
+/// A Value::Inline containing the link to the term.
+pub fn gls(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    _tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let key = args.take::<EcoString>("key");
+
+    Value::Inline(state.glossary.reference(key, span))
+}
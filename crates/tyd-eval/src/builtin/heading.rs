@@ -0,0 +1,118 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    counter,
+    func::Func,
+    ir,
+    label::{self, Target},
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents a section heading, which is usually written as `= Heading` in markup.
+///
+/// The function is mainly used with set rules, e.g. `#set heading(numbering: "1.1")`,
+/// whose defaults also apply to headings written in markup.
+#[derive(Debug, Clone, Copy)]
+pub struct Heading;
+
+impl From<Heading> for Value {
+    /// Converts the Heading struct into a function Value.
+    fn from(_: Heading) -> Self {
+        let signature = Signature::new("Creates a section heading.", Type::Block)
+            .content("content", "The content of the heading")
+            .named_or(
+                "level",
+                Type::Int,
+                1,
+                "The level of the heading, starting at one",
+            )
+            .named_or(
+                "numbering",
+                Type::Str,
+                Value::None,
+                "The numbering pattern of the heading, e.g. `1.1`, an empty pattern for none",
+            )
+            .named_or(
+                "label",
+                Type::Str,
+                "",
+                "A label to reference the heading with, e.g. `@label`",
+            );
+
+        Value::Func(Func::new("heading", signature, heading))
+    }
+}
+
+/// Creates a section heading, numbered and listed in the outline like headings in markup.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `content` - The content of the heading
+///   * `level` - The level of the heading
+///   * `numbering` - The numbering pattern, `none` for the numbering of the heading counter
+///   * `label` - A label to reference the heading with, empty for none
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting invalid patterns and duplicate labels
+/// * `state` - Document state, used to number the heading
+///
+/// # Returns
+///
+/// * `Value::Block` containing the heading
+pub fn heading(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let content = args.take::<ir::Content>("content");
+    let level = args.take::<i64>("level").max(1) as usize;
+    let numbering = match args.take::<Value>("numbering") {
+        Value::Str(pattern) => Some(pattern),
+        _ => None,
+    };
+    let label = args.take::<EcoString>("label");
+
+    state.counters.step(counter::HEADING, level);
+
+    let number = state
+        .counters
+        .display_with(counter::HEADING, numbering.as_deref())
+        .unwrap_or_else(|message| {
+            tracer.source_error(span, message);
+            None
+        });
+
+    if !label.is_empty() {
+        let target = Target {
+            supplement: "Section".into(),
+            number: number.clone(),
+            text: label::plain_text(&content).trim().into(),
+            span,
+        };
+        state.labels.define(label.clone(), target, tracer);
+    }
+
+    let label = (!label.is_empty()).then_some(label.as_str());
+    let ident = state
+        .outline
+        .push(level, number.clone(), content.clone(), label);
+
+    let mut inlines = Vec::new();
+
+    if let Some(number) = number {
+        inlines.push(ir::Inline::Str(number.to_string()));
+        inlines.push(ir::Inline::Space);
+    }
+
+    inlines.extend(content);
+
+    let attr = ir::AttrBuilder::new().ident(ident).build();
+    Value::Block(ir::Block::Header(level as i64, attr, inlines))
+}
@@ -1,11 +1,12 @@
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
+    func::Func,
     ir,
-    scope::Scope,
-    stack::Stack,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents a function that highlights text by applying a "mark" class to it.
@@ -18,7 +19,10 @@ pub struct Highlight;
 impl Into<Value> for Highlight {
     /// Converts this highlighter into a Value containing the highlight function.
     fn into(self) -> Value {
-        Value::Func(highlight)
+        let signature = Signature::new("Highlights the given content.", Type::Inline)
+            .content("content", "The content to highlight");
+
+        Value::Func(Func::new("highlight", signature, highlight))
     }
 }
 
@@ -26,36 +30,25 @@ impl Into<Value> for Highlight {
 ///
 /// # Arguments
 ///
-/// * `stack` - Stack containing positional arguments, where the first position should be the content to highlight
-/// * `scope` - Scope containing named arguments (none are used by this function)
+/// * `args` - The bound arguments:
+///   * `content` - The content to highlight
 /// * `_source` - Source information (unused)
-/// * `span` - The span in the source document for error reporting
-/// * `tracer` - Error tracer for reporting type or argument errors
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
 /// * `Value::Inline` - An inline element wrapping the content with highlighting
-/// * `Value::None` - If required arguments are missing or of incorrect type
 pub fn highlight(
-    mut stack: Stack,
-    scope: Scope,
+    mut args: Args,
     _source: Source,
-    span: Span,
-    tracer: &mut Tracer,
+    _span: Span,
+    _tracer: &mut Tracer,
+    _state: &mut State,
 ) -> Value {
-    let mut type_checker = TypeChecker::new(tracer, span);
-
-    // Extract the content to highlight from the first positional argument
-    let content = match type_checker.pop_from_stack::<ir::Content>(&mut stack, 0) {
-        Some(content) => content,
-        None => return Value::None,
-    };
-
-    // Warn about any unused arguments
-    type_checker.warn_unknown_positional(stack, 1);
-    type_checker.warn_unknown_named(scope);
+    let content = args.take::<ir::Content>("content");
 
-    // Create an inline span with the "mark" class containing the content
     let inline = ir::Inline::Span(ir::AttrBuilder::new().class("mark").build(), content);
 
     Value::Inline(inline)
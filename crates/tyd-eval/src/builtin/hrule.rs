@@ -1,11 +1,12 @@
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
+    func::Func,
     ir,
-    scope::Scope,
-    stack::Stack,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents a horizontal rule element in a document.
@@ -22,38 +23,35 @@ impl Into<Value> for HorizontalRule {
     ///
     /// A `Value::Func` containing the `hrule` function.
     fn into(self) -> Value {
-        Value::Func(hrule)
+        let signature = Signature::new("Creates a horizontal rule.", Type::Block);
+
+        Value::Func(Func::new("hrule", signature, hrule))
     }
 }
 
 /// Creates a horizontal rule block.
 ///
 /// This function generates a horizontal rule element in the document.
-/// It takes no arguments and will generate warnings for any arguments provided.
+/// It takes no arguments, any arguments provided are reported as warnings.
 ///
 /// # Arguments
 ///
-/// * `stack` - Positional arguments (none expected)
-/// * `scope` - Named arguments (none expected)
+/// * `_args` - The bound arguments (none expected)
 /// * `_source` - Source information (unused)
-/// * `span` - The span in the source file for error reporting
-/// * `tracer` - For tracking and reporting any errors
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
 /// A `Value::Block` containing a horizontal rule block
 pub fn hrule(
-    stack: Stack,
-    scope: Scope,
+    _args: Args,
     _source: Source,
-    span: Span,
-    tracer: &mut Tracer,
+    _span: Span,
+    _tracer: &mut Tracer,
+    _state: &mut State,
 ) -> Value {
-    let mut checker = TypeChecker::new(tracer, span);
-
-    checker.warn_unknown_positional(stack, 0);
-    checker.warn_unknown_named(scope);
-
     let block = ir::Block::HorizontalRule;
 
     Value::Block(block)
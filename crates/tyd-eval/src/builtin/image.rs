@@ -4,11 +4,12 @@ use ecow::EcoString;
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
+    func::Func,
     ir,
-    scope::Scope,
-    stack::Stack,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents an image that can be embedded in a document.
@@ -20,7 +21,17 @@ pub struct Image;
 impl Into<Value> for Image {
     /// Converts the Image struct into a function Value.
     fn into(self) -> Value {
-        Value::Func(image)
+        let signature = Signature::new("Embeds an image.", Type::Inline)
+            .named(
+                "src",
+                Type::Str,
+                "Path to the image file, relative to the current document",
+            )
+            .named_or("alt", Type::Str, "", "Alternative text for the image")
+            .named_or("width", Type::Str, "auto", "Width of the image")
+            .named_or("height", Type::Str, "auto", "Height of the image");
+
+        Value::Func(Func::new("image", signature, image))
     }
 }
 
@@ -28,59 +39,30 @@ impl Into<Value> for Image {
 ///
 /// # Parameters
 ///
-/// * `stack` - Stack of positional arguments (not used by this function)
-/// * `scope` - Named parameters including:
-///   * `src` - (required) Path to the image file, relative to the current document
-///   * `alt` - (optional) Alternative text for the image, defaults to empty string
-///   * `width` - (optional) Width specification for the image, defaults to "auto"
-///   * `height` - (optional) Height specification for the image, defaults to "auto"
+/// * `args` - The bound arguments:
+///   * `src` - Path to the image file, relative to the current document
+///   * `alt` - Alternative text for the image, defaults to empty string
+///   * `width` - Width specification for the image, defaults to "auto"
+///   * `height` - Height specification for the image, defaults to "auto"
 /// * `source` - Source information for the current document being processed
 /// * `span` - Span in the source code where this function is called
 /// * `tracer` - Error tracer for reporting issues
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
 /// Returns a Value containing the inline image element or Value::None if an error occurred.
 pub fn image(
-    stack: Stack,
-    mut scope: Scope,
+    mut args: Args,
     source: Source,
     span: Span,
     tracer: &mut Tracer,
+    _state: &mut State,
 ) -> Value {
-    let mut checker = TypeChecker::new(tracer, span);
-
-    // Required 'src' parameter
-    let src = match checker.remove_from_scope::<EcoString>(&mut scope, "src") {
-        Some(src) => src,
-        None => return Value::None,
-    };
-
-    // Optional 'alt' parameter (with empty default)
-    let alt = match checker.remove_from_scope_or::<EcoString>(&mut scope, "alt", EcoString::new()) {
-        Some(alt) => alt,
-        None => return Value::None,
-    };
-
-    // Optional 'width' parameter (with "auto" default)
-    let width = match checker.remove_from_scope_or::<EcoString>(&mut scope, "width", "auto".into())
-    {
-        Some(width) => width,
-        None => return Value::None,
-    };
-
-    // Optional 'height' parameter (with "auto" default)
-    let height =
-        match checker.remove_from_scope_or::<EcoString>(&mut scope, "height", "auto".into()) {
-            Some(height) => height,
-            None => return Value::None,
-        };
-
-    // Warn about unknown positional arguments
-    checker.warn_unknown_positional(stack, 0);
-
-    // Warn about unknown named arguments
-    checker.warn_unknown_named(scope);
+    let src = args.take::<EcoString>("src");
+    let alt = args.take::<EcoString>("alt");
+    let width = args.take::<EcoString>("width");
+    let height = args.take::<EcoString>("height");
 
     // work_path is the parent path of the file which is compiled at the moment
     let path = source.work_path().join(src.as_str());
@@ -0,0 +1,66 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'index' function which marks a term for the index.
+///
+/// This struct is used as a type to represent the index function.
+#[derive(Debug, Clone, Copy)]
+pub struct Index;
+
+impl From<Index> for Value {
+    /// Converts the Index struct into a function Value.
+    fn from(_: Index) -> Self {
+        let signature = Signature::new(
+            "Marks the current position as an occurrence of a term in the index.",
+            Type::Inline,
+        )
+        .positional("term", Type::Str, "The term listed in the index")
+        .positional_or(
+            "subterm",
+            Type::Str,
+            "",
+            "A subterm listed below the term, empty for none",
+        );
+
+        Value::Func(Func::new("index", signature, index))
+    }
+}
+
+/// Marks the current position as an occurrence of a term in the index.
+///
+/// The marker is invisible, the index links to it.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `term` - The term listed in the index
+///   * `subterm` - A subterm listed below the term, defaults to none
+/// * `_source` - Source information (unused)
+/// * `_span` - Source span (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `state` - Document state collecting the marked terms
+///
+/// # Returns
+///
+/// A Value::Inline containing the anchor of the occurrence.
+pub fn index(
+    mut args: Args,
+    _source: Source,
+    _span: Span,
+    _tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let term = args.take::<EcoString>("term");
+    let subterm = args.take::<EcoString>("subterm");
+
+    let section = state.outline.current();
+    Value::Inline(state.index.mark(term, subterm, section))
+}
@@ -1,11 +1,12 @@
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
+    func::Func,
     ir,
-    scope::Scope,
-    stack::Stack,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents a line break in the document.
@@ -22,7 +23,9 @@ impl Into<Value> for LineBreak {
     ///
     /// A Value of the Func variant containing the linebreak function.
     fn into(self) -> Value {
-        Value::Func(linebreak)
+        let signature = Signature::new("Inserts a line break.", Type::Inline);
+
+        Value::Func(Func::new("linebreak", signature, linebreak))
     }
 }
 
@@ -33,26 +36,21 @@ impl Into<Value> for LineBreak {
 ///
 /// # Arguments
 ///
-/// * `stack` - Stack of positional arguments (expected to be empty)
-/// * `scope` - Scope of named arguments (expected to be empty)
+/// * `_args` - The bound arguments (none expected)
 /// * `_source` - Source reference (unused)
-/// * `span` - Span in the source code where this function was called
-/// * `tracer` - Tracer for error reporting
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
 /// A Value representing a line break in the intermediate representation.
 pub fn linebreak(
-    stack: Stack,
-    scope: Scope,
+    _args: Args,
     _source: Source,
-    span: Span,
-    tracer: &mut Tracer,
+    _span: Span,
+    _tracer: &mut Tracer,
+    _state: &mut State,
 ) -> Value {
-    let mut checker = TypeChecker::new(tracer, span);
-
-    checker.warn_unknown_positional(stack, 0);
-    checker.warn_unknown_named(scope);
-
     Value::Inline(ir::Inline::LineBreak)
 }
@@ -1,10 +1,11 @@
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
-    scope::Scope,
-    stack::Stack,
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents the 'list' function which creates a list from stack arguments.
@@ -20,28 +21,39 @@ impl Into<Value> for List {
     ///
     /// A Value::Func containing the list function implementation.
     fn into(self) -> Value {
-        Value::Func(list)
+        let signature = Signature::new(
+            "Creates a list of the given values.",
+            Type::list(Type::Any),
+        )
+        .rest("items", Type::Any, "The items of the list");
+
+        Value::Func(Func::new("List", signature, list))
     }
 }
 
 /// Creates a list containing all positional arguments.
 ///
 /// This function converts all positional arguments into a list value.
-/// Any named arguments will generate warnings as they are ignored.
+/// Any named arguments are reported as warnings as they are ignored.
 ///
 /// # Arguments
 ///
-/// * `stack` - Stack of positional arguments that will become list elements
-/// * `scope` - Scope containing named arguments (all will be warned as unknown)
+/// * `args` - The bound arguments:
+///   * `items` - All positional arguments, which become the list elements
 /// * `_source` - Source information (unused)
-/// * `span` - Source span for error reporting
-/// * `tracer` - Error tracer for reporting warnings
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
 /// A Value::List containing all the positional arguments
-pub fn list(stack: Stack, scope: Scope, _source: Source, span: Span, tracer: &mut Tracer) -> Value {
-    let mut checker = TypeChecker::new(tracer, span);
-    checker.warn_unknown_named(scope);
-    Value::List(stack.into_inner())
+pub fn list(
+    mut args: Args,
+    _source: Source,
+    _span: Span,
+    _tracer: &mut Tracer,
+    _state: &mut State,
+) -> Value {
+    Value::List(args.take("items"))
 }
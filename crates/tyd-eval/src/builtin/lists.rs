@@ -0,0 +1,621 @@
+use ecow::{eco_format, EcoString};
+use std::cmp::Ordering;
+use tyd_syntax::{source::Source, Span};
+
+use super::module_func;
+use crate::{
+    error::TypeError,
+    func::{Caller, Func},
+    ir,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'list' module, a map of functions working with lists.
+///
+/// Lists are never modified in place, functions like `list.push` return a new list.
+/// Functions like `list.map` take a function or macro, which is called with the items.
+#[derive(Debug, Clone, Copy)]
+pub struct Lists;
+
+impl From<Lists> for Value {
+    /// Converts the Lists struct into a map of function Values.
+    fn from(_: Lists) -> Self {
+        let list = || Type::list(Type::Any);
+        let doc = "The list";
+
+        let functions = [
+            module_func(
+                "list",
+                "len",
+                Signature::new("Returns the number of items of a list.", Type::Int).positional(
+                    "list",
+                    list(),
+                    doc,
+                ),
+                len,
+            ),
+            module_func(
+                "list",
+                "push",
+                Signature::new("Returns the list with values appended.", list())
+                    .positional("list", list(), doc)
+                    .rest("values", Type::Any, "The appended values"),
+                push,
+            ),
+            module_func(
+                "list",
+                "concat",
+                Signature::new("Concatenates lists.", list()).rest(
+                    "lists",
+                    list(),
+                    "The concatenated lists",
+                ),
+                concat,
+            ),
+            module_func(
+                "list",
+                "reverse",
+                Signature::new("Returns the items of a list in reverse order.", list()).positional(
+                    "list",
+                    list(),
+                    doc,
+                ),
+                reverse,
+            ),
+            module_func(
+                "list",
+                "sort",
+                Signature::new(
+                    "Sorts the numbers, strings or booleans of a list in ascending order.",
+                    list(),
+                )
+                .positional("list", list(), doc),
+                sort,
+            ),
+            higher(
+                "sort_by",
+                Signature::new("Sorts a list by the keys computed for its items.", list())
+                    .positional("list", list(), doc)
+                    .positional(
+                        "key",
+                        Type::Func,
+                        "The function computing the key of an item",
+                    ),
+                sort_by,
+            ),
+            higher(
+                "map",
+                Signature::new("Applies a function to each item of a list.", list())
+                    .positional("list", list(), doc)
+                    .positional("f", Type::Func, "The function applied to an item"),
+                map,
+            ),
+            higher(
+                "filter",
+                Signature::new("Keeps the items of a list matching a predicate.", list())
+                    .positional("list", list(), doc)
+                    .positional(
+                        "predicate",
+                        Type::Func,
+                        "The function returning whether an item is kept",
+                    ),
+                filter,
+            ),
+            higher(
+                "fold",
+                Signature::new(
+                    "Combines the items of a list into a single value.",
+                    Type::Any,
+                )
+                .positional("list", list(), doc)
+                .positional("init", Type::Any, "The initial value")
+                .positional(
+                    "f",
+                    Type::Func,
+                    "The function combining the value so far with an item",
+                ),
+                fold,
+            ),
+            module_func(
+                "list",
+                "join",
+                Signature::new("Displays the items of a list as content.", Type::Content)
+                    .positional("list", list(), doc)
+                    .positional_or(
+                        "separator",
+                        Type::Any,
+                        "",
+                        "The string or content between two items",
+                    ),
+                join,
+            ),
+            module_func(
+                "list",
+                "zip",
+                Signature::new(
+                    "Pairs the items of two lists, up to the end of the shorter one.",
+                    list(),
+                )
+                .positional("list", list(), doc)
+                .positional("other", list(), "The list paired with the first one"),
+                zip,
+            ),
+            module_func(
+                "list",
+                "enumerate",
+                Signature::new("Pairs the items of a list with their index.", list())
+                    .positional("list", list(), doc)
+                    .named_or("start", Type::Int, 0, "The index of the first item"),
+                enumerate,
+            ),
+            module_func(
+                "list",
+                "first",
+                Signature::new("Returns the first item of a list.", Type::Any).positional(
+                    "list",
+                    list(),
+                    doc,
+                ),
+                first,
+            ),
+            module_func(
+                "list",
+                "last",
+                Signature::new("Returns the last item of a list.", Type::Any).positional(
+                    "list",
+                    list(),
+                    doc,
+                ),
+                last,
+            ),
+            module_func(
+                "list",
+                "contains",
+                Signature::new("Returns whether a list contains a value.", Type::Bool)
+                    .positional("list", list(), doc)
+                    .positional("value", Type::Any, "The value to search for"),
+                contains,
+            ),
+        ];
+
+        Value::Map(functions.into_iter().collect())
+    }
+}
+
+/// Creates a higher-order function of the module, named `list.<name>`.
+fn higher(
+    name: &str,
+    signature: Signature,
+    func: fn(Args, Span, &mut dyn Caller) -> Value,
+) -> (EcoString, Value) {
+    let value = Value::Func(Func::higher(eco_format!("list.{name}"), signature, func));
+    (name.into(), value)
+}
+
+/// Compares two values, which are numbers, strings, booleans, dates or lists of them.
+///
+/// # Returns
+/// The ordering of the values, or None if they cannot be compared
+pub(crate) fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+        (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+        (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Datetime(a), Value::Datetime(b)) => Some(a.cmp(b)),
+        (Value::List(a), Value::List(b)) => {
+            for (a, b) in a.iter().zip(b) {
+                match compare(a, b)? {
+                    Ordering::Equal => continue,
+                    ordering => return Some(ordering),
+                }
+            }
+
+            Some(a.len().cmp(&b.len()))
+        }
+        _ => None,
+    }
+}
+
+/// Sorts values by their keys, reporting the first pair of keys which cannot be compared.
+fn sort_keyed(mut pairs: Vec<(Value, Value)>, span: Span, tracer: &mut Tracer) -> Value {
+    let mut incomparable = None;
+
+    pairs.sort_by(|(a, _), (b, _)| {
+        compare(a, b).unwrap_or_else(|| {
+            incomparable.get_or_insert((a.ty(), b.ty()));
+            Ordering::Equal
+        })
+    });
+
+    if let Some((a, b)) = incomparable {
+        tracer.source_error(span, format!("Cannot compare values of type {a} and {b}"));
+        return Value::None;
+    }
+
+    Value::List(pairs.into_iter().map(|(_, item)| item).collect())
+}
+
+/// Returns the number of items of a list.
+fn len(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    Value::Int(args.take::<ir::List>("list").len() as i64)
+}
+
+/// Returns the list with the values appended.
+fn push(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let mut list = args.take::<ir::List>("list");
+    list.extend(args.take::<ir::List>("values"));
+    Value::List(list)
+}
+
+/// Concatenates the lists.
+fn concat(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let lists = args.take::<ir::List>("lists");
+
+    Value::List(
+        lists
+            .into_iter()
+            .flat_map(|list| match list {
+                Value::List(list) => list,
+                _ => Vec::new(),
+            })
+            .collect(),
+    )
+}
+
+/// Returns the items of a list in reverse order.
+fn reverse(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let mut list = args.take::<ir::List>("list");
+    list.reverse();
+    Value::List(list)
+}
+
+/// Sorts the items of a list in ascending order.
+fn sort(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let list = args.take::<ir::List>("list");
+    let pairs = list.into_iter().map(|item| (item.clone(), item)).collect();
+    sort_keyed(pairs, span, tracer)
+}
+
+/// Sorts the items of a list by the keys computed for them.
+fn sort_by(mut args: Args, span: Span, caller: &mut dyn Caller) -> Value {
+    let list = args.take::<ir::List>("list");
+    let key = args.take::<Value>("key");
+
+    let pairs = list
+        .into_iter()
+        .map(|item| (caller.call(&key, vec![item.clone()], span), item))
+        .collect();
+
+    let (_, tracer, _) = caller.context();
+    sort_keyed(pairs, span, tracer)
+}
+
+/// Applies a function to each item of a list.
+fn map(mut args: Args, span: Span, caller: &mut dyn Caller) -> Value {
+    let list = args.take::<ir::List>("list");
+    let f = args.take::<Value>("f");
+
+    Value::List(
+        list.into_iter()
+            .map(|item| caller.call(&f, vec![item], span))
+            .collect(),
+    )
+}
+
+/// Keeps the items of a list for which the predicate returns true.
+fn filter(mut args: Args, span: Span, caller: &mut dyn Caller) -> Value {
+    let list = args.take::<ir::List>("list");
+    let predicate = args.take::<Value>("predicate");
+    let mut kept = Vec::new();
+
+    for item in list {
+        match caller.call(&predicate, vec![item.clone()], span) {
+            Value::Bool(true) => kept.push(item),
+            Value::Bool(false) => {}
+            other => {
+                let (_, tracer, _) = caller.context();
+                let error = TypeError::WrongType {
+                    got: other.ty(),
+                    expected: Type::Bool,
+                };
+                tracer.source_error(span, error);
+                return Value::None;
+            }
+        }
+    }
+
+    Value::List(kept)
+}
+
+/// Combines the items of a list, starting with the initial value.
+fn fold(mut args: Args, span: Span, caller: &mut dyn Caller) -> Value {
+    let list = args.take::<ir::List>("list");
+    let init = args.take::<Value>("init");
+    let f = args.take::<Value>("f");
+
+    list.into_iter()
+        .fold(init, |acc, item| caller.call(&f, vec![acc, item], span))
+}
+
+/// Displays the items of a list as content, separated by the separator.
+fn join(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let list = args.take::<ir::List>("list");
+    let separator = args.take::<Value>("separator").into_content();
+    let mut content = Vec::new();
+
+    for (i, item) in list.into_iter().enumerate() {
+        if i > 0 {
+            content.extend(separator.iter().cloned());
+        }
+
+        content.extend(item.into_content());
+    }
+
+    Value::Content(content)
+}
+
+/// Pairs the items of two lists.
+fn zip(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let list = args.take::<ir::List>("list");
+    let other = args.take::<ir::List>("other");
+
+    Value::List(
+        list.into_iter()
+            .zip(other)
+            .map(|(a, b)| Value::List(vec![a, b]))
+            .collect(),
+    )
+}
+
+/// Pairs the items of a list with their index.
+fn enumerate(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let list = args.take::<ir::List>("list");
+    let start = args.take::<i64>("start");
+
+    Value::List(
+        (start..)
+            .zip(list)
+            .map(|(i, item)| Value::List(vec![Value::Int(i), item]))
+            .collect(),
+    )
+}
+
+/// Returns the first item of a list, reporting an error if it is empty.
+fn first(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let list = args.take::<ir::List>("list");
+
+    list.into_iter().next().unwrap_or_else(|| {
+        tracer.source_error(span, "List is empty");
+        Value::None
+    })
+}
+
+/// Returns the last item of a list, reporting an error if it is empty.
+fn last(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let list = args.take::<ir::List>("list");
+
+    list.into_iter().next_back().unwrap_or_else(|| {
+        tracer.source_error(span, "List is empty");
+        Value::None
+    })
+}
+
+/// Returns whether a list contains a value.
+fn contains(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let list = args.take::<ir::List>("list");
+    let value = args.take::<Value>("value");
+
+    Value::Bool(
+        list.iter()
+            .any(|item| compare(item, &value) == Some(Ordering::Equal)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::tests::{call, field, TestCaller};
+
+    fn list_call(name: &str, args: Vec<Value>) -> (Value, bool) {
+        call(field(Lists, name), args)
+    }
+
+    fn ints(values: &[i64]) -> Value {
+        Value::List(values.iter().map(|i| Value::Int(*i)).collect())
+    }
+
+    /// Returns the integers of a list, panicking for other values.
+    fn items(value: Value) -> Vec<i64> {
+        match value {
+            Value::List(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    Value::Int(i) => i,
+                    other => panic!("{other:?} is not an integer"),
+                })
+                .collect(),
+            other => panic!("{other:?} is not a list"),
+        }
+    }
+
+    /// Creates a function taking an integer, e.g. as callback of `list.map`.
+    fn unary(ty: Type, f: fn(Args, Source, Span, &mut Tracer, &mut State) -> Value) -> Value {
+        let signature = Signature::new("Test callback", ty).positional("x", Type::Int, "The item");
+        Value::Func(Func::new("callback", signature, f))
+    }
+
+    fn modulo() -> Value {
+        unary(Type::Int, |mut args, _, _, _, _| {
+            Value::Int(args.take::<i64>("x").rem_euclid(3))
+        })
+    }
+
+    fn even() -> Value {
+        unary(Type::Bool, |mut args, _, _, _, _| {
+            Value::Bool(args.take::<i64>("x") % 2 == 0)
+        })
+    }
+
+    fn sum() -> Value {
+        let signature = Signature::new("Test callback", Type::Int)
+            .positional("acc", Type::Int, "The sum so far")
+            .positional("x", Type::Int, "The item");
+
+        Value::Func(Func::new("callback", signature, |mut args, _, _, _, _| {
+            Value::Int(args.take::<i64>("acc") + args.take::<i64>("x"))
+        }))
+    }
+
+    #[test]
+    fn basics() {
+        assert!(matches!(
+            list_call("len", vec![ints(&[1, 2])]).0,
+            Value::Int(2)
+        ));
+        assert_eq!(
+            items(list_call("push", vec![ints(&[1]), Value::Int(2), Value::Int(3)]).0),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            items(list_call("concat", vec![ints(&[1]), ints(&[]), ints(&[2])]).0),
+            [1, 2]
+        );
+        assert_eq!(items(list_call("reverse", vec![ints(&[1, 2])]).0), [2, 1]);
+        assert!(matches!(
+            list_call("contains", vec![ints(&[1, 2]), Value::Float(2.0)]).0,
+            Value::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn first_and_last_of_empty_list() {
+        assert!(matches!(
+            list_call("first", vec![ints(&[4, 5])]).0,
+            Value::Int(4)
+        ));
+        assert!(matches!(
+            list_call("last", vec![ints(&[4, 5])]).0,
+            Value::Int(5)
+        ));
+
+        for name in ["first", "last"] {
+            let (value, failed) = list_call(name, vec![ints(&[])]);
+            assert!(failed);
+            assert!(matches!(value, Value::None));
+        }
+    }
+
+    #[test]
+    fn sort() {
+        assert_eq!(
+            items(list_call("sort", vec![ints(&[3, 1, 2])]).0),
+            [1, 2, 3]
+        );
+
+        let mixed = Value::List(vec![Value::Int(1), Value::Str("a".into())]);
+        let (value, failed) = list_call("sort", vec![mixed]);
+        assert!(failed);
+        assert!(matches!(value, Value::None));
+    }
+
+    #[test]
+    fn compare_values() {
+        let float = |f| Value::Float(f);
+
+        assert_eq!(compare(&Value::Int(1), &float(1.5)), Some(Ordering::Less));
+        assert_eq!(compare(&float(f64::NAN), &float(1.0)), None);
+        assert_eq!(
+            compare(&ints(&[1, 2]), &ints(&[1])),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare(&ints(&[1, 2]), &ints(&[2])), Some(Ordering::Less));
+        assert_eq!(compare(&Value::Bool(true), &Value::Int(1)), None);
+    }
+
+    #[test]
+    fn higher_order_functions() {
+        let mut caller = TestCaller::new();
+        let list = ints(&[5, 3, 4, 6]);
+
+        let mapped = caller.call_with(
+            &field(Lists, "map"),
+            vec![list.clone(), modulo()],
+            Vec::new(),
+        );
+        assert_eq!(items(mapped), [2, 0, 1, 0]);
+
+        let sorted = caller.call_with(
+            &field(Lists, "sort_by"),
+            vec![list.clone(), modulo()],
+            Vec::new(),
+        );
+        assert_eq!(items(sorted), [3, 6, 4, 5]);
+
+        let kept = caller.call_with(
+            &field(Lists, "filter"),
+            vec![list.clone(), even()],
+            Vec::new(),
+        );
+        assert_eq!(items(kept), [4, 6]);
+
+        let sum = caller.call_with(
+            &field(Lists, "fold"),
+            vec![list, Value::Int(0), sum()],
+            Vec::new(),
+        );
+        assert!(matches!(sum, Value::Int(18)));
+        assert!(!caller.failed());
+    }
+
+    #[test]
+    fn filter_requires_booleans() {
+        let mut caller = TestCaller::new();
+
+        let value = caller.call_with(
+            &field(Lists, "filter"),
+            vec![ints(&[1]), modulo()],
+            Vec::new(),
+        );
+
+        assert!(caller.failed());
+        assert!(matches!(value, Value::None));
+    }
+
+    #[test]
+    fn zip_and_enumerate() {
+        let (zipped, _) = list_call("zip", vec![ints(&[1, 2, 3]), ints(&[4, 5])]);
+        let Value::List(pairs) = zipped else {
+            panic!("{zipped:?} is not a list");
+        };
+        assert_eq!(items(pairs[1].clone()), [2, 5]);
+        assert_eq!(pairs.len(), 2);
+
+        let mut caller = TestCaller::new();
+        let enumerated = caller.call_with(
+            &field(Lists, "enumerate"),
+            vec![ints(&[7, 8])],
+            vec![("start", Value::Int(1))],
+        );
+        let Value::List(pairs) = enumerated else {
+            panic!("{enumerated:?} is not a list");
+        };
+        assert_eq!(items(pairs[1].clone()), [2, 8]);
+    }
+
+    #[test]
+    fn join() {
+        let list = Value::List(vec!["a".into(), "b".into()]);
+        let (value, _) = list_call("join", vec![list, ", ".into()]);
+
+        let Value::Content(content) = value else {
+            panic!("{value:?} is not content");
+        };
+        assert_eq!(crate::label::plain_text(&content), "a, b");
+    }
+}
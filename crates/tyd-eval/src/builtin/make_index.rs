@@ -0,0 +1,64 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    index::Options,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'make_index' function which creates the index of the document.
+///
+/// This struct is used as a type to represent the make_index function.
+#[derive(Debug, Clone, Copy)]
+pub struct MakeIndex;
+
+impl From<MakeIndex> for Value {
+    /// Converts the MakeIndex struct into a function Value.
+    fn from(_: MakeIndex) -> Self {
+        let signature = Signature::new(
+            "Creates an index listing the terms marked with `index`.",
+            Type::Block,
+        )
+        .named_or(
+            "title",
+            Type::Str,
+            "Index",
+            "The title of the index, empty for none",
+        );
+
+        Value::Func(Func::new("make_index", signature, make_index))
+    }
+}
+
+/// Creates an index listing the terms marked with `index`.
+///
+/// The terms are alphabetized and grouped by their initial, subterms are listed below their term.
+/// Each term links to its occurrences, displaying their section, or their page in PDF output.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `title` - The title of the index, defaults to `Index`
+/// * `_source` - Source information (unused)
+/// * `_span` - Source span (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `state` - Document state collecting the marked terms
+///
+/// # Returns
+///
+/// A Value::Block containing the index.
+pub fn make_index(
+    mut args: Args,
+    _source: Source,
+    _span: Span,
+    _tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let title = args.take::<EcoString>("title");
+
+    Value::Block(state.index.placeholder(Options { title }))
+}
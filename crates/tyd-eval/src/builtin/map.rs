@@ -1,10 +1,11 @@
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
-    scope::Scope,
-    stack::Stack,
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents a Map constructor function.
@@ -17,29 +18,36 @@ pub struct Map;
 impl Into<Value> for Map {
     /// Converts this Map constructor into a Value::Func
     fn into(self) -> Value {
-        Value::Func(map)
+        let signature = Signature::new("Creates a map of the given named values.", Type::Any)
+            .named_rest("entries", Type::Any, "The entries of the map");
+
+        Value::Func(Func::new("Map", signature, map))
     }
 }
 
 /// Creates a map value from named arguments.
 ///
-/// This function takes all named arguments from the scope and converts them
-/// into a map value. Any positional arguments will generate warnings.
+/// This function takes all named arguments and converts them
+/// into a map value. Any positional arguments are reported as warnings.
 ///
 /// # Arguments
 ///
-/// * `stack` - Positional arguments (not used, will generate warnings)
-/// * `scope` - Named arguments that will become map key-value pairs
-/// * `_source` - Source information (not used)
-/// * `span` - Span for error reporting
-/// * `tracer` - Error tracer for reporting warnings
+/// * `args` - The bound arguments:
+///   * `entries` - All named arguments, which become the map key-value pairs
+/// * `_source` - Source information (unused)
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
-/// A Value::Map containing all the named arguments from the scope
-pub fn map(stack: Stack, scope: Scope, _source: Source, span: Span, tracer: &mut Tracer) -> Value {
-    let mut checker = TypeChecker::new(tracer, span);
-    checker.warn_unknown_positional(stack, 0);
-
-    Value::Map(scope.into_inner())
+/// A Value::Map containing all the named arguments
+pub fn map(
+    mut args: Args,
+    _source: Source,
+    _span: Span,
+    _tracer: &mut Tracer,
+    _state: &mut State,
+) -> Value {
+    Value::Map(args.take("entries"))
 }
@@ -0,0 +1,308 @@
+use ecow::EcoString;
+use tyd_syntax::{error::SourceDiagnostic, source::Source, Span};
+
+use super::module_func;
+use crate::{
+    error::TypeError,
+    ir, label,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'map' module, a map of functions working with maps.
+///
+/// Maps are never modified in place, functions like `map.insert` return a new map.
+#[derive(Debug, Clone, Copy)]
+pub struct Maps;
+
+impl From<Maps> for Value {
+    /// Converts the Maps struct into a map of function Values.
+    fn from(_: Maps) -> Self {
+        let doc = "The map";
+
+        let functions = [
+            module_func(
+                "map",
+                "len",
+                Signature::new("Returns the number of entries of a map.", Type::Int).positional(
+                    "map",
+                    Type::Any,
+                    doc,
+                ),
+                len,
+            ),
+            module_func(
+                "map",
+                "keys",
+                Signature::new(
+                    "Returns the keys of a map in sorted order, which is the order of iteration.",
+                    Type::list(Type::Str),
+                )
+                .positional("map", Type::Any, doc),
+                keys,
+            ),
+            module_func(
+                "map",
+                "values",
+                Signature::new("Returns the values of a map.", Type::list(Type::Any)).positional(
+                    "map",
+                    Type::Any,
+                    doc,
+                ),
+                values,
+            ),
+            module_func(
+                "map",
+                "pairs",
+                Signature::new(
+                    "Returns the entries of a map as `List(key, value)` pairs.",
+                    Type::list(Type::Any),
+                )
+                .positional("map", Type::Any, doc),
+                pairs,
+            ),
+            module_func(
+                "map",
+                "get",
+                Signature::new("Returns the value of a key.", Type::Any)
+                    .positional("map", Type::Any, doc)
+                    .positional("key", Type::Str, "The key")
+                    .positional_or(
+                        "default",
+                        Type::Any,
+                        Value::None,
+                        "The value of a missing key, an error if not given",
+                    ),
+                get,
+            ),
+            module_func(
+                "map",
+                "insert",
+                Signature::new(
+                    "Returns the map with an entry added or replaced.",
+                    Type::Any,
+                )
+                .positional("map", Type::Any, doc)
+                .positional("key", Type::Str, "The key")
+                .positional("value", Type::Any, "The value"),
+                insert,
+            ),
+            module_func(
+                "map",
+                "remove",
+                Signature::new("Returns the map without an entry.", Type::Any)
+                    .positional("map", Type::Any, doc)
+                    .positional("key", Type::Str, "The key of the removed entry"),
+                remove,
+            ),
+            module_func(
+                "map",
+                "contains",
+                Signature::new("Returns whether a map contains a key.", Type::Bool)
+                    .positional("map", Type::Any, doc)
+                    .positional("key", Type::Str, "The key"),
+                contains,
+            ),
+        ];
+
+        Value::Map(functions.into_iter().collect())
+    }
+}
+
+/// Takes the map argument, reporting an error if the value is not a map.
+///
+/// Maps are declared as `Type::Any`, since map types compare their fields.
+fn take_map(args: &mut Args, span: Span, tracer: &mut Tracer) -> Option<ir::Map> {
+    match args.take::<Value>("map") {
+        Value::Map(map) => Some(map),
+        other => {
+            let error = TypeError::WrongType {
+                got: other.ty(),
+                expected: Type::Map(Vec::new()),
+            };
+            tracer.source_error(span, error);
+            None
+        }
+    }
+}
+
+/// Returns the number of entries of a map.
+fn len(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_map(&mut args, span, tracer) {
+        Some(map) => Value::Int(map.len() as i64),
+        None => Value::None,
+    }
+}
+
+/// Returns the keys of a map in sorted order.
+fn keys(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_map(&mut args, span, tracer) {
+        Some(map) => Value::List(map.into_keys().map(Value::Str).collect()),
+        None => Value::None,
+    }
+}
+
+/// Returns the values of a map, sorted by their keys.
+fn values(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_map(&mut args, span, tracer) {
+        Some(map) => Value::List(map.into_values().collect()),
+        None => Value::None,
+    }
+}
+
+/// Returns the entries of a map as pairs, sorted by their keys.
+fn pairs(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_map(&mut args, span, tracer) {
+        Some(map) => Value::List(
+            map.into_iter()
+                .map(|(key, value)| Value::List(vec![Value::Str(key), value]))
+                .collect(),
+        ),
+        None => Value::None,
+    }
+}
+
+/// Returns the value of a key, or the default if the key is missing.
+fn get(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let Some(mut map) = take_map(&mut args, span, tracer) else {
+        return Value::None;
+    };
+    let key = args.take::<EcoString>("key");
+    let default = args.take::<Value>("default");
+
+    match map.remove(&key) {
+        Some(value) => value,
+        None if !matches!(default, Value::None) => default,
+        None => {
+            let mut diag = SourceDiagnostic::error(span, format!("Map has no key '{key}'"));
+
+            if let Some(similar) = label::similar(&key, map.keys()) {
+                diag.set_help(format!("Did you mean '{similar}'?"));
+            }
+
+            tracer.diagnose_source(diag);
+            Value::None
+        }
+    }
+}
+
+/// Returns the map with the entry added, replacing the value of an existing key.
+fn insert(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let Some(mut map) = take_map(&mut args, span, tracer) else {
+        return Value::None;
+    };
+
+    map.insert(args.take("key"), args.take("value"));
+    Value::Map(map)
+}
+
+/// Returns the map without the entry of a key.
+fn remove(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let Some(mut map) = take_map(&mut args, span, tracer) else {
+        return Value::None;
+    };
+
+    map.remove(&args.take::<EcoString>("key"));
+    Value::Map(map)
+}
+
+/// Returns whether a map contains a key.
+fn contains(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    match take_map(&mut args, span, tracer) {
+        Some(map) => Value::Bool(map.contains_key(&args.take::<EcoString>("key"))),
+        None => Value::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::tests::{call, field, TestCaller};
+
+    fn map_call(name: &str, args: Vec<Value>) -> (Value, bool) {
+        call(field(Maps, name), args)
+    }
+
+    fn map() -> Value {
+        let mut map = ir::Map::new();
+        map.insert("b".into(), Value::Int(2));
+        map.insert("a".into(), Value::Int(1));
+        Value::Map(map)
+    }
+
+    fn keys(value: Value) -> Vec<EcoString> {
+        match value {
+            Value::Map(map) => map.into_keys().collect(),
+            Value::List(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    Value::Str(s) => s,
+                    other => panic!("{other:?} is not a string"),
+                })
+                .collect(),
+            other => panic!("{other:?} is neither a map nor a list"),
+        }
+    }
+
+    #[test]
+    fn entries() {
+        assert!(matches!(map_call("len", vec![map()]).0, Value::Int(2)));
+        assert_eq!(keys(map_call("keys", vec![map()]).0), ["a", "b"]);
+        assert!(matches!(
+            map_call("values", vec![map()]).0,
+            Value::List(values) if matches!(values[..], [Value::Int(1), Value::Int(2)])
+        ));
+        assert!(matches!(
+            map_call("pairs", vec![map()]).0,
+            Value::List(pairs) if pairs.len() == 2
+        ));
+        assert!(matches!(
+            map_call("contains", vec![map(), "a".into()]).0,
+            Value::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn maps_are_not_modified() {
+        let (inserted, _) = map_call("insert", vec![map(), "c".into(), Value::Int(3)]);
+        assert_eq!(keys(inserted), ["a", "b", "c"]);
+
+        let (removed, _) = map_call("remove", vec![map(), "a".into()]);
+        assert_eq!(keys(removed), ["b"]);
+
+        let (removed, failed) = map_call("remove", vec![map(), "missing".into()]);
+        assert!(!failed);
+        assert_eq!(keys(removed), ["a", "b"]);
+    }
+
+    #[test]
+    fn get() {
+        assert!(matches!(
+            map_call("get", vec![map(), "b".into()]).0,
+            Value::Int(2)
+        ));
+        assert!(matches!(
+            map_call("get", vec![map(), "c".into(), Value::Int(0)]).0,
+            Value::Int(0)
+        ));
+
+        let mut caller = TestCaller::new();
+        let value = caller.call_with(&field(Maps, "get"), vec![map(), "bb".into()], Vec::new());
+        assert!(matches!(value, Value::None));
+
+        let (diags, _) = caller.tracer.into_inner();
+        assert_eq!(diags[0].message, "Map has no key 'bb'");
+        assert_eq!(diags[0].help.as_deref(), Some("Did you mean 'b'?"));
+    }
+
+    #[test]
+    fn non_maps_are_errors() {
+        for name in ["len", "keys", "values", "pairs"] {
+            let (value, failed) = map_call(name, vec![Value::Int(1)]);
+            assert!(failed, "{name}");
+            assert!(matches!(value, Value::None));
+        }
+    }
+}
@@ -1,32 +1,115 @@
 //! Built-in module components for document formatting
+mod bibliography;
+mod calc;
+mod cite;
+mod code_file;
+mod counter;
+mod data;
+mod datetime;
+mod equation;
 mod figure;
+mod glossary;
+mod gls;
+mod heading;
 mod highlight;
 mod hrule;
 mod image;
+mod index;
 mod linebreak;
 mod list;
+mod lists;
+mod make_index;
 mod map;
+mod maps;
+mod numbering;
+mod outline;
+mod range;
+mod read;
 mod smallcaps;
+mod string;
+mod table_from;
+mod target;
+mod today;
 mod underline;
+mod when;
 
+pub use bibliography::Bibliography;
+pub use calc::Calc;
+pub use cite::Cite;
+pub use code_file::CodeFile;
+pub use counter::Counter;
+pub use data::{Csv, Json, Toml, Yaml};
+pub use datetime::Datetime;
+pub use equation::Equation;
 pub use figure::Figure;
+pub use glossary::Glossary;
+pub use gls::Gls;
+pub use heading::Heading;
 pub use highlight::Highlight;
 pub use hrule::HorizontalRule;
 pub use image::Image;
+pub use index::Index;
 pub use linebreak::LineBreak;
 pub use list::List;
+pub use lists::Lists;
+pub use make_index::MakeIndex;
 pub use map::Map;
+pub use maps::Maps;
+pub use numbering::Numbering;
+pub use outline::Outline;
+pub use range::Range;
+pub use read::Read;
 pub use smallcaps::SmallCaps;
+pub use string::Str;
+pub use table_from::TableFrom;
+pub use target::Target;
+pub use today::Today;
 pub use underline::Underline;
+pub use when::When;
 
-use crate::{scope::Scope, Plugin};
+use ecow::{eco_format, EcoString};
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    scope::Scope,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::Value,
+    Plugin,
+};
 
 /// Built-in plugin that provides the standard document components
 ///
 /// This plugin registers all the basic components:
 /// - Map: Creates key-value mappings
 /// - List: Creates lists
-/// - Figure: Adds figure elements with required captions
+/// - Range: Creates lists of integers
+/// - Lists: Module of functions working with lists, e.g. `list.map`
+/// - Maps: Module of functions working with maps, e.g. `map.keys`
+/// - Datetime: Creates dates from ISO strings or their components
+/// - Today: Returns the current date, fixed by `SOURCE_DATE_EPOCH`
+/// - Calc: Module of functions working with numbers, e.g. `calc.round`
+/// - Str: Module of functions working with strings, e.g. `str.upper`
+/// - Json, Csv, Toml, Yaml: Load data files as maps and lists
+/// - TableFrom: Builds a table from a list of maps or lists
+/// - Read: Reads a text file as a string
+/// - CodeFile: Displays a source file, a range of its lines or a region as a code block
+/// - Target: Returns the output format the document is compiled to
+/// - When: Includes content only for one output format
+/// - Counter: Steps and displays custom counters
+/// - Numbering: Sets the numbering patterns of counters
+/// - Heading: Creates section headings, e.g. to set their numbering
+/// - Outline: Creates a table of contents of the headings
+/// - Bibliography: Loads a bibliography and lists the cited references
+/// - Cite: Cites an entry of the bibliography
+/// - Figure: Adds figure elements with required captions, numbered
+/// - Glossary: Defines the terms of the following terms block as a glossary
+/// - Gls: Uses a term of a glossary
+/// - Index: Marks a term for the index
+/// - MakeIndex: Creates an index of the marked terms
+/// - Equation: Displays numbered equations
 /// - Highlight: Highlights text
 /// - HorizontalRule: Adds horizontal rule separators
 /// - Image: Embeds images with optional sizing
@@ -39,11 +122,38 @@ pub struct BuiltinPlugin;
 impl Plugin for BuiltinPlugin {
     /// Initializes the builtin plugin by registering all standard components
     /// with the provided scope
-    fn init(scope: &mut Scope) {
+    fn init(&self, scope: &mut Scope) {
         scope
             .with("Map", Map)
             .with("List", List)
+            .with("range", Range)
+            .with("list", Lists)
+            .with("map", Maps)
+            .with("datetime", Datetime)
+            .with("today", Today)
+            .with("calc", Calc)
+            .with("str", Str)
+            .with("json", Json)
+            .with("csv", Csv)
+            .with("toml", Toml)
+            .with("yaml", Yaml)
+            .with("table_from", TableFrom)
+            .with("read", Read)
+            .with("code_file", CodeFile)
+            .with("target", Target)
+            .with("when", When)
+            .with("counter", Counter)
+            .with("numbering", Numbering)
+            .with("heading", Heading)
+            .with("outline", Outline)
+            .with("bibliography", Bibliography)
+            .with("cite", Cite)
+            .with("glossary", Glossary)
+            .with("gls", Gls)
+            .with("index", Index)
+            .with("make_index", MakeIndex)
             .with("figure", Figure)
+            .with("equation", Equation)
             .with("highlight", Highlight)
             .with("hrule", HorizontalRule)
             .with("image", Image)
@@ -52,3 +162,95 @@ impl Plugin for BuiltinPlugin {
             .with("underline", Underline);
     }
 }
+
+/// Creates a function of a module, named `<prefix>.<name>`, e.g. `str.upper`.
+///
+/// # Returns
+/// The name of the function in the module together with the function
+pub(crate) fn module_func(
+    prefix: &str,
+    name: &str,
+    signature: Signature,
+    func: fn(Args, Source, Span, &mut Tracer, &mut State) -> Value,
+) -> (EcoString, Value) {
+    let value = Value::Func(Func::new(eco_format!("{prefix}.{name}"), signature, func));
+    (name.into(), value)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use tyd_syntax::{source::Source, Span, Spans};
+
+    use crate::{func::Caller, ir, scope::Scope, state::State, tracer::Tracer, value::Value};
+
+    /// Calls native functions outside of a document, collecting their diagnostics.
+    pub struct TestCaller {
+        pub source: Source,
+        pub tracer: Tracer,
+        pub state: State,
+    }
+
+    impl TestCaller {
+        pub fn new() -> Self {
+            let source = Source::new("test.tyd", "test.tyd", "");
+            let tracer = Tracer::new(source.clone(), Spans::from(Vec::new()));
+
+            Self {
+                source,
+                tracer,
+                state: State::default(),
+            }
+        }
+
+        /// Calls a function with positional and named arguments.
+        pub fn call_with(
+            &mut self,
+            func: &Value,
+            args: Vec<Value>,
+            named: Vec<(&str, Value)>,
+        ) -> Value {
+            let Value::Func(func) = func else {
+                panic!("{func:?} is not a function");
+            };
+
+            let mut scope = Scope::empty();
+            for (name, value) in named {
+                scope.insert(name.into(), value);
+            }
+
+            let span = self.source.end_of_input();
+            func.call(args, scope, None, &ir::Map::new(), span, self)
+        }
+
+        /// Returns whether an error was reported.
+        pub fn failed(&self) -> bool {
+            self.tracer.has_errors()
+        }
+    }
+
+    impl Caller for TestCaller {
+        fn context(&mut self) -> (Source, &mut Tracer, &mut State) {
+            (self.source.clone(), &mut self.tracer, &mut self.state)
+        }
+
+        fn call(&mut self, callee: &Value, args: Vec<Value>, _: Span) -> Value {
+            self.call_with(callee, args, Vec::new())
+        }
+    }
+
+    /// Calls a function with positional arguments and returns its result
+    /// together with whether an error was reported.
+    pub fn call(func: impl Into<Value>, args: Vec<Value>) -> (Value, bool) {
+        let mut caller = TestCaller::new();
+        let value = caller.call_with(&func.into(), args, Vec::new());
+        (value, caller.failed())
+    }
+
+    /// Returns the function of a module, e.g. `str.repeat`.
+    pub fn field(module: impl Into<Value>, name: &str) -> Value {
+        match module.into() {
+            Value::Map(map) => map[name].clone(),
+            other => panic!("{other:?} is not a module"),
+        }
+    }
+}
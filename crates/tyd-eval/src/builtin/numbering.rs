@@ -0,0 +1,83 @@
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    counter::Numbering as Pattern,
+    func::Func,
+    ir,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'numbering' function which configures how counters are displayed.
+///
+/// This struct is used as a type to represent the numbering function.
+#[derive(Debug, Clone, Copy)]
+pub struct Numbering;
+
+impl From<Numbering> for Value {
+    /// Converts the Numbering struct into a function Value.
+    fn from(_: Numbering) -> Self {
+        let signature = Signature::new(
+            "Sets the numbering patterns of counters, e.g. `numbering(heading: \"1.1\")`.",
+            Type::Content,
+        )
+        .named_rest(
+            "patterns",
+            Type::Str,
+            "The numbering pattern by counter name, an empty pattern disables the numbering",
+        );
+
+        Value::Func(Func::new("numbering", signature, numbering))
+    }
+}
+
+/// Sets the numbering patterns of counters.
+///
+/// Patterns consist of counting symbols (`1`, `a`, `A`, `i`, `I`) and the characters
+/// between them, e.g. `1.1`, `A.` or `(i)`. The numbering applies to all elements
+/// following the call.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `patterns` - The numbering pattern by counter name
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting issues
+/// * `state` - Document state holding the counters
+///
+/// # Returns
+///
+/// An empty Value::Content, as the function only changes the state.
+pub fn numbering(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let patterns = args.take::<ir::Map>("patterns");
+
+    for (name, pattern) in patterns {
+        let Value::Str(pattern) = pattern else {
+            continue;
+        };
+
+        if pattern.is_empty() {
+            state.counters.set_numbering(&name, None);
+            continue;
+        }
+
+        match Pattern::parse(&pattern) {
+            Some(numbering) => state.counters.set_numbering(&name, Some(numbering)),
+            None => tracer.source_error(
+                span,
+                format!("Invalid numbering pattern '{pattern}' for {name}"),
+            ),
+        }
+    }
+
+    Value::Content(ir::Content::new())
+}
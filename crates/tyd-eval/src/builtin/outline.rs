@@ -0,0 +1,91 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    outline::Options,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'outline' function which creates a table of contents.
+///
+/// This struct is used as a type to represent the outline function.
+#[derive(Debug, Clone, Copy)]
+pub struct Outline;
+
+impl From<Outline> for Value {
+    /// Converts the Outline struct into a function Value.
+    fn from(_: Outline) -> Self {
+        let signature = Signature::new(
+            "Creates a table of contents listing the headings of the document.",
+            Type::Block,
+        )
+        .named_or(
+            "title",
+            Type::Str,
+            "Contents",
+            "The title of the outline, empty for none",
+        )
+        .named_or(
+            "depth",
+            Type::Int,
+            3,
+            "The deepest level of headings listed",
+        )
+        .named_or(
+            "numbered",
+            Type::Bool,
+            true,
+            "Whether the numbers of numbered headings are displayed",
+        );
+
+        Value::Func(Func::new("outline", signature, outline))
+    }
+}
+
+/// Creates a table of contents listing the headings of the document.
+///
+/// The outline lists all headings of the document, including those following it,
+/// as it is filled after the whole document was evaluated.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `title` - The title of the outline, defaults to `Contents`
+///   * `depth` - The deepest level of headings listed, defaults to 3
+///   * `numbered` - Whether heading numbers are displayed, defaults to true
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting issues
+/// * `state` - Document state collecting the headings
+///
+/// # Returns
+///
+/// A Value::Block containing the outline or Value::None if an error occurred.
+pub fn outline(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let title = args.take::<EcoString>("title");
+    let depth = args.take::<i64>("depth");
+    let numbered = args.take::<bool>("numbered");
+
+    let Ok(depth) = usize::try_from(depth) else {
+        tracer.source_error(span, "Depth of outline must not be negative");
+        return Value::None;
+    };
+
+    let options = Options {
+        title,
+        depth,
+        numbered,
+    };
+
+    Value::Block(state.outline.placeholder(options))
+}
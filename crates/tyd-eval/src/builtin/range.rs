@@ -0,0 +1,176 @@
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// The maximum number of integers a range may contain
+pub const MAX_RANGE_LEN: u64 = 1 << 20;
+
+/// Represents the 'range' function which creates a list of integers.
+///
+/// This struct is used as a type to represent the range creation function.
+#[derive(Debug, Clone, Copy)]
+pub struct Range;
+
+impl From<Range> for Value {
+    /// Converts the Range struct into a function Value.
+    fn from(_: Range) -> Self {
+        let signature = Signature::new(
+            "Creates a list of integers from a start up to (excluding) an end.",
+            Type::list(Type::Int),
+        )
+        .positional_or("start", Type::Int, 0, "The first integer of the range")
+        .positional("end", Type::Int, "The integer at which the range stops")
+        .named_or(
+            "step",
+            Type::Int,
+            1,
+            "The distance between two integers, must not be zero",
+        );
+
+        Value::Func(Func::new("range", signature, range))
+    }
+}
+
+/// Creates a list of integers from a start up to (excluding) an end.
+///
+/// Called with a single positional argument, the range starts at zero:
+/// `range(3)` creates `List(0, 1, 2)`, while `range(1, 3)` creates `List(1, 2)`.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `start` - The first integer of the range, defaults to 0
+///   * `end` - The integer at which the range stops
+///   * `step` - The distance between two integers, defaults to 1
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting issues
+/// * `_state` - Document state (unused)
+///
+/// # Returns
+///
+/// A Value::List of integers or Value::None if an error occurred,
+/// e.g. if the range would contain more than `MAX_RANGE_LEN` integers.
+pub fn range(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _state: &mut State,
+) -> Value {
+    let start = args.take::<i64>("start");
+    let end = args.take::<i64>("end");
+    let step = args.take::<i64>("step");
+
+    if step == 0 {
+        tracer.source_error(span, "Step of range must not be zero");
+        return Value::None;
+    }
+
+    // Computed in 128 bits, so that the distance of any two integers fits
+    let distance = end as i128 - start as i128;
+    let len = if distance == 0 || (distance > 0) != (step > 0) {
+        0
+    } else {
+        (distance.unsigned_abs() - 1) / step.unsigned_abs() as u128 + 1
+    };
+
+    if len > MAX_RANGE_LEN as u128 {
+        tracer.source_error(
+            span,
+            format!("Range of {len} integers exceeds the limit of {MAX_RANGE_LEN} integers"),
+        );
+        return Value::None;
+    }
+
+    let mut list = Vec::with_capacity(len as usize);
+    let mut current = Some(start);
+
+    while let Some(value) = current
+        && list.len() < len as usize
+    {
+        list.push(Value::Int(value));
+        current = value.checked_add(step);
+    }
+
+    Value::List(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::tests::{call, TestCaller};
+
+    fn ints(value: Value) -> Vec<i64> {
+        match value {
+            Value::List(list) => list
+                .into_iter()
+                .map(|value| match value {
+                    Value::Int(int) => int,
+                    other => panic!("{other:?} is not an integer"),
+                })
+                .collect(),
+            other => panic!("{other:?} is not a list"),
+        }
+    }
+
+    fn range_step(start: i64, end: i64, step: i64) -> (Value, bool) {
+        let mut caller = TestCaller::new();
+        let args = vec![Value::Int(start), Value::Int(end)];
+        let value = caller.call_with(&Range.into(), args, vec![("step", Value::Int(step))]);
+        (value, caller.failed())
+    }
+
+    #[test]
+    fn starts_at_zero() {
+        let (value, failed) = call(Range, vec![Value::Int(3)]);
+        assert!(!failed);
+        assert_eq!(ints(value), [0, 1, 2]);
+    }
+
+    #[test]
+    fn steps_forward_and_backward() {
+        assert_eq!(ints(range_step(1, 8, 3).0), [1, 4, 7]);
+        assert_eq!(ints(range_step(5, 0, -2).0), [5, 3, 1]);
+    }
+
+    #[test]
+    fn empty_when_step_points_away_from_end() {
+        assert_eq!(ints(range_step(0, 5, -1).0), Vec::<i64>::new());
+        assert_eq!(ints(range_step(5, 5, 1).0), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn rejects_zero_step() {
+        let (value, failed) = range_step(0, 5, 0);
+        assert!(failed);
+        assert!(matches!(value, Value::None));
+    }
+
+    #[test]
+    fn stops_before_overflow() {
+        let (value, failed) = range_step(i64::MAX - 7, i64::MAX, 5);
+        assert!(!failed);
+        assert_eq!(ints(value), [i64::MAX - 7, i64::MAX - 2]);
+
+        let (value, _) = range_step(i64::MIN + 1, i64::MIN, -5);
+        assert_eq!(ints(value), [i64::MIN + 1]);
+    }
+
+    #[test]
+    fn rejects_huge_ranges() {
+        let (value, failed) = call(Range, vec![Value::Int(0), Value::Int(i64::MAX)]);
+        assert!(failed);
+        assert!(matches!(value, Value::None));
+
+        let (value, failed) = range_step(i64::MIN, i64::MAX, i64::MAX);
+        assert!(!failed);
+        assert_eq!(ints(value).len(), 3);
+    }
+}
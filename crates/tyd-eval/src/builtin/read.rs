@@ -0,0 +1,85 @@
+use ecow::EcoString;
+use std::{fs, path::PathBuf};
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    error::EngineError,
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'read' function which reads a text file.
+///
+/// This struct is used as a type to represent the read function.
+#[derive(Debug, Clone, Copy)]
+pub struct Read;
+
+impl From<Read> for Value {
+    /// Converts the Read struct into a function Value.
+    fn from(_: Read) -> Self {
+        let signature = Signature::new("Reads a text file as a string.", Type::Str).positional(
+            "path",
+            Type::Str,
+            "Path to the file, relative to the current document",
+        );
+
+        Value::Func(Func::new("read", signature, read))
+    }
+}
+
+/// Reads a text file relative to the current document.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `path` - Path to the file, relative to the current document
+/// * `source` - Source information for resolving the path
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting unreadable files
+/// * `_state` - Document state (unused)
+///
+/// # Returns
+///
+/// A Value::Str containing the text of the file or Value::None if an error occurred.
+pub fn read(
+    mut args: Args,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _state: &mut State,
+) -> Value {
+    let path = args.take::<EcoString>("path");
+
+    match read_file(&path, source, span, tracer) {
+        Some((_, text)) => Value::Str(text.into()),
+        None => Value::None,
+    }
+}
+
+/// Reads a text file relative to the current document, reporting an error if it cannot be read.
+///
+/// # Returns
+/// The resolved path and the text of the file, or None if an error occurred
+pub(super) fn read_file(
+    path: &str,
+    source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+) -> Option<(PathBuf, String)> {
+    let path = source.work_path().join(path);
+
+    match fs::read_to_string(&path) {
+        Ok(text) => Some((path, text)),
+        Err(err) => {
+            let error = EngineError::ReadFile {
+                path: path.display().to_string(),
+                message: err.to_string(),
+            };
+            tracer.source_error(span, error);
+            None
+        }
+    }
+}
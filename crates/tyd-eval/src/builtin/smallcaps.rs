@@ -1,11 +1,12 @@
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
+    func::Func,
     ir,
-    scope::Scope,
-    stack::Stack,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents the small-caps formatting function.
@@ -21,43 +22,36 @@ impl Into<Value> for SmallCaps {
     ///
     /// * `Value` - A function value that can be executed to format text in small caps.
     fn into(self) -> Value {
-        Value::Func(smallcaps)
+        let signature =
+            Signature::new("Formats the given content in small capitals.", Type::Inline)
+                .content("content", "The content to format");
+
+        Value::Func(Func::new("smallcaps", signature, smallcaps))
     }
 }
 
 /// Applies small-caps formatting to the provided content.
 ///
-/// This function takes content as its first positional argument and wraps it in
-/// small-caps formatting. It ignores any additional arguments and warns about their presence.
-///
 /// # Arguments
 ///
-/// * `stack` - The positional arguments stack, expected to contain content to format
-/// * `scope` - Named arguments (all will be warned as unknown)
+/// * `args` - The bound arguments:
+///   * `content` - The content to format
 /// * `_source` - Source information (unused)
-/// * `span` - The span in the source where this function is called
-/// * `tracer` - Error and warning tracer
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
 /// * `Value::Inline` - An inline element with small-caps formatting applied to the content
-/// * `Value::None` - If the required content argument is missing or of the wrong type
 pub fn smallcaps(
-    mut stack: Stack,
-    scope: Scope,
+    mut args: Args,
     _source: Source,
-    span: Span,
-    tracer: &mut Tracer,
+    _span: Span,
+    _tracer: &mut Tracer,
+    _state: &mut State,
 ) -> Value {
-    let mut checker = TypeChecker::new(tracer, span);
-
-    let content = match checker.pop_from_stack::<ir::Content>(&mut stack, 0) {
-        Some(content) => content,
-        None => return Value::None,
-    };
-
-    checker.warn_unknown_positional(stack, 1);
-    checker.warn_unknown_named(scope);
+    let content = args.take::<ir::Content>("content");
 
     let inline = ir::Inline::SmallCaps(content);
 
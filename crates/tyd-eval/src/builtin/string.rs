@@ -0,0 +1,524 @@
+use ecow::{eco_format, EcoString};
+use regex::Regex;
+use tyd_syntax::{source::Source, Span};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::module_func;
+use crate::{
+    ir, label,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// The maximum length in bytes of a string created by `str.repeat`
+pub const MAX_REPEAT_LEN: usize = 1 << 24;
+
+/// Represents the 'str' module, a map of functions working with strings.
+///
+/// The functions are accessed as fields of the module, e.g. `str.upper("text")`.
+/// Lengths and positions count grapheme clusters, so `str.len("é")` is one
+/// even if the character is made of multiple code points.
+#[derive(Debug, Clone, Copy)]
+pub struct Str;
+
+impl From<Str> for Value {
+    /// Converts the Str struct into a map of function Values.
+    fn from(_: Str) -> Self {
+        let text = "The string";
+
+        let functions = [
+            module_func(
+                "str",
+                "len",
+                Signature::new("Returns the number of characters of a string.", Type::Int)
+                    .positional("text", Type::Str, text),
+                len,
+            ),
+            module_func(
+                "str",
+                "upper",
+                Signature::new("Converts a string to uppercase.", Type::Str).positional(
+                    "text",
+                    Type::Str,
+                    text,
+                ),
+                upper,
+            ),
+            module_func(
+                "str",
+                "lower",
+                Signature::new("Converts a string to lowercase.", Type::Str).positional(
+                    "text",
+                    Type::Str,
+                    text,
+                ),
+                lower,
+            ),
+            module_func(
+                "str",
+                "trim",
+                Signature::new(
+                    "Removes leading and trailing whitespace of a string.",
+                    Type::Str,
+                )
+                .positional("text", Type::Str, text),
+                trim,
+            ),
+            module_func(
+                "str",
+                "split",
+                Signature::new("Splits a string at a separator.", Type::list(Type::Str))
+                    .positional("text", Type::Str, text)
+                    .positional_or(
+                        "separator",
+                        Type::Str,
+                        "",
+                        "The separator, splits at whitespace if empty",
+                    ),
+                split,
+            ),
+            module_func(
+                "str",
+                "replace",
+                Signature::new(
+                    "Replaces all occurrences of a pattern in a string.",
+                    Type::Str,
+                )
+                .positional("text", Type::Str, text)
+                .positional("pattern", Type::Str, "The replaced pattern")
+                .positional(
+                    "replacement",
+                    Type::Str,
+                    "The replacement, which can refer to groups of a regex with `$1`",
+                )
+                .named_or(
+                    "regex",
+                    Type::Bool,
+                    false,
+                    "Whether the pattern is a regular expression",
+                ),
+                replace,
+            ),
+            module_func(
+                "str",
+                "contains",
+                Signature::new("Returns whether a string contains a pattern.", Type::Bool)
+                    .positional("text", Type::Str, text)
+                    .positional("pattern", Type::Str, "The pattern to search for")
+                    .named_or(
+                        "regex",
+                        Type::Bool,
+                        false,
+                        "Whether the pattern is a regular expression",
+                    ),
+                contains,
+            ),
+            module_func(
+                "str",
+                "starts_with",
+                Signature::new("Returns whether a string starts with a prefix.", Type::Bool)
+                    .positional("text", Type::Str, text)
+                    .positional("prefix", Type::Str, "The prefix"),
+                starts_with,
+            ),
+            module_func(
+                "str",
+                "ends_with",
+                Signature::new("Returns whether a string ends with a suffix.", Type::Bool)
+                    .positional("text", Type::Str, text)
+                    .positional("suffix", Type::Str, "The suffix"),
+                ends_with,
+            ),
+            module_func(
+                "str",
+                "slice",
+                Signature::new("Returns the characters of a string in a range.", Type::Str)
+                    .positional("text", Type::Str, text)
+                    .positional(
+                        "start",
+                        Type::Int,
+                        "The first character, negative values count from the end",
+                    )
+                    .positional_or(
+                        "end",
+                        Type::Int,
+                        Value::None,
+                        "The character after the last one, defaults to the end of the string",
+                    ),
+                slice,
+            ),
+            module_func(
+                "str",
+                "repeat",
+                Signature::new("Repeats a string.", Type::Str)
+                    .positional("text", Type::Str, text)
+                    .positional("count", Type::Int, "How often the string is repeated"),
+                repeat,
+            ),
+            module_func(
+                "str",
+                "format",
+                Signature::new(
+                    "Formats values, e.g. `str.format(\"{} of {}\", 1, 3)`.",
+                    Type::Str,
+                )
+                .positional(
+                    "pattern",
+                    Type::Str,
+                    "The pattern, where `{}` or `{0}` are replaced by the values",
+                )
+                .rest("values", Type::Any, "The formatted values"),
+                format,
+            ),
+            module_func(
+                "str",
+                "matches",
+                Signature::new(
+                    "Returns all matches of a regular expression in a string.",
+                    Type::list(Type::Str),
+                )
+                .positional("text", Type::Str, text)
+                .positional("pattern", Type::Str, "The regular expression"),
+                matches,
+            ),
+        ];
+
+        Value::Map(functions.into_iter().collect())
+    }
+}
+
+/// Compiles a regular expression, reporting an error if it is invalid.
+fn regex(pattern: &str, span: Span, tracer: &mut Tracer) -> Option<Regex> {
+    match Regex::new(pattern) {
+        Ok(regex) => Some(regex),
+        Err(error) => {
+            tracer.source_error(span, format!("Invalid regular expression: {error}"));
+            None
+        }
+    }
+}
+
+/// Returns the number of grapheme clusters of a string.
+fn len(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    Value::Int(text.graphemes(true).count() as i64)
+}
+
+/// Converts a string to uppercase.
+fn upper(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    Value::Str(args.take::<EcoString>("text").to_uppercase())
+}
+
+/// Converts a string to lowercase.
+fn lower(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    Value::Str(args.take::<EcoString>("text").to_lowercase())
+}
+
+/// Removes leading and trailing whitespace of a string.
+fn trim(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    Value::Str(args.take::<EcoString>("text").trim().into())
+}
+
+/// Splits a string at a separator, or at whitespace if the separator is empty.
+fn split(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let separator = args.take::<EcoString>("separator");
+
+    let parts: Vec<&str> = if separator.is_empty() {
+        text.split_whitespace().collect()
+    } else {
+        text.split(separator.as_str()).collect()
+    };
+
+    Value::List(
+        parts
+            .into_iter()
+            .map(|part| Value::Str(part.into()))
+            .collect(),
+    )
+}
+
+/// Replaces all occurrences of a pattern, which may be a regular expression.
+fn replace(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let pattern = args.take::<EcoString>("pattern");
+    let replacement = args.take::<EcoString>("replacement");
+
+    if !args.take::<bool>("regex") {
+        return Value::Str(text.replace(pattern.as_str(), &replacement));
+    }
+
+    match regex(&pattern, span, tracer) {
+        Some(regex) => Value::Str(regex.replace_all(&text, replacement.as_str()).into()),
+        None => Value::None,
+    }
+}
+
+/// Returns whether a string contains a pattern, which may be a regular expression.
+fn contains(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let pattern = args.take::<EcoString>("pattern");
+
+    if !args.take::<bool>("regex") {
+        return Value::Bool(text.contains(pattern.as_str()));
+    }
+
+    match regex(&pattern, span, tracer) {
+        Some(regex) => Value::Bool(regex.is_match(&text)),
+        None => Value::None,
+    }
+}
+
+/// Returns whether a string starts with a prefix.
+fn starts_with(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let prefix = args.take::<EcoString>("prefix");
+    Value::Bool(text.starts_with(prefix.as_str()))
+}
+
+/// Returns whether a string ends with a suffix.
+fn ends_with(mut args: Args, _: Source, _: Span, _: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let suffix = args.take::<EcoString>("suffix");
+    Value::Bool(text.ends_with(suffix.as_str()))
+}
+
+/// Returns the grapheme clusters of a string from `start` up to (excluding) `end`.
+///
+/// Negative positions count from the end of the string, e.g. `str.slice("hello", -3)`
+/// returns `llo`. Positions outside of the string are reported as errors.
+fn slice(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let start = args.take::<i64>("start");
+    let end = match args.take::<Value>("end") {
+        Value::Int(end) => Some(end),
+        _ => None,
+    };
+
+    let graphemes = text.graphemes(true).collect::<Vec<_>>();
+    let len = graphemes.len() as i64;
+
+    let resolve = |pos: i64| {
+        let pos = if pos < 0 { len + pos } else { pos };
+        (0..=len).contains(&pos).then_some(pos as usize)
+    };
+
+    let (Some(start), Some(end)) = (resolve(start), resolve(end.unwrap_or(len))) else {
+        tracer.source_error(
+            span,
+            format!("Position out of bounds for string of length {len}"),
+        );
+        return Value::None;
+    };
+
+    if start > end {
+        tracer.source_error(span, "Start of slice must not be after its end");
+        return Value::None;
+    }
+
+    Value::Str(graphemes[start..end].concat().into())
+}
+
+/// Repeats a string a number of times.
+///
+/// The repeated string must not be longer than `MAX_REPEAT_LEN` bytes.
+fn repeat(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let count = args.take::<i64>("count");
+
+    let Ok(count) = usize::try_from(count) else {
+        tracer.source_error(span, "Count of repetitions must not be negative");
+        return Value::None;
+    };
+
+    match text.len().checked_mul(count) {
+        Some(len) if len <= MAX_REPEAT_LEN => Value::Str(text.repeat(count)),
+        _ => {
+            tracer.source_error(
+                span,
+                format!("Repeated string exceeds the limit of {MAX_REPEAT_LEN} bytes"),
+            );
+            Value::None
+        }
+    }
+}
+
+/// Replaces the placeholders of a pattern by the displayed values.
+///
+/// `{}` is replaced by the next value, `{0}` by the value at that position, and
+/// `{{` and `}}` display literal braces. Content is displayed as its plain text.
+fn format(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let pattern = args.take::<EcoString>("pattern");
+    let values = args.take::<ir::List>("values");
+
+    let mut out = EcoString::new();
+    let mut next = 0;
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let Some((index, rest)) = chars.as_str().split_once('}') else {
+                    tracer.source_error(span, "Unclosed placeholder in format pattern");
+                    return Value::None;
+                };
+
+                let position = if index.is_empty() {
+                    next += 1;
+                    Some(next - 1)
+                } else {
+                    index.trim().parse::<usize>().ok()
+                };
+
+                let Some(position) = position else {
+                    tracer.source_error(span, format!("Invalid placeholder '{{{index}}}'"));
+                    return Value::None;
+                };
+
+                let Some(value) = values.get(position) else {
+                    tracer.source_error(
+                        span,
+                        format!(
+                            "Placeholder {position} has no value, the number of values is {}",
+                            values.len()
+                        ),
+                    );
+                    return Value::None;
+                };
+
+                out.push_str(&display(value));
+                chars = rest.chars();
+            }
+            c => out.push(c),
+        }
+    }
+
+    Value::Str(out)
+}
+
+/// Displays a formatted value as text.
+fn display(value: &Value) -> EcoString {
+    match value {
+        Value::Str(s) => s.clone(),
+        Value::Int(i) => eco_format!("{i}"),
+        Value::Float(f) => eco_format!("{f}"),
+        Value::Bool(b) => eco_format!("{b}"),
+        Value::Content(content) => label::plain_text(content),
+        Value::Inline(inline) => label::plain_text(std::slice::from_ref(inline)),
+        Value::None => EcoString::new(),
+        other => eco_format!("{other:?}"),
+    }
+}
+
+/// Returns all non-overlapping matches of a regular expression.
+fn matches(mut args: Args, _: Source, span: Span, tracer: &mut Tracer, _: &mut State) -> Value {
+    let text = args.take::<EcoString>("text");
+    let pattern = args.take::<EcoString>("pattern");
+
+    match regex(&pattern, span, tracer) {
+        Some(regex) => Value::List(
+            regex
+                .find_iter(&text)
+                .map(|found| Value::Str(found.as_str().into()))
+                .collect(),
+        ),
+        None => Value::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::tests::{call, field};
+
+    fn str_call(name: &str, args: Vec<Value>) -> (Value, bool) {
+        call(field(Str, name), args)
+    }
+
+    fn text(value: Value) -> EcoString {
+        match value {
+            Value::Str(s) => s,
+            other => panic!("{other:?} is not a string"),
+        }
+    }
+
+    #[test]
+    fn len_counts_graphemes() {
+        let (value, _) = str_call("len", vec!["e\u{301}a".into()]);
+        assert!(matches!(value, Value::Int(2)));
+    }
+
+    #[test]
+    fn repeat() {
+        let (value, failed) = str_call("repeat", vec!["ab".into(), Value::Int(3)]);
+        assert!(!failed);
+        assert_eq!(text(value), "ababab");
+    }
+
+    #[test]
+    fn repeat_rejects_negative_count() {
+        let (value, failed) = str_call("repeat", vec!["ab".into(), Value::Int(-1)]);
+        assert!(failed);
+        assert!(matches!(value, Value::None));
+    }
+
+    #[test]
+    fn repeat_rejects_huge_strings() {
+        let (value, failed) = str_call("repeat", vec!["ab".into(), Value::Int(i64::MAX)]);
+        assert!(failed);
+        assert!(matches!(value, Value::None));
+
+        let count = (MAX_REPEAT_LEN / 2 + 1) as i64;
+        let (_, failed) = str_call("repeat", vec!["ab".into(), Value::Int(count)]);
+        assert!(failed);
+    }
+
+    #[test]
+    fn slice_from_end() {
+        let (value, _) = str_call("slice", vec!["hello".into(), Value::Int(-3)]);
+        assert_eq!(text(value), "llo");
+    }
+
+    #[test]
+    fn slice_out_of_bounds() {
+        let (_, failed) = str_call("slice", vec!["hello".into(), Value::Int(6)]);
+        assert!(failed);
+
+        let args = vec!["hello".into(), Value::Int(3), Value::Int(1)];
+        let (_, failed) = str_call("slice", args);
+        assert!(failed);
+    }
+
+    #[test]
+    fn format_placeholders() {
+        let args = vec!["{1} {{of}} {}".into(), Value::Int(1), Value::Int(3)];
+        let (value, failed) = str_call("format", args);
+        assert!(!failed);
+        assert_eq!(text(value), "3 {of} 1");
+    }
+
+    #[test]
+    fn format_invalid_placeholders() {
+        for pattern in ["{", "{x}", "{2}"] {
+            let (value, failed) = str_call("format", vec![pattern.into(), Value::Int(1)]);
+            assert!(failed, "{pattern}");
+            assert!(matches!(value, Value::None));
+        }
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let (_, failed) = str_call("matches", vec!["text".into(), "(".into()]);
+        assert!(failed);
+    }
+}
@@ -0,0 +1,326 @@
+use ecow::EcoString;
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    counter,
+    error::TypeError,
+    func::Func,
+    ir,
+    label::Target,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'table_from' function which builds a table from data.
+///
+/// This struct is used as a type to represent the table_from function.
+#[derive(Debug, Clone, Copy)]
+pub struct TableFrom;
+
+impl From<TableFrom> for Value {
+    /// Converts the TableFrom struct into a function Value.
+    fn from(_: TableFrom) -> Self {
+        let signature = Signature::new(
+            "Builds a table with a header row from a list of maps or lists, e.g. loaded by `csv`.",
+            Type::Block,
+        )
+        .positional(
+            "data",
+            Type::list(Type::Any),
+            "The rows, either maps from column names to cells or lists of cells",
+        )
+        .named_or(
+            "columns",
+            Type::list(Type::Str),
+            Value::List(Vec::new()),
+            "The keys of the columns of map rows, or the header of list rows; \
+             the keys of the first map or the first list if empty",
+        )
+        .named_or(
+            "label",
+            Type::Str,
+            "",
+            "A label to reference the table with, e.g. `@label`",
+        );
+
+        Value::Func(Func::new("table_from", signature, table_from))
+    }
+}
+
+/// Builds a table with a header row from a list of maps or lists.
+///
+/// Rows which are maps are displayed in the order of the columns, so that
+/// columns can also select the displayed keys, and keys missing in a row
+/// are empty cells. Rows which are lists are padded with empty cells to the
+/// length of the longest row.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `data` - The rows of the table
+///   * `columns` - The keys or header of the columns, derived from the first row if empty
+///   * `label` - A label to reference the table with, empty for none
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting rows of the wrong type
+/// * `state` - Document state, used to number the table
+///
+/// # Returns
+///
+/// A Value::Block containing the table or Value::None if an error occurred.
+pub fn table_from(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let data = args.take::<ir::List>("data");
+    let columns = args.take::<ir::List>("columns");
+    let label = args.take::<EcoString>("label");
+
+    let (header, rows) = match data.first() {
+        Some(Value::Map(first)) => {
+            let columns: Vec<EcoString> = match columns.is_empty() {
+                true => first.keys().cloned().collect(),
+                false => columns.into_iter().filter_map(into_str).collect(),
+            };
+            let mut rows = Vec::new();
+
+            for row in data {
+                let Value::Map(mut row) = row else {
+                    wrong_row(row, Type::Map(Vec::new()), span, tracer);
+                    return Value::None;
+                };
+
+                let cells = columns
+                    .iter()
+                    .map(|column| row.remove(column).unwrap_or(Value::None))
+                    .collect();
+                rows.push(cells);
+            }
+
+            let header = columns.into_iter().map(Value::Str).collect();
+            (header, rows)
+        }
+        Some(Value::List(_)) => {
+            let mut rows = Vec::new();
+
+            for row in data {
+                let Value::List(cells) = row else {
+                    wrong_row(row, Type::list(Type::Any), span, tracer);
+                    return Value::None;
+                };
+
+                rows.push(cells);
+            }
+
+            let header = match columns.is_empty() {
+                true => rows.remove(0),
+                false => columns,
+            };
+            (header, rows)
+        }
+        Some(other) => {
+            let ty = other.ty();
+            tracer.source_error(
+                span,
+                format!("Cannot build a table from rows of type {ty}, expected maps or lists"),
+            );
+            return Value::None;
+        }
+        None => {
+            tracer.source_error(span, "Cannot build a table without rows");
+            return Value::None;
+        }
+    };
+
+    let width = rows
+        .iter()
+        .map(Vec::len)
+        .chain([header.len()])
+        .max()
+        .unwrap_or_default();
+
+    state.counters.step(counter::TABLE, 1);
+
+    let number = state.counters.display(counter::TABLE);
+
+    if !label.is_empty() {
+        let target = Target {
+            supplement: "Table".into(),
+            number: number.clone(),
+            text: label.clone(),
+            span,
+        };
+        state.labels.define(label.clone(), target, tracer);
+    }
+
+    let caption = match number {
+        Some(number) => {
+            let inlines = vec![
+                ir::Inline::Str("Table".to_owned()),
+                ir::Inline::Space,
+                ir::Inline::Str(number.to_string()),
+            ];
+            (None, vec![ir::Block::Plain(inlines)])
+        }
+        None => (None, Vec::new()),
+    };
+
+    let rows = rows.into_iter().map(|cells| row(cells, width)).collect();
+
+    let attr = ir::AttrBuilder::new().ident(label.to_string()).build();
+    let col_spec = (ir::Alignment::AlignDefault, ir::ColWidth::ColWidthDefault);
+    let col_specs = vec![col_spec; width];
+    let head = (ir::AttrBuilder::empty(), vec![row(header, width)]);
+    let body = vec![(ir::AttrBuilder::empty(), 0, rows, Vec::new())];
+    let foot = (ir::AttrBuilder::empty(), Vec::new());
+
+    Value::Block(ir::Block::Table(attr, caption, col_specs, head, body, foot))
+}
+
+/// Reports a row whose type differs from the type of the first row.
+fn wrong_row(row: Value, expected: Type, span: Span, tracer: &mut Tracer) {
+    let error = TypeError::WrongType {
+        got: row.ty(),
+        expected,
+    };
+    tracer.source_error(span, error);
+}
+
+/// Returns the string of a column key, which is type checked by the signature.
+fn into_str(value: Value) -> Option<EcoString> {
+    match value {
+        Value::Str(s) => Some(s),
+        _ => None,
+    }
+}
+
+/// Builds a table row of the cells, padded with empty cells to the width of the table.
+fn row(cells: Vec<Value>, width: usize) -> ir::Row {
+    let empty = std::iter::repeat_with(|| Value::None);
+
+    let cells = cells
+        .into_iter()
+        .chain(empty)
+        .take(width)
+        .map(|value| {
+            let blocks = match value {
+                Value::Block(block) => vec![block],
+                Value::None => Vec::new(),
+                value => vec![ir::Block::Plain(value.into_content())],
+            };
+
+            (
+                ir::AttrBuilder::empty(),
+                ir::Alignment::AlignDefault,
+                1,
+                1,
+                blocks,
+            )
+        })
+        .collect();
+
+    (ir::AttrBuilder::empty(), cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builtin::tests::TestCaller, label::plain_text};
+
+    fn str_map(entries: &[(&str, &str)]) -> Value {
+        Value::Map(
+            entries
+                .iter()
+                .map(|(key, value)| (EcoString::from(*key), Value::from(*value)))
+                .collect(),
+        )
+    }
+
+    fn str_list(items: &[&str]) -> Value {
+        Value::List(items.iter().map(|item| Value::from(*item)).collect())
+    }
+
+    /// Builds a table, returning the text of its cells row by row, starting with the header.
+    fn table(data: Vec<Value>, named: Vec<(&str, Value)>) -> Vec<Vec<String>> {
+        let mut caller = TestCaller::new();
+        let value = caller.call_with(&TableFrom.into(), vec![Value::List(data)], named);
+        assert!(!caller.failed());
+
+        let Value::Block(ir::Block::Table(_, _, _, (_, head), body, _)) = value else {
+            panic!("{value:?} is not a table");
+        };
+
+        head.iter()
+            .chain(body.iter().flat_map(|(_, _, rows, _)| rows))
+            .map(|(_, cells)| {
+                cells
+                    .iter()
+                    .map(|(.., blocks)| match blocks.as_slice() {
+                        [ir::Block::Plain(inlines)] => plain_text(inlines).to_string(),
+                        [] => String::new(),
+                        blocks => panic!("unexpected {blocks:?}"),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn fails(data: Vec<Value>) -> bool {
+        let mut caller = TestCaller::new();
+        let value = caller.call_with(&TableFrom.into(), vec![Value::List(data)], Vec::new());
+        matches!(value, Value::None) && caller.failed()
+    }
+
+    #[test]
+    fn map_rows() {
+        let data = vec![
+            str_map(&[("name", "Jane"), ("age", "42")]),
+            str_map(&[("name", "John")]),
+        ];
+
+        assert_eq!(
+            table(data.clone(), Vec::new()),
+            [["age", "name"], ["42", "Jane"], ["", "John"]]
+        );
+        assert_eq!(
+            table(data, vec![("columns", str_list(&["name"]))]),
+            [["name"], ["Jane"], ["John"]]
+        );
+    }
+
+    #[test]
+    fn list_rows() {
+        let data = vec![
+            str_list(&["a", "b"]),
+            str_list(&["c"]),
+            str_list(&["d", "e", "f"]),
+        ];
+
+        assert_eq!(
+            table(data.clone(), Vec::new()),
+            [["a", "b", ""], ["c", "", ""], ["d", "e", "f"]]
+        );
+        assert_eq!(
+            table(data, vec![("columns", str_list(&["x"]))]),
+            [
+                ["x", "", ""],
+                ["a", "b", ""],
+                ["c", "", ""],
+                ["d", "e", "f"]
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_rows() {
+        assert!(fails(Vec::new()));
+        assert!(fails(vec![Value::Int(1)]));
+        assert!(fails(vec![str_map(&[("a", "b")]), str_list(&["c"])]));
+        assert!(fails(vec![str_list(&["c"]), str_map(&[("a", "b")])]));
+    }
+}
@@ -0,0 +1,50 @@
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'target' function which returns the output format of the document.
+///
+/// This struct is used as a type to represent the target function.
+#[derive(Debug, Clone, Copy)]
+pub struct Target;
+
+impl From<Target> for Value {
+    /// Converts the Target struct into a function Value.
+    fn from(_: Target) -> Self {
+        let signature = Signature::new(
+            "Returns the output format the document is compiled to: `html`, `pdf`, `docx` or `json`.",
+            Type::Str,
+        );
+
+        Value::Func(Func::new("target", signature, target))
+    }
+}
+
+/// Returns the name of the output format the document is compiled to.
+///
+/// # Arguments
+///
+/// * `_args` - The bound arguments (none)
+/// * `_source` - Source information (unused)
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `state` - Document state holding the output format
+///
+/// # Returns
+///
+/// A Value::Str containing the name of the output format, e.g. `html`.
+pub fn target(
+    _args: Args,
+    _source: Source,
+    _span: Span,
+    _tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    Value::Str(state.target.name().into())
+}
@@ -0,0 +1,61 @@
+use tyd_syntax::{source::Source, Span};
+
+use crate::{
+    datetime::{self, SOURCE_DATE_EPOCH},
+    func::Func,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'today' function which returns the current date.
+///
+/// The date is fixed by the `SOURCE_DATE_EPOCH` environment variable if it is set,
+/// so that documents displaying it can be built reproducibly.
+#[derive(Debug, Clone, Copy)]
+pub struct Today;
+
+impl From<Today> for Value {
+    /// Converts the Today struct into a function Value.
+    fn from(_: Today) -> Self {
+        let signature = Signature::new(
+            "Returns the current date, or the date of `SOURCE_DATE_EPOCH` if it is set.",
+            Type::Datetime,
+        );
+
+        Value::Func(Func::new("today", signature, today))
+    }
+}
+
+/// Returns the current date in UTC, or the date of the `SOURCE_DATE_EPOCH` timestamp.
+///
+/// # Arguments
+///
+/// * `_args` - The bound arguments (none)
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting an invalid `SOURCE_DATE_EPOCH`
+/// * `_state` - Document state (unused)
+///
+/// # Returns
+///
+/// * `Value::Datetime` containing the date, or `Value::None` if the timestamp is invalid
+pub fn today(
+    _args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    _state: &mut State,
+) -> Value {
+    match datetime::Datetime::today() {
+        Ok(date) => Value::Datetime(date),
+        Err(message) => {
+            tracer.source_error(
+                span,
+                format!("{message}, unset {SOURCE_DATE_EPOCH} to use the current date"),
+            );
+            Value::None
+        }
+    }
+}
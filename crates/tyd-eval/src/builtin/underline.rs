@@ -1,11 +1,12 @@
 use tyd_syntax::{source::Source, Span};
 
 use crate::{
+    func::Func,
     ir,
-    scope::Scope,
-    stack::Stack,
+    signature::{Args, Signature},
+    state::State,
     tracer::Tracer,
-    value::{TypeChecker, Value},
+    value::{Type, Value},
 };
 
 /// Represents the underline function that creates underlined text.
@@ -17,7 +18,10 @@ pub struct Underline;
 impl Into<Value> for Underline {
     /// Converts an Underline instance into a Value containing the underline function.
     fn into(self) -> Value {
-        Value::Func(underline)
+        let signature = Signature::new("Underlines the given content.", Type::Inline)
+            .content("content", "The content to underline");
+
+        Value::Func(Func::new("underline", signature, underline))
     }
 }
 
@@ -25,16 +29,16 @@ impl Into<Value> for Underline {
 ///
 /// # Arguments
 ///
-/// * `stack` - The stack of positional arguments, expected to contain content to be underlined
-/// * `scope` - The scope containing named arguments (none are used by this function)
+/// * `args` - The bound arguments:
+///   * `content` - The content to underline
 /// * `_source` - The source document (unused)
-/// * `span` - The source span where this function is being called from
-/// * `tracer` - Error tracer for reporting issues
+/// * `_span` - Span information (unused)
+/// * `_tracer` - Error tracer (unused)
+/// * `_state` - Document state (unused)
 ///
 /// # Returns
 ///
-/// * `Value::Inline` containing the underlined content if successful
-/// * `Value::None` if required arguments are missing or of incorrect types
+/// * `Value::Inline` containing the underlined content
 ///
 /// # Example
 ///
@@ -42,25 +46,14 @@ impl Into<Value> for Underline {
 /// @underline("text to underline")
 /// ```
 pub fn underline(
-    mut stack: Stack,
-    scope: Scope,
+    mut args: Args,
     _source: Source,
-    span: Span,
-    tracer: &mut Tracer,
+    _span: Span,
+    _tracer: &mut Tracer,
+    _state: &mut State,
 ) -> Value {
-    let mut checker = TypeChecker::new(tracer, span);
-
-    // Extract the content to underline from the first position
-    let content = match checker.pop_from_stack::<ir::Content>(&mut stack, 0) {
-        Some(content) => content,
-        None => return Value::None,
-    };
-
-    // Warn about any unused arguments
-    checker.warn_unknown_positional(stack, 1);
-    checker.warn_unknown_named(scope);
+    let content = args.take::<ir::Content>("content");
 
-    // Create an underlined inline element with the content
     let inline = ir::Inline::Underline(content);
 
     Value::Inline(inline)
@@ -0,0 +1,84 @@
+use ecow::EcoString;
+use tyd_syntax::{error::SourceDiagnostic, source::Source, Span};
+
+use crate::{
+    func::Func,
+    ir, label,
+    render::Target,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::{Type, Value},
+};
+
+/// Represents the 'when' function which includes content for one output format only.
+///
+/// This struct is used as a type to represent the when function.
+#[derive(Debug, Clone, Copy)]
+pub struct When;
+
+impl From<When> for Value {
+    /// Converts the When struct into a function Value.
+    fn from(_: When) -> Self {
+        let signature = Signature::new(
+            "Includes the content only if the document is compiled to the target format.",
+            Type::Content,
+        )
+        .named(
+            "target",
+            Type::Str,
+            "The output format: `html`, `pdf`, `docx` or `json`",
+        )
+        .content("content", "The content to include");
+
+        Value::Func(Func::new("when", signature, when))
+    }
+}
+
+/// Includes content only if the document is compiled to the target format.
+///
+/// # Arguments
+///
+/// * `args` - The bound arguments:
+///   * `target` - The output format the content is included for
+///   * `content` - The content to include
+/// * `_source` - Source information (unused)
+/// * `span` - Source span for error reporting
+/// * `tracer` - Error tracer for reporting unknown formats
+/// * `state` - Document state holding the output format
+///
+/// # Returns
+///
+/// A Value::Content containing the content, which is empty for other formats,
+/// or Value::None if the format is unknown.
+pub fn when(
+    mut args: Args,
+    _source: Source,
+    span: Span,
+    tracer: &mut Tracer,
+    state: &mut State,
+) -> Value {
+    let name = args.take::<EcoString>("target");
+    let content = args.take::<ir::Content>("content");
+
+    let Some(target) = Target::from_name(&name) else {
+        let mut diag = SourceDiagnostic::error(span, format!("Unknown target format '{name}'"));
+
+        let targets: Vec<EcoString> = Target::ALL
+            .iter()
+            .map(|target| target.name().into())
+            .collect();
+
+        if let Some(similar) = label::similar(&name, &targets) {
+            diag.set_help(format!("Did you mean '{similar}'?"));
+        }
+
+        tracer.diagnose_source(diag);
+        return Value::None;
+    };
+
+    match target == state.target {
+        true => Value::Content(content),
+        false => Value::Content(ir::Content::new()),
+    }
+}
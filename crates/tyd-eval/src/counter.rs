@@ -0,0 +1,391 @@
+use ecow::{eco_format, EcoString};
+use std::collections::BTreeMap;
+
+/// The name of the counter numbering headings
+pub const HEADING: &str = "heading";
+/// The name of the counter numbering figures
+pub const FIGURE: &str = "figure";
+/// The name of the counter numbering tables
+pub const TABLE: &str = "table";
+/// The name of the counter numbering equations
+pub const EQUATION: &str = "equation";
+
+/// A symbol in a numbering pattern, which is replaced by a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Symbol {
+    /// Arabic numerals: 1, 2, 3
+    Arabic,
+    /// Lowercase latin letters: a, b, c
+    LowerLatin,
+    /// Uppercase latin letters: A, B, C
+    UpperLatin,
+    /// Lowercase roman numerals: i, ii, iii
+    LowerRoman,
+    /// Uppercase roman numerals: I, II, III
+    UpperRoman,
+}
+
+impl Symbol {
+    /// Returns the symbol for a character of a pattern.
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '1' => Some(Self::Arabic),
+            'a' => Some(Self::LowerLatin),
+            'A' => Some(Self::UpperLatin),
+            'i' => Some(Self::LowerRoman),
+            'I' => Some(Self::UpperRoman),
+            _ => None,
+        }
+    }
+
+    /// Formats a number with this symbol.
+    fn apply(self, n: usize) -> EcoString {
+        match self {
+            Self::Arabic => eco_format!("{n}"),
+            Self::LowerLatin => latin(n).to_lowercase(),
+            Self::UpperLatin => latin(n),
+            Self::LowerRoman => roman(n).to_lowercase(),
+            Self::UpperRoman => roman(n),
+        }
+    }
+}
+
+/// Formats a number as bijective base 26 letters: A, ..., Z, AA, AB, ...
+fn latin(mut n: usize) -> EcoString {
+    if n == 0 {
+        return "0".into();
+    }
+
+    let mut letters = Vec::new();
+
+    while n > 0 {
+        n -= 1;
+        letters.push((b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+
+    letters.into_iter().rev().collect()
+}
+
+/// Formats a number as roman numerals.
+pub(crate) fn roman(mut n: usize) -> EcoString {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    if n == 0 {
+        return "0".into();
+    }
+
+    let mut out = EcoString::new();
+
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+
+    out
+}
+
+/// A pattern describing how the levels of a counter are displayed, e.g. `1.1`, `A.` or `(i)`.
+///
+/// Every counting symbol (`1`, `a`, `A`, `i`, `I`) displays one level, the characters
+/// between them are kept as separators. If a counter has more levels than the pattern
+/// has symbols, the last symbol is repeated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Numbering {
+    /// The characters before the first symbol
+    prefix: EcoString,
+    /// The symbols, each followed by the characters up to the next symbol
+    pieces: Vec<(Symbol, EcoString)>,
+}
+
+impl Numbering {
+    /// Parses a numbering pattern.
+    ///
+    /// # Arguments
+    /// * `pattern` - The pattern, e.g. `1.1`
+    ///
+    /// # Returns
+    /// The numbering, or `None` if the pattern contains no counting symbol
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let mut prefix = EcoString::new();
+        let mut pieces: Vec<(Symbol, EcoString)> = Vec::new();
+
+        for c in pattern.chars() {
+            match (Symbol::from_char(c), pieces.last_mut()) {
+                (Some(symbol), _) => pieces.push((symbol, EcoString::new())),
+                (None, Some((_, suffix))) => suffix.push(c),
+                (None, None) => prefix.push(c),
+            }
+        }
+
+        (!pieces.is_empty()).then_some(Self { prefix, pieces })
+    }
+
+    /// Displays the levels of a counter.
+    ///
+    /// # Arguments
+    /// * `numbers` - The value of each level of the counter
+    pub fn apply(&self, numbers: &[usize]) -> EcoString {
+        let last = self.pieces.len() - 1;
+        let mut out = self.prefix.clone();
+
+        for (i, n) in numbers.iter().enumerate() {
+            let (symbol, _) = self.pieces[i.min(last)];
+            out.push_str(&symbol.apply(*n));
+
+            if i + 1 == numbers.len() {
+                out.push_str(&self.pieces[last].1);
+            } else if i < last {
+                out.push_str(&self.pieces[i].1);
+            } else if last > 0 {
+                out.push_str(&self.pieces[last - 1].1);
+            } else {
+                out.push('.');
+            }
+        }
+
+        out
+    }
+}
+
+/// Counters of the document by name, together with their numbering.
+///
+/// A counter has multiple levels, e.g. the heading counter has one level per heading depth.
+/// Stepping a level resets all deeper levels.
+#[derive(Debug, Clone)]
+pub struct Counters {
+    /// The value of each level of the counters
+    values: BTreeMap<EcoString, Vec<usize>>,
+    /// How the counters are displayed, counters without numbering are not displayed
+    numberings: BTreeMap<EcoString, Numbering>,
+}
+
+impl Default for Counters {
+    /// Creates the counters with figures and equations numbered
+    /// and headings and tables not numbered.
+    fn default() -> Self {
+        let numberings = [(FIGURE, "1"), (EQUATION, "(1)")]
+            .into_iter()
+            .filter_map(|(name, pattern)| Some((name.into(), Numbering::parse(pattern)?)))
+            .collect();
+
+        Self {
+            values: BTreeMap::new(),
+            numberings,
+        }
+    }
+}
+
+impl Counters {
+    /// Steps a level of a counter, resetting all deeper levels.
+    ///
+    /// Missing higher levels are initialized with one.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the counter
+    /// * `level` - The level to step, starting at one
+    ///
+    /// # Returns
+    /// The new value of the counter
+    pub fn step(&mut self, name: &str, level: usize) -> &[usize] {
+        let level = level.max(1);
+        let values = self.values.entry(name.into()).or_default();
+
+        if values.len() < level {
+            values.resize(level, 1);
+            values[level - 1] = 0;
+        }
+
+        values.truncate(level);
+        values[level - 1] += 1;
+
+        values
+    }
+
+    /// Sets a level of a counter to a value, resetting all deeper levels.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the counter
+    /// * `level` - The level to set, starting at one
+    /// * `value` - The new value of the level
+    pub fn set(&mut self, name: &str, level: usize, value: usize) {
+        let level = level.max(1);
+        let values = self.values.entry(name.into()).or_default();
+
+        values.resize(level, 1);
+        values[level - 1] = value;
+    }
+
+    /// Returns the current value of a counter, which is empty if it was never stepped.
+    pub fn get(&self, name: &str) -> &[usize] {
+        self.values.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the numbering of a counter, if it is numbered.
+    pub fn numbering(&self, name: &str) -> Option<&Numbering> {
+        self.numberings.get(name)
+    }
+
+    /// Sets or removes the numbering of a counter.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the counter
+    /// * `numbering` - The new numbering, or `None` to stop displaying the counter
+    pub fn set_numbering(&mut self, name: &str, numbering: Option<Numbering>) {
+        match numbering {
+            Some(numbering) => self.numberings.insert(name.into(), numbering),
+            None => self.numberings.remove(name),
+        };
+    }
+
+    /// Displays the current value of a counter with its numbering.
+    ///
+    /// # Returns
+    /// The displayed value, or `None` if the counter is not numbered
+    pub fn display(&self, name: &str) -> Option<EcoString> {
+        let numbering = self.numbering(name)?;
+        Some(numbering.apply(self.get(name)))
+    }
+
+    /// Displays the current value of a counter with a numbering pattern,
+    /// which overrides the numbering of the counter.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the counter
+    /// * `pattern` - The pattern, an empty pattern to not display the counter,
+    ///   or `None` to use the numbering of the counter
+    ///
+    /// # Returns
+    /// The displayed value, which is `None` if the counter is not displayed,
+    /// or an error message if the pattern is invalid
+    pub fn display_with(
+        &self,
+        name: &str,
+        pattern: Option<&str>,
+    ) -> Result<Option<EcoString>, String> {
+        match pattern {
+            None => Ok(self.display(name)),
+            Some("") => Ok(None),
+            Some(pattern) => match Numbering::parse(pattern) {
+                Some(numbering) => Ok(Some(numbering.apply(self.get(name)))),
+                None => Err(format!("Invalid numbering pattern '{pattern}' for {name}")),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(pattern: &str, numbers: &[usize]) -> EcoString {
+        Numbering::parse(pattern).unwrap().apply(numbers)
+    }
+
+    #[test]
+    fn parse_requires_a_symbol() {
+        assert!(Numbering::parse("").is_none());
+        assert!(Numbering::parse("().-").is_none());
+        assert!(Numbering::parse("(1)").is_some());
+    }
+
+    #[test]
+    fn apply_patterns() {
+        assert_eq!(apply("1", &[3]), "3");
+        assert_eq!(apply("1.1", &[2, 5]), "2.5");
+        assert_eq!(apply("A.", &[2]), "B.");
+        assert_eq!(apply("(i)", &[4]), "(iv)");
+        assert_eq!(apply("I.a", &[9, 1]), "IX.a");
+    }
+
+    #[test]
+    fn apply_repeats_the_last_symbol() {
+        assert_eq!(apply("1", &[1, 2, 3]), "1.2.3");
+        assert_eq!(apply("1.a", &[1, 2, 3]), "1.b.c");
+        assert_eq!(apply("1.1.", &[1, 2, 3]), "1.2.3.");
+    }
+
+    #[test]
+    fn apply_with_fewer_levels_than_symbols() {
+        assert_eq!(apply("1.1", &[2]), "2");
+        assert_eq!(apply("(1.a)", &[2]), "(2)");
+    }
+
+    #[test]
+    fn latin_and_roman_numbers() {
+        assert_eq!(latin(1), "A");
+        assert_eq!(latin(26), "Z");
+        assert_eq!(latin(27), "AA");
+        assert_eq!(latin(0), "0");
+        assert_eq!(roman(1994), "MCMXCIV");
+        assert_eq!(roman(0), "0");
+    }
+
+    #[test]
+    fn step_resets_deeper_levels() {
+        let mut counters = Counters::default();
+
+        assert_eq!(counters.step(HEADING, 1), [1]);
+        assert_eq!(counters.step(HEADING, 2), [1, 1]);
+        assert_eq!(counters.step(HEADING, 2), [1, 2]);
+        assert_eq!(counters.step(HEADING, 1), [2]);
+        assert_eq!(counters.step(HEADING, 3), [2, 1, 1]);
+        assert_eq!(counters.step(HEADING, 0), [3]);
+    }
+
+    #[test]
+    fn set_level() {
+        let mut counters = Counters::default();
+
+        counters.set("theorem", 2, 5);
+        assert_eq!(counters.get("theorem"), [1, 5]);
+        assert_eq!(counters.step("theorem", 2), [1, 6]);
+        assert!(counters.get("unknown").is_empty());
+    }
+
+    #[test]
+    fn default_numberings() {
+        let counters = Counters::default();
+
+        assert!(counters.numbering(HEADING).is_none());
+        assert!(counters.numbering(TABLE).is_none());
+        assert!(counters.numbering(FIGURE).is_some());
+        assert!(counters.numbering(EQUATION).is_some());
+    }
+
+    #[test]
+    fn display_with_patterns() {
+        let mut counters = Counters::default();
+        counters.step(HEADING, 2);
+
+        assert_eq!(counters.display(HEADING), None);
+        assert_eq!(counters.display_with(HEADING, None), Ok(None));
+        assert_eq!(counters.display_with(HEADING, Some("")), Ok(None));
+        assert_eq!(
+            counters.display_with(HEADING, Some("1.a")),
+            Ok(Some("1.a".into()))
+        );
+        assert!(counters.display_with(HEADING, Some("-")).is_err());
+
+        counters.set_numbering(HEADING, Numbering::parse("1.1"));
+        assert_eq!(counters.display(HEADING), Some("1.1".into()));
+        counters.set_numbering(HEADING, None);
+        assert_eq!(counters.display(HEADING), None);
+    }
+}
@@ -0,0 +1,259 @@
+use ecow::{eco_format, EcoString};
+
+use crate::{datetime::Datetime, ir, value::Value};
+
+/// Converts a JSON document into a value.
+///
+/// Objects become maps, arrays lists and `null` becomes `none`.
+pub fn from_json(text: &str) -> Result<Value, String> {
+    let json = serde_json::from_str(text).map_err(|error| error.to_string())?;
+    Ok(json_value(json))
+}
+
+/// Converts a JSON value into a value.
+fn json_value(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::None,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(s) => Value::Str(s.into()),
+        serde_json::Value::Array(items) => Value::List(items.into_iter().map(json_value).collect()),
+        serde_json::Value::Object(entries) => Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), json_value(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Converts a TOML document into a map.
+///
+/// Tables become maps, arrays lists and dates datetimes, except for times
+/// with an offset, which are kept as strings.
+pub fn from_toml(text: &str) -> Result<Value, String> {
+    let table = text
+        .parse::<toml::Table>()
+        .map_err(|error| error.to_string())?;
+    Ok(toml_value(toml::Value::Table(table)))
+}
+
+/// Converts a TOML value into a value.
+fn toml_value(toml: toml::Value) -> Value {
+    match toml {
+        toml::Value::String(s) => Value::Str(s.into()),
+        toml::Value::Integer(i) => Value::Int(i),
+        toml::Value::Float(f) => Value::Float(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => {
+            let text = datetime.to_string();
+
+            match Datetime::parse(&text) {
+                Ok(datetime) => Value::Datetime(datetime),
+                Err(_) => Value::Str(text.into()),
+            }
+        }
+        toml::Value::Array(items) => Value::List(items.into_iter().map(toml_value).collect()),
+        toml::Value::Table(entries) => Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), toml_value(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Converts a YAML document into a value.
+///
+/// Mappings become maps, whose keys must be scalars, sequences lists and `null` becomes `none`.
+pub fn from_yaml(text: &str) -> Result<Value, String> {
+    let yaml = serde_yaml::from_str(text).map_err(|error| error.to_string())?;
+    yaml_value(yaml)
+}
+
+/// Converts a YAML value into a value.
+fn yaml_value(yaml: serde_yaml::Value) -> Result<Value, String> {
+    Ok(match yaml {
+        serde_yaml::Value::Null => Value::None,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_yaml::Value::String(s) => Value::Str(s.into()),
+        serde_yaml::Value::Sequence(items) => Value::List(
+            items
+                .into_iter()
+                .map(yaml_value)
+                .collect::<Result<_, _>>()?,
+        ),
+        serde_yaml::Value::Mapping(entries) => {
+            let mut map = ir::Map::new();
+
+            for (key, value) in entries {
+                map.insert(yaml_key(key)?, yaml_value(value)?);
+            }
+
+            Value::Map(map)
+        }
+        serde_yaml::Value::Tagged(tagged) => yaml_value(tagged.value)?,
+    })
+}
+
+/// Converts a YAML mapping key, which must be a scalar, into a string.
+fn yaml_key(key: serde_yaml::Value) -> Result<EcoString, String> {
+    match key {
+        serde_yaml::Value::String(s) => Ok(s.into()),
+        serde_yaml::Value::Bool(b) => Ok(eco_format!("{b}")),
+        serde_yaml::Value::Number(n) => Ok(eco_format!("{n}")),
+        serde_yaml::Value::Null => Ok("null".into()),
+        _ => Err("mapping keys must be strings, numbers or booleans".to_owned()),
+    }
+}
+
+/// Converts a CSV document into a list of rows.
+///
+/// # Arguments
+/// * `text` - The CSV document
+/// * `delimiter` - The character separating the fields of a row
+/// * `header` - Whether the first row names the columns, so that rows become maps
+///   from the column names to the fields
+///
+/// # Returns
+/// The rows, whose fields are strings, or a message describing the malformed row
+pub fn from_csv(text: &str, delimiter: u8, header: bool) -> Result<Value, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(header)
+        .from_reader(text.as_bytes());
+
+    let columns = match header {
+        true => reader
+            .headers()
+            .map_err(|error| error.to_string())?
+            .iter()
+            .map(EcoString::from)
+            .collect(),
+        false => Vec::new(),
+    };
+
+    let mut rows = Vec::new();
+
+    for record in reader.records() {
+        let record = record.map_err(|error| error.to_string())?;
+        let fields = record.iter().map(|field| Value::Str(field.into()));
+
+        let row = match header {
+            true => Value::Map(columns.iter().cloned().zip(fields).collect()),
+            false => Value::List(fields.collect()),
+        };
+
+        rows.push(row);
+    }
+
+    Ok(Value::List(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(value: Value) -> ir::Map {
+        match value {
+            Value::Map(map) => map,
+            other => panic!("{other:?} is not a map"),
+        }
+    }
+
+    fn list(value: Value) -> ir::List {
+        match value {
+            Value::List(list) => list,
+            other => panic!("{other:?} is not a list"),
+        }
+    }
+
+    #[test]
+    fn json() {
+        let value = map(from_json(r#"{"a": [1, 2.5, null], "b": {"c": true}}"#).unwrap());
+
+        assert!(matches!(
+            list(value["a"].clone())[..],
+            [Value::Int(1), Value::Float(2.5), Value::None]
+        ));
+        assert!(matches!(map(value["b"].clone())["c"], Value::Bool(true)));
+        assert!(matches!(
+            from_json("18446744073709551615").unwrap(),
+            Value::Float(_)
+        ));
+        assert!(from_json("1e400").is_err());
+        assert!(from_json("{\"a\": }").is_err());
+        assert!(from_json("").is_err());
+    }
+
+    #[test]
+    fn toml() {
+        let value = map(from_toml(
+            "title = \"Report\"\n\
+                 date = 2024-05-01\n\
+                 time = 2024-05-01T12:30:00\n\
+                 zoned = 2024-05-01T12:30:00Z\n\
+                 [[authors]]\n\
+                 name = \"Jane\"\n",
+        )
+        .unwrap());
+
+        assert!(matches!(&value["date"], Value::Datetime(Datetime::Date(_))));
+        assert!(matches!(
+            &value["time"],
+            Value::Datetime(Datetime::Datetime(_))
+        ));
+        assert!(matches!(&value["zoned"], Value::Str(s) if s == "2024-05-01T12:30:00Z"));
+        assert!(matches!(&list(value["authors"].clone())[0], Value::Map(_)));
+        assert!(from_toml("key = ").is_err());
+        assert!(map(from_toml("").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn yaml() {
+        let value = map(from_yaml("1: one\ntrue: yes\n~: null\nlist: [a, !tag b]\n").unwrap());
+
+        assert_eq!(
+            value.keys().collect::<Vec<_>>(),
+            ["1", "list", "null", "true"]
+        );
+        assert!(matches!(&value["true"], Value::Str(s) if s == "yes"));
+        assert!(matches!(value["null"], Value::None));
+        assert!(matches!(&list(value["list"].clone())[1], Value::Str(s) if s == "b"));
+        assert!(matches!(from_yaml("").unwrap(), Value::None));
+    }
+
+    #[test]
+    fn yaml_errors() {
+        assert!(from_yaml("[1, 2]: pair\n")
+            .unwrap_err()
+            .contains("mapping keys"));
+        assert!(from_yaml("a: [unclosed\n").is_err());
+    }
+
+    #[test]
+    fn csv() {
+        let rows = list(from_csv("name;age\nJane;42\n\"Doe; John\";7\n", b';', true).unwrap());
+        assert_eq!(rows.len(), 2);
+        assert!(matches!(&map(rows[1].clone())["name"], Value::Str(s) if s == "Doe; John"));
+
+        let rows = list(from_csv("a,b\nc,d\n", b',', false).unwrap());
+        assert_eq!(rows.len(), 2);
+        assert!(matches!(&list(rows[0].clone())[1], Value::Str(s) if s == "b"));
+
+        assert!(list(from_csv("", b',', true).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn csv_rows_of_different_lengths() {
+        assert!(from_csv("a,b\nc\n", b',', true).is_err());
+        assert!(from_csv("a,b\nc\n", b',', false).is_err());
+    }
+}
@@ -0,0 +1,399 @@
+use ecow::{eco_format, EcoString};
+use std::{cmp::Ordering, fmt};
+use time::{format_description, Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+use crate::{
+    func::Func,
+    signature::{Args, Signature},
+    value::{Type, Value},
+};
+
+/// The environment variable fixing the current date for reproducible builds
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// The fields of dates, which are accessed like fields of maps, e.g. `date.year`
+const DATE_FIELDS: &[&str] = &["year", "month", "day", "weekday", "display"];
+
+/// The fields of datetimes, which have a time of day in addition to the fields of dates
+const TIME_FIELDS: &[&str] = &["hour", "minute", "second"];
+
+/// The formats of ISO 8601 strings, which are parsed in order
+const ISO_FORMATS: &[&str] = &[
+    "[year]-[month]-[day]T[hour]:[minute]:[second]",
+    "[year]-[month]-[day] [hour]:[minute]:[second]",
+    "[year]-[month]-[day]T[hour]:[minute]",
+    "[year]-[month]-[day] [hour]:[minute]",
+];
+
+/// A calendar date, optionally with a time of day.
+#[derive(Debug, Clone, Copy)]
+pub enum Datetime {
+    /// A date without a time of day
+    Date(Date),
+    /// A date with a time of day
+    Datetime(PrimitiveDateTime),
+}
+
+impl Datetime {
+    /// Creates a date, or a datetime if any component of the time of day is given.
+    ///
+    /// # Arguments
+    /// * `year`, `month`, `day` - The date, with months and days starting at one
+    /// * `time` - The hour, minute and second of the day, if any
+    ///
+    /// # Returns
+    /// The datetime, or a message describing the invalid component
+    pub fn from_parts(
+        year: i64,
+        month: i64,
+        day: i64,
+        time: Option<(i64, i64, i64)>,
+    ) -> Result<Self, String> {
+        let year = i32::try_from(year).map_err(|_| format!("Year {year} is out of range"))?;
+        let month = u8::try_from(month)
+            .ok()
+            .and_then(|month| Month::try_from(month).ok())
+            .ok_or_else(|| format!("Month {month} is out of range, expected 1 to 12"))?;
+        let date = u8::try_from(day)
+            .ok()
+            .and_then(|day| Date::from_calendar_date(year, month, day).ok())
+            .ok_or_else(|| format!("Day {day} is out of range for {month} {year}"))?;
+
+        let Some((hour, minute, second)) = time else {
+            return Ok(Self::Date(date));
+        };
+
+        let component = |value: i64, name: &str| {
+            u8::try_from(value).map_err(|_| format!("{name} {value} is out of range"))
+        };
+        let time = Time::from_hms(
+            component(hour, "Hour")?,
+            component(minute, "Minute")?,
+            component(second, "Second")?,
+        )
+        .map_err(|error| error.to_string())?;
+
+        Ok(Self::Datetime(PrimitiveDateTime::new(date, time)))
+    }
+
+    /// Parses an ISO 8601 date, e.g. `2024-05-01`, or datetime, e.g. `2024-05-01T12:30:00`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+
+        for format in ISO_FORMATS {
+            let items =
+                format_description::parse_borrowed::<2>(format).expect("ISO formats are valid");
+
+            if let Ok(datetime) = PrimitiveDateTime::parse(text, &items) {
+                return Ok(Self::Datetime(datetime));
+            }
+        }
+
+        let items = format_description::parse_borrowed::<2>("[year]-[month]-[day]")
+            .expect("ISO format is valid");
+
+        Date::parse(text, &items)
+            .map(Self::Date)
+            .map_err(|_| format!("'{text}' is not an ISO 8601 date, e.g. 2024-05-01"))
+    }
+
+    /// Returns the current date in UTC.
+    ///
+    /// If the `SOURCE_DATE_EPOCH` environment variable is set, the date of this
+    /// Unix timestamp is returned instead, so that builds are reproducible.
+    pub fn today() -> Result<Self, String> {
+        let Ok(epoch) = std::env::var(SOURCE_DATE_EPOCH) else {
+            return Ok(Self::Date(OffsetDateTime::now_utc().date()));
+        };
+
+        epoch
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|epoch| OffsetDateTime::from_unix_timestamp(epoch).ok())
+            .map(|datetime| Self::Date(datetime.date()))
+            .ok_or_else(|| format!("{SOURCE_DATE_EPOCH} '{epoch}' is not a Unix timestamp"))
+    }
+
+    /// Returns the date.
+    pub fn date(&self) -> Date {
+        match self {
+            Self::Date(date) => *date,
+            Self::Datetime(datetime) => datetime.date(),
+        }
+    }
+
+    /// Returns the time of day, if any.
+    pub fn time(&self) -> Option<Time> {
+        match self {
+            Self::Date(_) => None,
+            Self::Datetime(datetime) => Some(datetime.time()),
+        }
+    }
+
+    /// Formats the datetime with a pattern, e.g. `[day].[month].[year]`.
+    ///
+    /// Patterns are format descriptions of the `time` crate, whose components
+    /// are written in square brackets, e.g. `[year]`, `[month repr:long]` or `[hour]`.
+    pub fn display(&self, pattern: &str) -> Result<EcoString, String> {
+        let items = format_description::parse_borrowed::<2>(pattern)
+            .map_err(|error| format!("Invalid date pattern: {error}"))?;
+
+        let formatted = match self {
+            Self::Date(date) => date.format(&items),
+            Self::Datetime(datetime) => datetime.format(&items),
+        };
+
+        formatted.map(EcoString::from).map_err(|error| match error {
+            time::error::Format::InsufficientTypeInformation { .. } => {
+                "The pattern displays a time of day, but the date has none".to_owned()
+            }
+            error => error.to_string(),
+        })
+    }
+
+    /// Returns the names of the fields of the datetime.
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> {
+        let time = match self {
+            Self::Date(_) => &[][..],
+            Self::Datetime(_) => TIME_FIELDS,
+        };
+
+        DATE_FIELDS.iter().chain(time).copied()
+    }
+
+    /// Returns a field of the datetime, e.g. its `year`.
+    ///
+    /// The `display` field is a function formatting the datetime with a pattern.
+    pub fn field(&self, name: &str) -> Option<Value> {
+        let date = self.date();
+        let time = self.time();
+
+        let value = match name {
+            "year" => Value::Int(date.year() as i64),
+            "month" => Value::Int(date.month() as i64),
+            "day" => Value::Int(date.day() as i64),
+            "weekday" => Value::Int(date.weekday().number_from_monday() as i64),
+            "hour" => Value::Int(time?.hour() as i64),
+            "minute" => Value::Int(time?.minute() as i64),
+            "second" => Value::Int(time?.second() as i64),
+            "display" => {
+                let signature = Signature::new("Formats the date with a pattern.", Type::Str)
+                    .positional_or(
+                        "pattern",
+                        Type::Str,
+                        "",
+                        "The pattern, e.g. `[day].[month].[year]`, ISO 8601 if empty",
+                    );
+                let datetime = *self;
+
+                Value::Func(Func::new(
+                    "display",
+                    signature,
+                    move |mut args: Args, _, span, tracer, _| {
+                        let pattern = args.take::<EcoString>("pattern");
+
+                        if pattern.is_empty() {
+                            return Value::Str(eco_format!("{datetime}"));
+                        }
+
+                        match datetime.display(&pattern) {
+                            Ok(text) => Value::Str(text),
+                            Err(message) => {
+                                tracer.source_error(span, message);
+                                Value::None
+                            }
+                        }
+                    },
+                ))
+            }
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Returns the datetime, with dates at midnight.
+    fn as_datetime(&self) -> PrimitiveDateTime {
+        match self {
+            Self::Date(date) => date.midnight(),
+            Self::Datetime(datetime) => *datetime,
+        }
+    }
+}
+
+impl PartialEq for Datetime {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Datetime {}
+
+impl PartialOrd for Datetime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Datetime {
+    /// Compares datetimes chronologically, dates are at midnight.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_datetime().cmp(&other.as_datetime())
+    }
+}
+
+impl fmt::Display for Datetime {
+    /// Formats the datetime in ISO 8601, e.g. `2024-05-01` or `2024-05-01T12:30:00`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = self.date();
+        write!(
+            f,
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            date.month() as u8,
+            date.day()
+        )?;
+
+        if let Some(time) = self.time() {
+            write!(
+                f,
+                "T{:02}:{:02}:{:02}",
+                time.hour(),
+                time.minute(),
+                time.second()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::tests::TestCaller;
+
+    fn date(text: &str) -> Datetime {
+        Datetime::parse(text).unwrap()
+    }
+
+    #[test]
+    fn from_parts() {
+        let date = Datetime::from_parts(2024, 2, 29, None).unwrap();
+        assert_eq!(date.to_string(), "2024-02-29");
+
+        let datetime = Datetime::from_parts(2024, 5, 1, Some((9, 5, 0))).unwrap();
+        assert_eq!(datetime.to_string(), "2024-05-01T09:05:00");
+    }
+
+    #[test]
+    fn invalid_parts() {
+        let error =
+            |year, month, day, time| Datetime::from_parts(year, month, day, time).unwrap_err();
+
+        assert_eq!(
+            error(i64::MAX, 1, 1, None),
+            format!("Year {} is out of range", i64::MAX)
+        );
+        assert_eq!(
+            error(2024, 13, 1, None),
+            "Month 13 is out of range, expected 1 to 12"
+        );
+        assert_eq!(
+            error(2024, -1, 1, None),
+            "Month -1 is out of range, expected 1 to 12"
+        );
+        assert_eq!(
+            error(2023, 2, 29, None),
+            "Day 29 is out of range for February 2023"
+        );
+        assert_eq!(
+            error(2024, 1, 1, Some((-1, 0, 0))),
+            "Hour -1 is out of range"
+        );
+        assert!(Datetime::from_parts(2024, 1, 1, Some((24, 0, 0))).is_err());
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(date(" 2024-05-01 ").to_string(), "2024-05-01");
+        assert_eq!(
+            date("2024-05-01T12:30:15").to_string(),
+            "2024-05-01T12:30:15"
+        );
+        assert_eq!(date("2024-05-01 12:30").to_string(), "2024-05-01T12:30:00");
+
+        for text in [
+            "2024-5-1",
+            "01.05.2024",
+            "2024-02-30",
+            "2024-05-01T25:00",
+            "",
+        ] {
+            assert!(Datetime::parse(text).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        let datetime = date("2024-05-01T12:30:00");
+
+        assert_eq!(
+            datetime.display("[day].[month].[year]").unwrap(),
+            "01.05.2024"
+        );
+        assert_eq!(
+            datetime.display("[month repr:long] [hour]h").unwrap(),
+            "May 12h"
+        );
+        assert!(datetime
+            .display("[unknown]")
+            .unwrap_err()
+            .starts_with("Invalid date pattern"));
+        assert_eq!(
+            date("2024-05-01").display("[hour]").unwrap_err(),
+            "The pattern displays a time of day, but the date has none"
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let date = date("2024-05-01");
+        assert_eq!(date.fields().collect::<Vec<_>>(), DATE_FIELDS);
+        assert!(matches!(date.field("weekday"), Some(Value::Int(3))));
+        assert!(date.field("hour").is_none());
+        assert!(date.field("other").is_none());
+
+        let datetime = self::date("2024-05-01T12:30:15");
+        assert_eq!(
+            datetime.fields().count(),
+            DATE_FIELDS.len() + TIME_FIELDS.len()
+        );
+        assert!(matches!(datetime.field("second"), Some(Value::Int(15))));
+    }
+
+    #[test]
+    fn display_field() {
+        let display = date("2024-05-01").field("display").unwrap();
+        let mut caller = TestCaller::new();
+
+        let iso = caller.call_with(&display, Vec::new(), Vec::new());
+        assert!(matches!(iso, Value::Str(s) if s == "2024-05-01"));
+
+        let year = caller.call_with(&display, vec!["[year]".into()], Vec::new());
+        assert!(matches!(year, Value::Str(s) if s == "2024"));
+        assert!(!caller.failed());
+
+        let invalid = caller.call_with(&display, vec!["[hour]".into()], Vec::new());
+        assert!(matches!(invalid, Value::None));
+        assert!(caller.failed());
+    }
+
+    #[test]
+    fn dates_are_at_midnight() {
+        assert_eq!(date("2024-05-01"), date("2024-05-01T00:00"));
+        assert!(date("2024-05-01") < date("2024-05-01T00:01"));
+        assert!(date("2024-05-02") > date("2024-05-01T23:59"));
+    }
+}
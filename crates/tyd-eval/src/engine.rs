@@ -1,17 +1,49 @@
-use ecow::EcoString;
-use std::{collections::BTreeMap, fmt::Debug, mem};
+use ecow::{eco_format, EcoString};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tyd_core::prelude::*;
-use tyd_syntax::{source::Source, Spans};
+use tyd_syntax::{
+    error::SourceDiagnostic,
+    parser::{parse, ParseResult},
+    source::Source,
+    Span, Spans,
+};
 
 use crate::{
-    error::{EngineError, SymbolError, TypeError},
+    counter,
+    error::{ArgumentError, EngineError, PatternError, ReferenceError, SymbolError, TypeError},
+    func::{Caller, Closure},
     ir,
+    label::{self, Target},
+    math, render,
     scope::Scope,
+    show::{self, Selector, ShowRule},
     stack::Stack,
+    state::State,
     tracer::Tracer,
-    value::{Type, TypeCast, Value},
+    value::{Type, TypeCast, TypeChecker, Value},
 };
 
+#[cfg(feature = "highlight")]
+use crate::highlight;
+
+/// The maximum depth of nested macro calls before evaluation is aborted
+pub const MAX_CALL_DEPTH: usize = 64;
+
+/// Control flow requested by a `break` or `continue` expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    /// Exit the innermost loop
+    Break,
+    /// Skip to the next iteration of the innermost loop
+    Continue,
+}
+
 /// Result of running the document processing engine
 ///
 /// Contains the processed Pandoc document and a tracer with potential errors
@@ -63,6 +95,33 @@ pub struct Engine {
 
     /// Span metadata for error reporting
     spans: Spans,
+
+    /// Current depth of nested macro calls
+    depth: usize,
+
+    /// Current depth of nested loops
+    loops: usize,
+
+    /// Control flow requested inside of the innermost loop
+    flow: Option<Flow>,
+
+    /// The global variable scope, used to evaluate imported files
+    global: Scope,
+
+    /// Bindings of already imported files by their path
+    modules: BTreeMap<PathBuf, ir::Map>,
+
+    /// Paths of the files currently being evaluated, used to detect cyclic includes
+    files: Vec<PathBuf>,
+
+    /// Document state shared with native functions, e.g. the counters
+    state: State,
+
+    /// The show rules currently being applied, which do not apply to their own output
+    showing: Vec<Arc<ShowRule>>,
+
+    /// Whether math is converted into MathML instead of being left to pandoc
+    mathml: bool,
 }
 
 impl Engine {
@@ -71,7 +130,8 @@ impl Engine {
     /// # Arguments
     /// * `global_scope` - The global variable scope to use for document processing
     /// * `tracer` - Error tracker for collecting and reporting errors
-    pub fn new(global_scope: Scope, tracer: Tracer) -> Self {
+    /// * `target` - The output format the document is compiled to
+    pub fn new(global_scope: Scope, tracer: Tracer, target: render::Target) -> Self {
         Self {
             inlines: Vec::new(),
             blocks: Vec::new(),
@@ -80,13 +140,37 @@ impl Engine {
             bullet_list: Vec::new(),
             ordered_list: Vec::new(),
             stack: Stack::new(),
-            scope: Scope::new(global_scope),
-            source: tracer.source.clone(),
+            scope: Scope::new(global_scope.clone()),
+            global: global_scope,
+            modules: BTreeMap::new(),
+            files: vec![tracer.source().path().to_path_buf()],
+            source: tracer.source().clone(),
             spans: tracer.spans.clone(),
             tracer,
+            depth: 0,
+            loops: 0,
+            flow: None,
+            state: State {
+                target,
+                ..State::default()
+            },
+            showing: Vec::new(),
+            mathml: false,
         }
     }
 
+    /// Sets whether math is converted into MathML instead of being left to pandoc
+    ///
+    /// Math is then checked while it is evaluated, reporting unsupported commands as
+    /// warnings, and included in the document as raw HTML.
+    ///
+    /// # Arguments
+    /// * `mathml` - Whether math is converted into MathML
+    pub fn with_mathml(mut self, mathml: bool) -> Self {
+        self.mathml = mathml;
+        self
+    }
+
     /// Processes a document and produces a result
     ///
     /// Visits all nodes in the document, applies transformations, and collects
@@ -106,7 +190,7 @@ impl Engine {
         }
 
         let Self {
-            blocks,
+            mut blocks,
             inlines,
             rows,
             definitions,
@@ -117,6 +201,15 @@ impl Engine {
             mut tracer,
             source: _,
             spans: _,
+            depth: _,
+            loops: _,
+            flow: _,
+            global: _,
+            modules: _,
+            files: _,
+            state,
+            showing: _,
+            mathml,
         } = self;
 
         assert!(inlines.is_empty());
@@ -126,6 +219,10 @@ impl Engine {
         assert!(ordered_list.is_empty());
         assert!(stack.is_empty());
 
+        state.labels.check(&state.bibliography, &mut tracer);
+        state.bibliography.check(&mut tracer);
+        state.glossary.check(&mut tracer);
+
         if tracer.has_errors() {
             return EngineResult {
                 pandoc: None,
@@ -150,6 +247,36 @@ impl Engine {
             };
         }
 
+        match scope.get("date") {
+            Some(Value::Datetime(date)) => {
+                meta.insert(
+                    "date".to_owned(),
+                    ir::MetaValue::MetaString(date.to_string()),
+                );
+            }
+            Some(Value::Str(date)) => {
+                meta.insert(
+                    "date".to_owned(),
+                    ir::MetaValue::MetaString(date.to_string()),
+                );
+            }
+            Some(other) => tracer.error(TypeError::WrongType {
+                got: other.ty(),
+                expected: Type::Datetime,
+            }),
+            None => {}
+        }
+
+        state.labels.resolve(&mut blocks);
+        state.bibliography.resolve(&mut blocks, &state.labels);
+        state.glossary.resolve(&mut blocks);
+        state.index.resolve(&mut blocks);
+        state.outline.resolve(&mut blocks);
+
+        if mathml {
+            math::resolve(&mut blocks);
+        }
+
         let pandoc = ir::Pandoc {
             pandoc_api_version: vec![1, 23, 1],
             meta,
@@ -223,239 +350,1177 @@ impl Engine {
     fn replace_ordered_list(&mut self, src: Vec<Vec<ir::Block>>) -> Vec<Vec<ir::Block>> {
         mem::replace(&mut self.ordered_list, src)
     }
-}
 
-impl Visitor for Engine {
-    type Error = Tracer;
-
-    /// Handles error nodes in the document
-    fn visit_error(
+    /// Defines the label of an element, so that it can be referenced
+    ///
+    /// # Arguments
+    /// * `label` - The label of the element
+    /// * `supplement` - The kind of the element displayed by references, e.g. `Section`
+    /// * `number` - The displayed number of the element, if it is numbered
+    /// * `text` - The text displayed by references if the element is not numbered,
+    ///   defaults to the label
+    /// * `doc` - The document containing the label
+    fn define_label(
         &mut self,
-        (error, id): Full<tree::Error>,
-        _doc: &Doc,
-    ) -> Result<(), Self::Error> {
-        self.tracer.node_error(id, error);
-        Ok(())
-    }
-
-    /// Processes raw code blocks
-    fn visit_raw(&mut self, raw: Full<tree::Raw>, doc: &Doc) -> Result<(), Self::Error> {
-        let tree::Raw { lang, text } = raw.0;
-
-        let lang = lang.map(|id| doc.node(id).0.to_string());
-        let attr = ir::AttrBuilder::new().class_opt(lang).build();
-
-        let block = ir::Block::CodeBlock(attr, doc.node(*text).0.to_string());
-        self.blocks.push(block);
+        label: NodeId<tree::Label>,
+        supplement: &str,
+        number: Option<EcoString>,
+        text: Option<EcoString>,
+        doc: &Doc,
+    ) {
+        let name = doc.node(label).0.clone();
+        let target = Target {
+            supplement: supplement.into(),
+            number,
+            text: text.unwrap_or_else(|| name.clone()),
+            span: self.spans.get(label).inner_copied(),
+        };
 
-        Ok(())
+        self.state.labels.define(name, target, &mut self.tracer);
     }
 
-    /// Processes heading elements
-    fn visit_heading(
+    /// Evaluates the arguments of a call in the current scope
+    ///
+    /// # Arguments
+    /// * `args` - The arguments of the call
+    /// * `doc` - The document containing the call
+    ///
+    /// # Returns
+    /// The positional arguments in call order, the named arguments and the content block
+    fn eval_args(
         &mut self,
-        heading: Full<tree::Heading>,
+        args: Full<tree::Args>,
         doc: &Doc,
-    ) -> Result<(), Self::Error> {
-        let tree::Heading {
-            marker,
-            content,
-            label,
-        } = heading.0;
+    ) -> Result<(Vec<Value>, Scope, Option<Value>), Tracer> {
+        let tree::Args { args, content } = args.0;
 
-        let attr = ir::AttrBuilder::new()
-            .ident_opt(label.map(|id| doc.node(id).0.to_string()))
-            .build();
+        let stack = self.stack.take();
 
-        for id in content {
-            self.visit_inline(doc.full(*id), doc)?;
+        self.scope.enter();
+        for id in args {
+            self.visit_arg(doc.full(*id), doc)?;
         }
+        let named = self.scope.exit();
+        let positional = self.stack.take().into_inner();
 
-        let block = ir::Block::Header(doc.node(*marker).0 as i64, attr, self.take_inlines());
-        self.blocks.push(block);
-
-        Ok(())
-    }
-
-    /// Processes table elements
-    fn visit_table(&mut self, table: Full<tree::Table>, doc: &Doc) -> Result<(), Self::Error> {
-        let tree::Table {
-            rows,
-            columns,
-            label,
-        } = table.0;
+        let content = match content {
+            Some(id) => {
+                self.visit_content(doc.full(*id), doc)?;
+                self.stack.pop()
+            }
+            None => None,
+        };
 
-        for id in rows {
-            self.visit_table_row(doc.full(*id), doc)?;
-        }
+        self.stack.replace(stack);
 
-        let rows = self.take_rows();
+        Ok((positional, named, content))
+    }
 
-        let attr = ir::AttrBuilder::new()
-            .ident_opt(label.map(|id| doc.node(id).0.to_string()))
-            .build();
-        let caption = (None, Vec::new());
-        let col_spec = (ir::Alignment::AlignCenter, ir::ColWidth::ColWidthDefault);
-        let col_specs = vec![col_spec; *columns];
-        let head = (ir::AttrBuilder::empty(), Vec::new());
-        let body = vec![(ir::AttrBuilder::empty(), *columns as i64, rows, Vec::new())];
-        let foot = (ir::AttrBuilder::empty(), Vec::new());
+    /// Calls a function or closure and pushes its result onto the stack
+    ///
+    /// # Arguments
+    /// * `callee` - The called value, which must be a function or closure
+    /// * `args` - The arguments of the call
+    /// * `span` - The span of the call for error reporting
+    /// * `doc` - The document containing the call
+    fn call_value(
+        &mut self,
+        callee: Value,
+        args: Full<tree::Args>,
+        span: Span,
+        doc: &Doc,
+    ) -> Result<(), Tracer> {
+        let (positional, named, content) = self.eval_args(args, doc)?;
+        let result = self.apply(callee, positional, named, content, span)?;
 
-        let block = ir::Block::Table(attr, caption, col_specs, head, body, foot);
-        self.blocks.push(block);
+        self.stack.push(result);
         Ok(())
     }
 
-    /// Processes table rows
-    fn visit_table_row(
+    /// Calls a function or closure with evaluated arguments
+    ///
+    /// # Arguments
+    /// * `callee` - The called value, which must be a function or closure
+    /// * `positional` - The positional arguments in call order
+    /// * `named` - The named arguments
+    /// * `content` - The trailing content block, if any
+    /// * `span` - The span of the call for error reporting
+    ///
+    /// # Returns
+    /// The result of the call, or `Value::None` if the value is not callable
+    fn apply(
         &mut self,
-        table_row: Full<tree::TableRow>,
-        doc: &Doc,
-    ) -> Result<(), Self::Error> {
-        let blocks = self.take_blocks();
+        callee: Value,
+        positional: Vec<Value>,
+        named: Scope,
+        content: Option<Value>,
+        span: Span,
+    ) -> Result<Value, Tracer> {
+        match callee {
+            Value::Func(f) => {
+                let defaults = self.scope.defaults(f.name());
+                Ok(f.call(positional, named, content, &defaults, span, self))
+            }
+            Value::Closure(closure) => {
+                self.apply_closure(closure, positional, named, content, span)?;
+                Ok(self.stack.pop().unwrap_or(Value::None))
+            }
+            other => {
+                self.tracer.source_error(
+                    span,
+                    TypeError::WrongType {
+                        got: other.ty(),
+                        expected: Type::Func,
+                    },
+                );
+                Ok(Value::None)
+            }
+        }
+    }
 
-        for id in &table_row.0 .0 {
-            self.visit_block(doc.full(*id), doc)?;
+    /// Calls a user-defined closure with evaluated arguments and pushes its result onto the stack
+    ///
+    /// The body is evaluated in a new scope on top of the scope captured by the closure.
+    /// Parameters without a default value are filled by name or by position,
+    /// parameters with a default value can only be provided by name.
+    ///
+    /// # Arguments
+    /// * `closure` - The closure to call
+    /// * `positional` - The positional arguments in call order
+    /// * `named` - The named arguments
+    /// * `content` - The trailing content block, if any
+    /// * `span` - The span of the call for error reporting
+    fn apply_closure(
+        &mut self,
+        closure: Arc<Closure>,
+        positional: Vec<Value>,
+        mut named: Scope,
+        content: Option<Value>,
+        span: Span,
+    ) -> Result<(), Tracer> {
+        let mut positional = positional.into_iter();
+
+        if self.depth >= MAX_CALL_DEPTH {
+            let message = EngineError::CallDepthExceeded(MAX_CALL_DEPTH).to_string();
+            let diag = SourceDiagnostic::error(span, message).with_help(format!(
+                "Check that the recursion of macro '{}' terminates",
+                closure.name
+            ));
+            self.tracer.diagnose_source(diag);
+            self.stack.push_none();
+            return Ok(());
         }
 
-        let cells = self
-            .replace_blocks(blocks)
-            .into_iter()
-            .map(|block| {
-                (
-                    ir::AttrBuilder::empty(),
-                    ir::Alignment::AlignCenter,
-                    1,
-                    1,
-                    vec![block],
-                )
-            })
-            .collect();
+        let mut scope = Scope::new(closure.scope.clone());
+        scope.insert(closure.name.clone(), closure.clone());
+
+        let mut checker = TypeChecker::new(&mut self.tracer, span);
+        let mut missing = false;
+
+        for (name, default) in &closure.params {
+            let value = match default {
+                Some(default) => named.remove(name).unwrap_or_else(|| default.clone()),
+                None => match named.remove(name).or_else(|| positional.next()) {
+                    Some(value) => value,
+                    None => {
+                        checker.missing_required(name.clone(), Type::Any);
+                        missing = true;
+                        continue;
+                    }
+                },
+            };
 
-        self.rows.push((ir::AttrBuilder::empty(), cells));
-        Ok(())
-    }
+            scope.insert(name.clone(), value);
+        }
 
-    /// Processes unordered lists
-    fn visit_list(&mut self, list: Full<tree::List>, doc: &Doc) -> Result<(), Self::Error> {
-        let bullet_list = self.take_bullet_list();
+        let mut unknown = Stack::new();
 
-        self.walk_list(list, doc)?;
+        match (&closure.content, content) {
+            (Some(name), Some(content)) => {
+                scope.insert(name.clone(), content);
+            }
+            (Some(name), None) => {
+                checker.missing_required(name.clone(), Type::Content);
+                missing = true;
+            }
+            (None, Some(content)) => unknown.push(content),
+            (None, None) => (),
+        }
 
-        let bullet_list = self.replace_bullet_list(bullet_list);
-        let block = ir::Block::BulletList(bullet_list);
-        self.blocks.push(block);
-        Ok(())
-    }
+        let required = closure.params.iter().filter(|(_, d)| d.is_none()).count();
+        positional.for_each(|value| unknown.push(value));
 
-    /// Processes unordered list items
-    fn visit_list_item(
-        &mut self,
-        list_item: Full<tree::ListItem>,
-        doc: &Doc,
-    ) -> Result<(), Self::Error> {
-        let blocks = self.take_blocks();
+        checker.warn_unknown_positional(unknown, required);
+        checker.warn_unknown_named(named);
 
-        for id in &list_item.0 .0 {
-            self.visit_block(doc.full(*id), doc)?;
+        if missing {
+            self.stack.push_none();
+            return Ok(());
         }
 
-        let bullet_point = self.replace_blocks(blocks);
+        let stack = self.stack.take();
+        let scope = mem::replace(&mut self.scope, scope);
+        let loops = mem::take(&mut self.loops);
+        // The body is reported against the file the closure was defined in, e.g. an imported one
+        let spans = mem::replace(&mut self.spans, closure.spans.clone());
+        let source = mem::replace(&mut self.source, closure.source.clone());
+        let tracer_spans = mem::replace(&mut self.tracer.spans, closure.spans.clone());
+        self.depth += 1;
 
-        self.bullet_list.push(bullet_point);
-        Ok(())
-    }
+        let result = self.visit_expr(closure.doc.full(closure.body), &closure.doc);
 
-    /// Processes ordered lists (enumerations)
-    fn visit_enum(&mut self, enumeration: Full<tree::Enum>, doc: &Doc) -> Result<(), Self::Error> {
-        let ordered_list = self.take_ordered_list();
+        self.depth -= 1;
+        self.tracer.spans = tracer_spans;
+        self.source = source;
+        self.spans = spans;
+        self.loops = loops;
+        self.scope = scope;
+        result?;
 
-        self.walk_enum(enumeration, doc)?;
+        let result = self.stack.replace(stack).pop().unwrap_or(Value::None);
+
+        self.stack.push(result);
 
-        let ordered_list = self.replace_ordered_list(ordered_list);
-        let attrs = (1, ir::ListNumberStyle::Decimal, ir::ListNumberDelim::Period);
-        let block = ir::Block::OrderedList(attrs, ordered_list);
-        self.blocks.push(block);
         Ok(())
     }
 
-    /// Processes ordered list items
-    fn visit_enum_item(
+    /// Evaluates the selector of a show rule
+    ///
+    /// # Arguments
+    /// * `selector` - The selector, an identifier naming the kind of element or a string
+    /// * `filter` - The fields the selected elements must have, if any
+    /// * `id` - The show rule node used for error reporting
+    /// * `doc` - The document containing the show rule
+    ///
+    /// # Returns
+    /// The selector, or `None` if it is invalid
+    fn eval_selector(
         &mut self,
-        enum_item: Full<tree::EnumItem>,
+        selector: NodeId<tree::Expr>,
+        filter: Option<NodeId<tree::Args>>,
+        id: NodeId<tree::Show>,
         doc: &Doc,
-    ) -> Result<(), Self::Error> {
-        let blocks = self.take_blocks();
-
-        for id in &enum_item.0 .0 {
-            self.visit_block(doc.full(*id), doc)?;
-        }
-
-        let ordered_point = self.replace_blocks(blocks);
+    ) -> Result<Option<Selector>, Tracer> {
+        let name = match *doc.node(selector) {
+            tree::Expr::Ident(ident) => doc.node(ident).0.clone(),
+            _ => {
+                self.visit_expr(doc.full(selector), doc)?;
+
+                return Ok(match self.stack.pop().unwrap() {
+                    Value::Str(text) if filter.is_none() => Some(Selector::Text(text)),
+                    Value::Str(_) => {
+                        let error = EngineError::UnknownField {
+                            element: "text".into(),
+                            field: "where".into(),
+                        };
+                        self.tracer.node_error(id, error);
+                        None
+                    }
+                    other => {
+                        let error = TypeError::WrongType {
+                            got: other.ty(),
+                            expected: Type::Str,
+                        };
+                        self.tracer.node_error(id, error);
+                        None
+                    }
+                });
+            }
+        };
 
-        self.ordered_list.push(ordered_point);
-        Ok(())
-    }
+        let span = self.spans.get(id).inner_copied();
 
-    /// Processes definition term lists
-    fn visit_terms(&mut self, terms: Full<tree::Terms>, doc: &Doc) -> Result<(), Self::Error> {
-        self.walk_terms(terms, doc)?;
+        let Some((kind, fields)) = show::element(&name) else {
+            let message = EngineError::UnknownElement(name.clone()).to_string();
+            let mut diag = SourceDiagnostic::error(span, message);
+            let names = show::element_names()
+                .map(EcoString::from)
+                .collect::<Vec<_>>();
 
-        let definition_list = self.take_definitions();
-        let block = ir::Block::DefinitionList(definition_list);
-        self.blocks.push(block);
+            if let Some(similar) = label::similar(&name, &names) {
+                diag.set_help(format!("Did you mean '{similar}'?"));
+            }
 
-        Ok(())
-    }
+            self.tracer.diagnose_source(diag);
+            return Ok(None);
+        };
 
-    /// Processes individual definition term items
-    fn visit_term_item(
-        &mut self,
-        term_item: Full<tree::TermItem>,
-        doc: &Doc,
-    ) -> Result<(), Self::Error> {
-        let inlines = self.take_inlines();
+        let Some(filter) = filter else {
+            return Ok(Some(Selector::Kind(kind, ir::Map::new())));
+        };
 
-        let tree::TermItem { term, desc } = term_item.0;
+        let (positional, named, content) = self.eval_args(doc.full(filter), doc)?;
 
-        for id in term {
-            self.visit_inline(doc.full(*id), doc)?;
+        if !positional.is_empty() || content.is_some() {
+            self.tracer.node_error(id, ArgumentError::OnlyNamed);
+            return Ok(None);
         }
 
-        let definition = self.take_inlines();
+        let filter = named.into_inner();
 
-        for id in desc {
-            self.visit_inline(doc.full(*id), doc)?;
+        for field in filter.keys() {
+            if !fields.contains(&field.as_str()) {
+                let error = EngineError::UnknownField {
+                    element: name.clone(),
+                    field: field.clone(),
+                };
+                self.tracer.node_error(id, error);
+                return Ok(None);
+            }
         }
 
-        let body = self.replace_inlines(inlines);
+        Ok(Some(Selector::Kind(kind, filter)))
+    }
 
-        self.definitions
-            .push((definition, vec![vec![ir::Block::Plain(body)]]));
-        Ok(())
+    /// Returns the show rule selecting an element, if any
+    ///
+    /// Rules added later and in nested scopes take precedence, and rules being applied
+    /// are skipped.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of the element
+    /// * `text` - The text of the element, if it is a word
+    /// * `fields` - Computes the fields of the element
+    fn find_show(
+        &self,
+        kind: NodeKind,
+        text: Option<&str>,
+        fields: impl Fn() -> ir::Map,
+    ) -> Option<Arc<ShowRule>> {
+        self.scope
+            .shows()
+            .filter(|rule| !self.showing.iter().any(|active| Arc::ptr_eq(active, rule)))
+            .find(|rule| rule.selector.matches(kind, text, &fields))
+            .cloned()
     }
 
-    /// Processes paragraph blocks
-    fn visit_paragraph(
-        &mut self,
-        paragraph: Full<tree::Paragraph>,
-        doc: &Doc,
-    ) -> Result<(), Self::Error> {
-        self.walk_paragraph(paragraph, doc)?;
+    /// Returns the fields of a block show rules can filter by
+    fn block_fields(block: &tree::Block, doc: &Doc) -> ir::Map {
+        let mut fields = ir::Map::new();
 
-        let block = ir::Block::Para(self.take_inlines());
-        self.blocks.push(block);
-        Ok(())
-    }
+        match *block {
+            tree::Block::Heading(id) => {
+                let tree::Heading { marker, label, .. } = doc.node(id);
+                let level = doc.node(*marker).0 as i64;
+                let label = label.map(|id| doc.node(id).0.clone()).unwrap_or_default();
 
-    /// Processes plain text blocks
-    fn visit_plain(&mut self, plain: Full<tree::Plain>, doc: &Doc) -> Result<(), Self::Error> {
-        self.walk_plain(plain, doc)?;
+                fields.insert("level".into(), Value::Int(level));
+                fields.insert("label".into(), Value::Str(label));
+            }
+            tree::Block::Raw(id) => {
+                let tree::Raw { text, lang } = *doc.node(id);
+                let lang = lang.map(|id| doc.node(id).0.clone()).unwrap_or_default();
 
-        let block = ir::Block::Plain(self.take_inlines());
-        self.blocks.push(block);
-        Ok(())
-    }
+                fields.insert("lang".into(), Value::Str(lang));
+                fields.insert("text".into(), Value::Str(doc.node(text).0.clone()));
+            }
+            tree::Block::Table(id) => {
+                let tree::Table { columns, label, .. } = doc.node(id);
+                let label = label.map(|id| doc.node(id).0.clone()).unwrap_or_default();
+
+                fields.insert("columns".into(), Value::Int(*columns as i64));
+                fields.insert("label".into(), Value::Str(label));
+            }
+            _ => {}
+        }
+
+        fields
+    }
+
+    /// Returns the fields of an inline element show rules can filter by
+    fn inline_fields(inline: &tree::Inline, doc: &Doc) -> ir::Map {
+        let mut fields = ir::Map::new();
+
+        match *inline {
+            tree::Inline::Link(id) => {
+                let url = doc.node(doc.node(id).href).0.clone();
+                fields.insert("url".into(), Value::Str(url));
+            }
+            tree::Inline::Ref(id) => {
+                let label = doc.node(doc.node(id).label).0.clone();
+                fields.insert("label".into(), Value::Str(label));
+            }
+            tree::Inline::RawInline(id) => {
+                let tree::RawInline { text, lang } = *doc.node(id);
+                let lang = lang.map(|id| doc.node(id).0.clone()).unwrap_or_default();
+
+                fields.insert("lang".into(), Value::Str(lang));
+                fields.insert("text".into(), Value::Str(doc.node(text).0.clone()));
+            }
+            tree::Inline::MathInline(id) => {
+                fields.insert("text".into(), Value::Str(doc.node(id).0.clone()));
+            }
+            _ => {}
+        }
+
+        fields
+    }
+
+    /// Applies a show rule to an element
+    ///
+    /// Functions are called with the element as their first argument and with the fields
+    /// of the element as named arguments, for the fields they have parameters for.
+    /// Any other transform replaces the element.
+    ///
+    /// # Arguments
+    /// * `rule` - The show rule to apply
+    /// * `it` - The evaluated element
+    /// * `fields` - The fields of the element
+    /// * `span` - The span of the element for error reporting
+    ///
+    /// # Returns
+    /// The value replacing the element
+    fn apply_show(
+        &mut self,
+        rule: Arc<ShowRule>,
+        it: Value,
+        fields: ir::Map,
+        span: Span,
+    ) -> Result<Value, Tracer> {
+        self.showing.push(rule.clone());
+
+        let result = match &rule.transform {
+            Value::Func(f) => {
+                let mut named = Scope::empty();
+                named.extend(
+                    fields
+                        .into_iter()
+                        .filter(|(name, _)| f.signature().get(name).is_some()),
+                );
+
+                let defaults = self.scope.defaults(f.name());
+                f.call(vec![it], named, None, &defaults, span, self)
+            }
+            Value::Closure(closure) => {
+                let mut named = Scope::empty();
+                named.extend(
+                    fields
+                        .into_iter()
+                        .filter(|(name, _)| closure.params.iter().any(|(param, _)| param == name)),
+                );
+
+                self.apply_closure(closure.clone(), vec![it], named, None, span)?;
+                self.stack.pop().unwrap_or(Value::None)
+            }
+            other => other.clone(),
+        };
+
+        self.showing.pop();
+        Ok(result)
+    }
+
+    /// Transforms a word whose text without the punctuation around it is selected
+    /// by a show rule, e.g. `Rust` of `Rust.`, keeping the punctuation
+    ///
+    /// # Returns
+    /// Whether the word was transformed
+    fn show_word(&mut self, word: &str, span: Span) -> Result<bool, Tracer> {
+        let is_punctuation = |c: char| !c.is_alphanumeric();
+        let core = word.trim_matches(is_punctuation);
+        let prefix = &word[..word.len() - word.trim_start_matches(is_punctuation).len()];
+        let suffix = &word[prefix.len() + core.len()..];
+
+        let rule = (!core.is_empty() && core.len() < word.len())
+            .then(|| self.find_show(NodeKind::Word, Some(core), ir::Map::new))
+            .flatten();
+
+        let Some(rule) = rule else {
+            return Ok(false);
+        };
+
+        if !prefix.is_empty() {
+            self.inlines.push(ir::Inline::Str(prefix.to_owned()));
+        }
+
+        let output = vec![ir::Inline::Str(core.to_owned())];
+        self.push_shown(rule, output, ir::Map::new(), span)?;
+
+        if !suffix.is_empty() {
+            self.inlines.push(ir::Inline::Str(suffix.to_owned()));
+        }
+
+        Ok(true)
+    }
+
+    /// Applies a show rule to an evaluated inline element and appends the result
+    fn push_shown(
+        &mut self,
+        rule: Arc<ShowRule>,
+        output: ir::Content,
+        fields: ir::Map,
+        span: Span,
+    ) -> Result<(), Tracer> {
+        match self.apply_show(rule, Value::Content(output), fields, span)? {
+            Value::Block(_) => self.tracer.source_error(span, EngineError::ExpectedInline),
+            Value::None => {}
+            value => self.inlines.extend(value.into_content()),
+        }
+
+        Ok(())
+    }
+
+    /// Parses and evaluates another file in a nested engine
+    ///
+    /// The nested engine shares the module cache, the document state and the stack of
+    /// files being evaluated, so that cyclic includes are detected. Diagnostics of the
+    /// file are moved into the current tracer, which renders them against the source
+    /// of the file.
+    ///
+    /// # Arguments
+    /// * `id` - The node used for error reporting
+    /// * `path` - The path of the file, relative to the current file
+    /// * `scope` - The scope the file is evaluated in
+    ///
+    /// # Returns
+    /// The blocks and the top-level scope of the evaluated file,
+    /// or None if the file could not be evaluated
+    fn eval_file<T>(
+        &mut self,
+        id: NodeId<T>,
+        path: &Path,
+        scope: Scope,
+    ) -> Option<(Vec<ir::Block>, Scope)> {
+        let path = self.source.work_path().join(path);
+
+        let source = match Source::from_path(&path) {
+            Ok(source) => source,
+            Err(err) => {
+                let error = EngineError::ReadFile {
+                    path: path.display().to_string(),
+                    message: err.to_string(),
+                };
+                self.tracer.node_error(id, error);
+                return None;
+            }
+        };
+
+        let path = source.path().to_path_buf();
+        let name = path.display().to_string();
+
+        if self.files.contains(&path) {
+            self.tracer.node_error(id, EngineError::CyclicInclude(name));
+            return None;
+        }
+
+        let ParseResult { doc, spans, errors } = parse(&source);
+        let tracer = Tracer::with_diagnostics(errors, source, spans);
+
+        let doc = match doc {
+            Some(doc) => doc,
+            None => {
+                self.tracer.append(tracer);
+                self.tracer.node_error(id, EngineError::FileErrors(name));
+                return None;
+            }
+        };
+
+        let mut engine = Engine::new(scope, tracer, self.state.target);
+        engine.global = self.global.clone();
+        engine.modules = mem::take(&mut self.modules);
+        engine.files = mem::take(&mut self.files);
+        engine.state = mem::take(&mut self.state);
+        engine.files.push(path);
+        engine.depth = self.depth;
+        engine.mathml = self.mathml;
+
+        let result = doc.visit_by(&mut engine);
+
+        self.modules = mem::take(&mut engine.modules);
+        self.files = mem::take(&mut engine.files);
+        self.state = mem::take(&mut engine.state);
+        self.files.pop();
+
+        let (tracer, failed) = match result {
+            Ok(()) => {
+                let failed = engine.tracer.has_errors();
+                (engine.tracer, failed)
+            }
+            Err(tracer) => (tracer, true),
+        };
+
+        self.tracer.append(tracer);
+
+        if failed {
+            self.tracer.node_error(id, EngineError::FileErrors(name));
+            return None;
+        }
+
+        Some((engine.blocks, engine.scope))
+    }
+
+    /// Binds a value to a pattern in the current scope.
+    ///
+    /// Lists are destructured by position, maps by key. Mismatches are reported
+    /// as errors and leave the affected identifiers unbound.
+    ///
+    /// # Arguments
+    /// * `id` - The pattern to bind the value to
+    /// * `value` - The value to bind
+    /// * `doc` - The document containing the pattern
+    fn bind_pattern(&mut self, id: NodeId<tree::Pattern>, value: Value, doc: &Doc) {
+        match *doc.node(id) {
+            tree::Pattern::Wildcard => (),
+            tree::Pattern::Ident(ident) => {
+                let name = doc.node(ident).0.clone();
+                self.scope.insert(name, value);
+            }
+            tree::Pattern::Destructure(destructure) => match value {
+                Value::List(list) => self.destructure_list(destructure, list, doc),
+                Value::Map(map) => self.destructure_map(destructure, map, doc),
+                other => self.tracer.node_error(
+                    id,
+                    TypeError::WrongType {
+                        got: other.ty(),
+                        expected: Type::list(Type::Any),
+                    },
+                ),
+            },
+        }
+    }
+
+    /// Destructures a list by position.
+    ///
+    /// A rest item collects all values which are not bound by the items around it.
+    fn destructure_list(&mut self, id: NodeId<tree::Destructure>, list: ir::List, doc: &Doc) {
+        let items = &doc.node(id).0;
+
+        let mut rests = items
+            .iter()
+            .filter(|item| matches!(doc.node(**item), tree::DestructureItem::Rest(_)));
+        let has_rest = rests.next().is_some();
+
+        if let Some(item) = rests.next() {
+            self.tracer.node_error(*item, PatternError::MultipleRest);
+            return;
+        }
+
+        let expected = items.len() - has_rest as usize;
+
+        if list.len() < expected {
+            self.tracer.node_error(
+                id,
+                PatternError::NotEnoughValues {
+                    expected,
+                    got: list.len(),
+                },
+            );
+            return;
+        } else if !has_rest && list.len() > expected {
+            self.tracer.node_error(
+                id,
+                PatternError::TooManyValues {
+                    expected,
+                    got: list.len(),
+                },
+            );
+            return;
+        }
+
+        let mut rest_len = list.len() - expected;
+        let mut values = list.into_iter();
+
+        for item in items {
+            match *doc.node(*item) {
+                tree::DestructureItem::Pattern(pattern) => {
+                    let value = values.next().unwrap();
+                    self.bind_pattern(pattern, value, doc);
+                }
+                tree::DestructureItem::Named(named) => {
+                    let name = doc.node(doc.node(named).name).0.clone();
+                    self.tracer
+                        .node_error(*item, PatternError::NamedInList(name));
+                    values.next();
+                }
+                tree::DestructureItem::Rest(ident) => {
+                    let rest = values.by_ref().take(rest_len).collect::<ir::List>();
+                    rest_len = 0;
+
+                    if let Some(ident) = ident {
+                        let name = doc.node(ident).0.clone();
+                        self.scope.insert(name, rest);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Destructures a map by key.
+    ///
+    /// Identifiers bind the value of the key with the same name, named patterns
+    /// the value of their key and a rest item collects all remaining entries.
+    fn destructure_map(&mut self, id: NodeId<tree::Destructure>, mut map: ir::Map, doc: &Doc) {
+        let items = &doc.node(id).0;
+        let mut rest = None;
+
+        for item in items {
+            let (name, pattern) = match *doc.node(*item) {
+                tree::DestructureItem::Pattern(pattern) => match *doc.node(pattern) {
+                    tree::Pattern::Ident(ident) => (doc.node(ident).0.clone(), pattern),
+                    _ => {
+                        self.tracer.node_error(*item, PatternError::ExpectedKey);
+                        continue;
+                    }
+                },
+                tree::DestructureItem::Named(named) => {
+                    let tree::NamedPattern { name, pattern } = *doc.node(named);
+                    (doc.node(name).0.clone(), pattern)
+                }
+                tree::DestructureItem::Rest(ident) => {
+                    if rest.is_some() {
+                        self.tracer.node_error(*item, PatternError::MultipleRest);
+                    }
+                    rest = Some(ident);
+                    continue;
+                }
+            };
+
+            match map.remove(&name) {
+                Some(value) => self.bind_pattern(pattern, value, doc),
+                None => self
+                    .tracer
+                    .node_error(*item, PatternError::MissingKey(name)),
+            }
+        }
+
+        if let Some(Some(ident)) = rest {
+            let name = doc.node(ident).0.clone();
+            self.scope.insert(name, map);
+        }
+    }
+}
+
+impl Caller for Engine {
+    fn context(&mut self) -> (Source, &mut Tracer, &mut State) {
+        (self.source.clone(), &mut self.tracer, &mut self.state)
+    }
+
+    fn call(&mut self, callee: &Value, args: Vec<Value>, span: Span) -> Value {
+        match self.apply(callee.clone(), args, Scope::empty(), None, span) {
+            Ok(value) => value,
+            Err(tracer) => {
+                self.tracer.append(tracer);
+                Value::None
+            }
+        }
+    }
+}
+
+impl Visitor for Engine {
+    type Error = Tracer;
+
+    /// Processes blocks, transforming them with the show rule selecting them, if any
+    ///
+    /// Headings and paragraphs are passed to the show rule as their content, which is
+    /// replaced unless the rule returns a block. Other blocks are passed as a whole.
+    fn visit_block(&mut self, block: Full<tree::Block>, doc: &Doc) -> Result<(), Self::Error> {
+        let fields = || Self::block_fields(block.0, doc);
+
+        let Some(rule) = self.find_show(block.0.kind(), None, fields) else {
+            return self.walk_block(block, doc);
+        };
+
+        let start = self.blocks.len();
+        self.walk_block(block, doc)?;
+        let mut output = self.blocks.split_off(start);
+
+        if output.is_empty() {
+            return Ok(());
+        }
+
+        let it = match output.as_slice() {
+            [ir::Block::Header(_, _, inlines)
+            | ir::Block::Para(inlines)
+            | ir::Block::Plain(inlines)] => Value::Content(inlines.clone()),
+            [block] => Value::Block(block.clone()),
+            _ => Value::Block(ir::Block::Div(ir::AttrBuilder::empty(), output.clone())),
+        };
+
+        let span = self.spans.get(block.1).inner_copied();
+        let result = self.apply_show(rule, it, fields(), span)?;
+
+        match (result, output.as_mut_slice()) {
+            (Value::Block(block), _) => self.blocks.push(block),
+            (Value::None, _) => {}
+            (
+                value,
+                [ir::Block::Header(_, _, inlines)
+                | ir::Block::Para(inlines)
+                | ir::Block::Plain(inlines)],
+            ) => {
+                *inlines = value.into_content();
+                self.blocks.append(&mut output);
+            }
+            (value, _) => self.blocks.push(ir::Block::Para(value.into_content())),
+        }
+
+        Ok(())
+    }
+
+    /// Processes inline elements, transforming them with the show rule selecting them, if any
+    fn visit_inline(&mut self, inline: Full<tree::Inline>, doc: &Doc) -> Result<(), Self::Error> {
+        let text = match *inline.0 {
+            tree::Inline::Word(id) => Some(doc.node(id).0.as_str()),
+            _ => None,
+        };
+        let fields = || Self::inline_fields(inline.0, doc);
+
+        let span = self.spans.get(inline.1).inner_copied();
+
+        let Some(rule) = self.find_show(inline.0.kind(), text, fields) else {
+            if let Some(word) = text
+                && self.show_word(word, span)?
+            {
+                return Ok(());
+            }
+
+            return self.walk_inline(inline, doc);
+        };
+
+        let start = self.inlines.len();
+        self.walk_inline(inline, doc)?;
+        let output = self.inlines.split_off(start);
+
+        self.push_shown(rule, output, fields(), span)
+    }
+
+    /// Handles error nodes in the document
+    fn visit_error(
+        &mut self,
+        (error, id): Full<tree::Error>,
+        _doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.tracer.node_error(id, error);
+        Ok(())
+    }
+
+    /// Processes raw code blocks
+    fn visit_raw(&mut self, raw: Full<tree::Raw>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Raw { lang, text } = raw.0;
+
+        let lang = lang.map(|id| doc.node(id).0.to_string());
+        let text = doc.node(*text).0.to_string();
+
+        #[cfg(feature = "highlight")]
+        if let Some(block) = lang
+            .as_deref()
+            .and_then(|lang| highlight::block(lang, &text))
+        {
+            self.blocks.push(block);
+            return Ok(());
+        }
+
+        let attr = ir::AttrBuilder::new().class_opt(lang).build();
+
+        let block = ir::Block::CodeBlock(attr, text);
+        self.blocks.push(block);
+
+        Ok(())
+    }
+
+    /// Processes heading elements
+    fn visit_heading(
+        &mut self,
+        heading: Full<tree::Heading>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let tree::Heading {
+            marker,
+            content,
+            label,
+        } = heading.0;
+
+        let level = doc.node(*marker).0;
+        self.state.counters.step(counter::HEADING, level as usize);
+
+        // The numbering set by `#set heading(numbering: ...)` overrides the counter numbering
+        let pattern = match self.scope.defaults("heading").get("numbering") {
+            Some(Value::Str(pattern)) => Some(pattern.clone()),
+            _ => None,
+        };
+
+        let number = self
+            .state
+            .counters
+            .display_with(counter::HEADING, pattern.as_deref())
+            .unwrap_or_else(|message| {
+                self.tracer.node_error(heading.1, message);
+                None
+            });
+
+        if let Some(number) = &number {
+            self.inlines.push(ir::Inline::Str(number.to_string()));
+            self.inlines.push(ir::Inline::Space);
+        }
+
+        let start = self.inlines.len();
+
+        for id in content {
+            self.visit_inline(doc.full(*id), doc)?;
+        }
+
+        if let Some(label) = *label {
+            let text = label::plain_text(&self.inlines[start..]);
+            let text = text.trim().into();
+            self.define_label(label, "Section", number.clone(), Some(text), doc);
+        }
+
+        let ident = self.state.outline.push(
+            level as usize,
+            number,
+            self.inlines[start..].to_vec(),
+            label.map(|id| doc.node(id).0.as_str()),
+        );
+        let attr = ir::AttrBuilder::new().ident(ident).build();
+
+        let block = ir::Block::Header(level as i64, attr, self.take_inlines());
+        self.blocks.push(block);
+
+        Ok(())
+    }
+
+    /// Processes table elements
+    fn visit_table(&mut self, table: Full<tree::Table>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Table {
+            rows,
+            columns,
+            label,
+        } = table.0;
+
+        for id in rows {
+            self.visit_table_row(doc.full(*id), doc)?;
+        }
+
+        let rows = self.take_rows();
+
+        let attr = ir::AttrBuilder::new()
+            .ident_opt(label.map(|id| doc.node(id).0.to_string()))
+            .build();
+        self.state.counters.step(counter::TABLE, 1);
+
+        let number = self.state.counters.display(counter::TABLE);
+
+        if let Some(label) = *label {
+            self.define_label(label, "Table", number.clone(), None, doc);
+        }
+
+        let caption = match number {
+            Some(number) => {
+                let inlines = vec![
+                    ir::Inline::Str("Table".to_owned()),
+                    ir::Inline::Space,
+                    ir::Inline::Str(number.to_string()),
+                ];
+                (None, vec![ir::Block::Plain(inlines)])
+            }
+            None => (None, Vec::new()),
+        };
+        let col_spec = (ir::Alignment::AlignCenter, ir::ColWidth::ColWidthDefault);
+        let col_specs = vec![col_spec; *columns];
+        let head = (ir::AttrBuilder::empty(), Vec::new());
+        let body = vec![(ir::AttrBuilder::empty(), *columns as i64, rows, Vec::new())];
+        let foot = (ir::AttrBuilder::empty(), Vec::new());
+
+        let block = ir::Block::Table(attr, caption, col_specs, head, body, foot);
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    /// Processes table rows
+    fn visit_table_row(
+        &mut self,
+        table_row: Full<tree::TableRow>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let blocks = self.take_blocks();
+
+        for id in &table_row.0 .0 {
+            self.visit_block(doc.full(*id), doc)?;
+        }
+
+        let cells = self
+            .replace_blocks(blocks)
+            .into_iter()
+            .map(|block| {
+                (
+                    ir::AttrBuilder::empty(),
+                    ir::Alignment::AlignCenter,
+                    1,
+                    1,
+                    vec![block],
+                )
+            })
+            .collect();
+
+        self.rows.push((ir::AttrBuilder::empty(), cells));
+        Ok(())
+    }
+
+    /// Processes unordered lists
+    fn visit_list(&mut self, list: Full<tree::List>, doc: &Doc) -> Result<(), Self::Error> {
+        let bullet_list = self.take_bullet_list();
+
+        self.walk_list(list, doc)?;
+
+        let bullet_list = self.replace_bullet_list(bullet_list);
+        let block = ir::Block::BulletList(bullet_list);
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    /// Processes unordered list items
+    fn visit_list_item(
+        &mut self,
+        list_item: Full<tree::ListItem>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let tree::ListItem { content, label } = list_item.0;
+
+        let blocks = self.take_blocks();
+
+        for id in content {
+            self.visit_block(doc.full(*id), doc)?;
+        }
+
+        let mut bullet_point = self.replace_blocks(blocks);
+
+        if let Some(label) = *label {
+            self.define_label(label, "Item", None, None, doc);
+
+            let attr = ir::AttrBuilder::new()
+                .ident(doc.node(label).0.to_string())
+                .build();
+            bullet_point = vec![ir::Block::Div(attr, bullet_point)];
+        }
+
+        self.bullet_list.push(bullet_point);
+        Ok(())
+    }
+
+    /// Processes ordered lists (enumerations)
+    fn visit_enum(&mut self, enumeration: Full<tree::Enum>, doc: &Doc) -> Result<(), Self::Error> {
+        let ordered_list = self.take_ordered_list();
+
+        self.walk_enum(enumeration, doc)?;
+
+        let ordered_list = self.replace_ordered_list(ordered_list);
+        let attrs = (1, ir::ListNumberStyle::Decimal, ir::ListNumberDelim::Period);
+        let block = ir::Block::OrderedList(attrs, ordered_list);
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    /// Processes ordered list items
+    fn visit_enum_item(
+        &mut self,
+        enum_item: Full<tree::EnumItem>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let tree::EnumItem { content, label } = enum_item.0;
+
+        // The number of the item has to be known before nested lists take the items
+        let number = eco_format!("{}", self.ordered_list.len() + 1);
+        let blocks = self.take_blocks();
+
+        for id in content {
+            self.visit_block(doc.full(*id), doc)?;
+        }
+
+        let mut ordered_point = self.replace_blocks(blocks);
+
+        if let Some(label) = *label {
+            self.define_label(label, "Item", Some(number), None, doc);
+
+            let attr = ir::AttrBuilder::new()
+                .ident(doc.node(label).0.to_string())
+                .build();
+            ordered_point = vec![ir::Block::Div(attr, ordered_point)];
+        }
+
+        self.ordered_list.push(ordered_point);
+        Ok(())
+    }
+
+    /// Processes definition term lists
+    ///
+    /// A terms block following a glossary defines its terms and is displayed by the glossary.
+    fn visit_terms(&mut self, terms: Full<tree::Terms>, doc: &Doc) -> Result<(), Self::Error> {
+        let id = terms.1;
+        self.walk_terms(terms, doc)?;
+
+        let definition_list = self.take_definitions();
+
+        if self.state.glossary.is_pending() {
+            for term in self.state.glossary.define(definition_list) {
+                self.tracer
+                    .node_error(id, ReferenceError::DuplicateTerm(term));
+            }
+
+            return Ok(());
+        }
+
+        let block = ir::Block::DefinitionList(definition_list);
+        self.blocks.push(block);
+
+        Ok(())
+    }
+
+    /// Processes individual definition term items
+    fn visit_term_item(
+        &mut self,
+        term_item: Full<tree::TermItem>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let inlines = self.take_inlines();
+
+        let tree::TermItem { term, desc } = term_item.0;
+
+        for id in term {
+            self.visit_inline(doc.full(*id), doc)?;
+        }
+
+        let definition = self.take_inlines();
+
+        for id in desc {
+            self.visit_inline(doc.full(*id), doc)?;
+        }
+
+        let body = self.replace_inlines(inlines);
+
+        self.definitions
+            .push((definition, vec![vec![ir::Block::Plain(body)]]));
+        Ok(())
+    }
+
+    /// Processes paragraph blocks
+    fn visit_paragraph(
+        &mut self,
+        paragraph: Full<tree::Paragraph>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        self.walk_paragraph(paragraph, doc)?;
+
+        let inlines = self.take_inlines();
+
+        // Paragraphs of statements only, e.g. `#let x = 1`, are not displayed
+        if inlines
+            .iter()
+            .all(|inline| matches!(inline, ir::Inline::Space | ir::Inline::SoftBreak))
+        {
+            return Ok(());
+        }
+
+        self.blocks.push(ir::Block::Para(inlines));
+        Ok(())
+    }
+
+    /// Processes plain text blocks
+    fn visit_plain(&mut self, plain: Full<tree::Plain>, doc: &Doc) -> Result<(), Self::Error> {
+        self.walk_plain(plain, doc)?;
+
+        let block = ir::Block::Plain(self.take_inlines());
+        self.blocks.push(block);
+        Ok(())
+    }
 
     /// Processes quoted text
     fn visit_quote(&mut self, quote: Full<tree::Quote>, doc: &Doc) -> Result<(), Self::Error> {
@@ -571,11 +1636,25 @@ impl Visitor for Engine {
     }
 
     /// Processes references to document elements
-    fn visit_ref(&mut self, reference: Full<tree::Ref>, _doc: &Doc) -> Result<(), Self::Error> {
-        let href = format!("#{}", reference.0 .0);
-        let content = vec![ir::Inline::Str(reference.0 .0.to_string())];
+    ///
+    /// The displayed content of references without custom content is filled in
+    /// after evaluation, once the referenced element is known.
+    fn visit_ref(&mut self, reference: Full<tree::Ref>, doc: &Doc) -> Result<(), Self::Error> {
+        let (tree::Ref { label, content }, id) = reference;
 
-        let inline = ir::Inline::Link(ir::AttrBuilder::empty(), content, (href, String::new()));
+        let inlines = self.take_inlines();
+
+        if let Some(content) = content {
+            for id in content {
+                self.visit_inline(doc.full(*id), doc)?;
+            }
+        }
+
+        let content = self.replace_inlines(inlines);
+        let label = doc.node(*label).0.clone();
+        let span = self.spans.get(id).inner_copied();
+
+        let inline = self.state.labels.reference(label, content, span);
         self.inlines.push(inline);
         Ok(())
     }
@@ -584,9 +1663,25 @@ impl Visitor for Engine {
     fn visit_raw_inline(
         &mut self,
         raw_inline: Full<tree::RawInline>,
-        _doc: &Doc,
+        doc: &Doc,
     ) -> Result<(), Self::Error> {
-        let inline = ir::Inline::Code(ir::AttrBuilder::empty(), raw_inline.0 .0.to_string());
+        let tree::RawInline { text, lang } = raw_inline.0;
+
+        let lang = lang.map(|id| doc.node(id).0.to_string());
+        let text = doc.node(*text).0.to_string();
+
+        #[cfg(feature = "highlight")]
+        if let Some(inline) = lang
+            .as_deref()
+            .and_then(|lang| highlight::inline(lang, &text))
+        {
+            self.inlines.push(inline);
+            return Ok(());
+        }
+
+        let attr = ir::AttrBuilder::new().class_opt(lang).build();
+
+        let inline = ir::Inline::Code(attr, text);
         self.inlines.push(inline);
         Ok(())
     }
@@ -597,7 +1692,20 @@ impl Visitor for Engine {
         math_inline: Full<tree::MathInline>,
         _doc: &Doc,
     ) -> Result<(), Self::Error> {
-        let inline = ir::Inline::Math(ir::MathType::InlineMath, math_inline.0 .0.to_string());
+        let text = &math_inline.0 .0;
+
+        if self.mathml {
+            // The span of the math without the enclosing `$`
+            let mut span = self.spans.get(math_inline.1).inner_copied();
+            span.start += 1;
+            span.end -= 1;
+
+            for warning in tyd_syntax::math::parse(text).warnings {
+                self.tracer.diagnose_source(warning.into_diagnostic(span));
+            }
+        }
+
+        let inline = ir::Inline::Math(ir::MathType::InlineMath, text.to_string());
         self.inlines.push(inline);
         Ok(())
     }
@@ -638,6 +1746,11 @@ impl Visitor for Engine {
 
     /// Processes code execution blocks
     fn visit_code(&mut self, (code, id): Full<tree::Code>, doc: &Doc) -> Result<(), Self::Error> {
+        let statement = matches!(
+            doc.node(code.0),
+            tree::Expr::Let(_) | tree::Expr::Macro(_) | tree::Expr::Set(_) | tree::Expr::Show(_)
+        );
+
         self.walk_code((code, id), doc)?;
 
         let inline = match self.stack.pop().unwrap() {
@@ -650,16 +1763,9 @@ impl Visitor for Engine {
 
                 return Ok(());
             }
-            Value::Inline(inline) => inline,
-            Value::Content(content) => ir::Inline::Span(ir::AttrBuilder::empty(), content),
-            Value::Bool(b) => ir::Inline::Str(format!("{b}")),
-            Value::Float(f) => ir::Inline::Str(format!("{f}")),
-            Value::Int(i) => ir::Inline::Str(format!("{i}")),
-            Value::Str(s) => ir::Inline::Str(s.to_string()),
-            Value::List(l) => ir::Inline::Str(format!("{l:?}")),
-            Value::Map(m) => ir::Inline::Str(format!("{m:?}")),
-            Value::None => ir::Inline::Str(format!("{:?}", None::<()>)),
-            Value::Func(f) => ir::Inline::Str(format!("{f:?}")),
+            // Statements, break and continue do not produce any output
+            Value::None if statement || self.flow.is_some() => return Ok(()),
+            value => value.display(),
         };
 
         self.inlines.push(inline);
@@ -681,13 +1787,11 @@ impl Visitor for Engine {
     fn visit_bind(&mut self, bind: Full<tree::Bind>, doc: &Doc) -> Result<(), Self::Error> {
         let tree::Bind { name, value } = bind.0;
 
-        let name = doc.node(*name).0.clone();
-
         self.visit_expr(doc.full(*value), doc)?;
 
         let value = self.stack.pop().unwrap();
 
-        self.scope.insert(name, value);
+        self.bind_pattern(*name, value, doc);
 
         Ok(())
     }
@@ -736,32 +1840,30 @@ impl Visitor for Engine {
 
         self.visit_expr(doc.full(*inside), doc)?;
 
-        // TODO allow maps
-
-        let collection = match self.stack.try_pop::<ir::List>().unwrap() {
-            Ok(list) => list,
+        let collection = match self.stack.pop().unwrap().into_items() {
+            Ok(items) => items,
             Err(got) => {
                 self.stack.push_none();
-                self.tracer.node_error(
-                    id,
-                    TypeError::WrongType {
-                        got,
-                        expected: Type::list(Type::Any),
-                    },
-                );
+                self.tracer.node_error(id, EngineError::NotIterable(got));
                 return Ok(());
             }
         };
 
         let stack = self.stack.take();
+        self.loops += 1;
 
-        let name = doc.node(*el).0.clone();
         for value in collection {
-            self.scope.insert(name.clone(), value);
+            self.bind_pattern(*el, value, doc);
 
             self.visit_content(doc.full(*content), doc)?;
+
+            if let Some(Flow::Break) = self.flow.take() {
+                break;
+            }
         }
 
+        self.loops -= 1;
+
         let content = self
             .stack
             .replace(stack)
@@ -773,39 +1875,233 @@ impl Visitor for Engine {
         Ok(())
     }
 
-    /// Processes function calls
-    fn visit_call(&mut self, (call, id): Full<tree::Call>, doc: &Doc) -> Result<(), Self::Error> {
-        let tree::Call { ident, args } = call;
+    /// Processes break expressions by stopping the innermost loop
+    fn visit_break(&mut self, (_, id): Full<tree::Break>, _doc: &Doc) -> Result<(), Self::Error> {
+        if self.loops == 0 {
+            self.tracer.node_error(id, EngineError::BreakOutsideLoop);
+        } else {
+            self.flow = Some(Flow::Break);
+        }
 
-        let ident = &doc.node(*ident).0;
+        self.stack.push_none();
+        Ok(())
+    }
+
+    /// Processes continue expressions by skipping the rest of the current iteration
+    fn visit_continue(
+        &mut self,
+        (_, id): Full<tree::Continue>,
+        _doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        if self.loops == 0 {
+            self.tracer.node_error(id, EngineError::ContinueOutsideLoop);
+        } else {
+            self.flow = Some(Flow::Continue);
+        }
+
+        self.stack.push_none();
+        Ok(())
+    }
 
-        match self.scope.try_get::<ir::Func>(ident) {
-            Some(Ok(f)) => {
-                let stack = self.stack.take();
+    /// Processes includes by evaluating the file in a new scope on top of the current one
+    ///
+    /// The blocks of the included file are pushed as a single block onto the stack.
+    fn visit_include(
+        &mut self,
+        (include, id): Full<tree::Include>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let tree::Include { path } = include;
 
-                self.scope.enter();
-                self.visit_args(doc.full(*args), doc)?;
+        self.visit_expr(doc.full(*path), doc)?;
 
-                let result = f(
-                    self.stack.take(),
-                    self.scope.exit(),
-                    self.source.clone(),
-                    self.spans.get(id).inner_copied(),
-                    &mut self.tracer,
+        let path = match self.stack.try_pop::<EcoString>().unwrap() {
+            Ok(path) => path,
+            Err(got) => {
+                self.stack.push_none();
+                self.tracer.node_error(
+                    id,
+                    TypeError::WrongType {
+                        got,
+                        expected: Type::Str,
+                    },
                 );
+                return Ok(());
+            }
+        };
 
-                self.stack.replace(stack);
-                self.stack.push(result);
+        match self.eval_file(id, Path::new(path.as_str()), self.scope.clone()) {
+            Some((blocks, _)) => {
+                let block = ir::Block::Div(ir::AttrBuilder::empty(), blocks);
+                self.stack.push(Value::Block(block));
             }
-            Some(Err(got)) => {
+            None => self.stack.push_none(),
+        }
+
+        Ok(())
+    }
+
+    /// Processes imports by evaluating the file in a new scope on top of the global one
+    ///
+    /// The top-level bindings of the imported file are pushed as a map onto the stack.
+    /// Files are evaluated only once, subsequent imports use the cached bindings.
+    fn visit_import(
+        &mut self,
+        (import, id): Full<tree::Import>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let tree::Import { path } = import;
+
+        self.visit_expr(doc.full(*path), doc)?;
+
+        let path = match self.stack.try_pop::<EcoString>().unwrap() {
+            Ok(path) => path,
+            Err(got) => {
+                self.stack.push_none();
                 self.tracer.node_error(
                     id,
                     TypeError::WrongType {
                         got,
+                        expected: Type::Str,
+                    },
+                );
+                return Ok(());
+            }
+        };
+
+        let key = self
+            .source
+            .work_path()
+            .join(path.as_str())
+            .canonicalize()
+            .ok();
+
+        if let Some(module) = key.as_ref().and_then(|key| self.modules.get(key)) {
+            self.stack.push(Value::Map(module.clone()));
+            return Ok(());
+        }
+
+        // The content of imported files is discarded, so it must not step any counters
+        let state = self.state.clone();
+        let result = self.eval_file(id, Path::new(path.as_str()), self.global.clone());
+        self.state = state;
+
+        match result {
+            Some((_, scope)) => {
+                let module = scope.into_inner();
+
+                if let Some(key) = key {
+                    self.modules.insert(key, module.clone());
+                }
+
+                self.stack.push(Value::Map(module));
+            }
+            None => self.stack.push_none(),
+        }
+
+        Ok(())
+    }
+
+    /// Processes set rules, which set default arguments of a builtin function
+    /// for the following calls in the current scope
+    fn visit_set(&mut self, (set, id): Full<tree::Set>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Set { ident, args } = set;
+
+        let ident = &doc.node(*ident).0;
+        let span = self.spans.get(id).inner_copied();
+
+        let func = match self.scope.get(ident) {
+            Some(Value::Func(f)) => f,
+            Some(other) => {
+                self.tracer.node_error(
+                    id,
+                    TypeError::WrongType {
+                        got: other.ty(),
                         expected: Type::Func,
                     },
                 );
                 self.stack.push_none();
+                return Ok(());
+            }
+            None => {
+                self.stack.push_none();
+                self.tracer
+                    .node_error(id, SymbolError::NotFound(ident.clone()));
+                return Ok(());
+            }
+        };
+
+        let (positional, named, content) = self.eval_args(doc.full(*args), doc)?;
+
+        if !positional.is_empty() || content.is_some() {
+            self.tracer.node_error(id, ArgumentError::OnlyNamed);
+        }
+
+        let defaults = named.into_inner();
+
+        if func
+            .signature()
+            .check_defaults(&defaults, span, &mut self.tracer)
+        {
+            self.scope.set_defaults(func.name().clone(), defaults);
+        }
+
+        self.stack.push_none();
+        Ok(())
+    }
+
+    /// Processes show rules, which transform the selected elements following them
+    /// in the current scope
+    fn visit_show(&mut self, (show, id): Full<tree::Show>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Show {
+            selector,
+            filter,
+            param,
+            transform,
+        } = *show;
+
+        let Some(selector) = self.eval_selector(selector, filter, id, doc)? else {
+            self.stack.push_none();
+            return Ok(());
+        };
+
+        // An anonymous transform like `it => [...]` is a closure with a single parameter
+        let transform = match param {
+            Some(param) => Value::Closure(Arc::new(Closure {
+                name: "show".into(),
+                params: vec![(doc.node(param).0.clone(), None)],
+                content: None,
+                body: transform,
+                doc: doc.clone(),
+                spans: self.spans.clone(),
+                source: self.source.clone(),
+                scope: self.scope.clone(),
+            })),
+            None => {
+                self.visit_expr(doc.full(transform), doc)?;
+                self.stack.pop().unwrap()
+            }
+        };
+
+        self.scope.add_show(ShowRule {
+            selector,
+            transform,
+        });
+        self.stack.push_none();
+
+        Ok(())
+    }
+
+    /// Processes function calls
+    fn visit_call(&mut self, (call, id): Full<tree::Call>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Call { ident, args } = call;
+
+        let ident = &doc.node(*ident).0;
+
+        match self.scope.get(ident) {
+            Some(callee) => {
+                let span = self.spans.get(id).inner_copied();
+                self.call_value(callee, doc.full(*args), span, doc)?;
             }
             None => {
                 self.stack.push_none();
@@ -817,6 +2113,122 @@ impl Visitor for Engine {
         Ok(())
     }
 
+    /// Processes accesses to fields of maps, calling the accessed field if arguments are given
+    fn visit_access(
+        &mut self,
+        (access, id): Full<tree::Access>,
+        doc: &Doc,
+    ) -> Result<(), Self::Error> {
+        let tree::Access {
+            ident,
+            fields,
+            args,
+        } = access;
+
+        let mut name = doc.node(*ident).0.clone();
+
+        let Some(mut value) = self.scope.get(&name) else {
+            self.stack.push_none();
+            self.tracer.node_error(id, SymbolError::NotFound(name));
+            return Ok(());
+        };
+
+        for field in fields {
+            let field = &doc.node(*field).0;
+
+            let (found, fields): (_, Vec<EcoString>) = match value {
+                Value::Map(mut map) => (map.remove(field), map.into_keys().collect()),
+                Value::Datetime(datetime) => (
+                    datetime.field(field),
+                    datetime.fields().map(EcoString::from).collect(),
+                ),
+                other => {
+                    let error = TypeError::WrongType {
+                        got: other.ty(),
+                        expected: Type::Map(Vec::new()),
+                    };
+                    self.tracer.node_error(id, error);
+                    self.stack.push_none();
+                    return Ok(());
+                }
+            };
+
+            value = match found {
+                Some(value) => value,
+                None => {
+                    let span = self.spans.get(id).inner_copied();
+                    let error = SymbolError::FieldNotFound {
+                        name: name.clone(),
+                        field: field.clone(),
+                    };
+                    let mut diag = SourceDiagnostic::error(span, error.to_string());
+
+                    if let Some(similar) = label::similar(field, &fields) {
+                        diag.set_help(format!("Did you mean '{similar}'?"));
+                    }
+
+                    self.tracer.diagnose_source(diag);
+                    self.stack.push_none();
+                    return Ok(());
+                }
+            };
+
+            name = eco_format!("{name}.{field}");
+        }
+
+        match args {
+            Some(args) => {
+                let span = self.spans.get(id).inner_copied();
+                self.call_value(value, doc.full(*args), span, doc)?;
+            }
+            None => self.stack.push(value),
+        }
+
+        Ok(())
+    }
+
+    /// Processes macro definitions
+    ///
+    /// Default values of the parameters are evaluated once at the definition site.
+    fn visit_macro(&mut self, macro_: Full<tree::Macro>, doc: &Doc) -> Result<(), Self::Error> {
+        let tree::Macro { name, params, body } = *macro_.0;
+        let tree::Params { params, content } = doc.node(params);
+
+        let mut closure_params = Vec::with_capacity(params.len());
+
+        for id in params {
+            let tree::Param { name, default } = *doc.node(*id);
+
+            let default = match default {
+                Some(id) => {
+                    self.visit_expr(doc.full(id), doc)?;
+                    self.stack.pop()
+                }
+                None => None,
+            };
+
+            closure_params.push((doc.node(name).0.clone(), default));
+        }
+
+        let name = doc.node(name).0.clone();
+
+        let closure = Closure {
+            name: name.clone(),
+            params: closure_params,
+            content: content.map(|id| doc.node(id).0.clone()),
+            body,
+            doc: doc.clone(),
+            spans: self.spans.clone(),
+            source: self.source.clone(),
+            scope: self.scope.clone(),
+        };
+
+        self.scope.insert(name, Arc::new(closure));
+        self.stack.push_none();
+
+        Ok(())
+    }
+
     /// Processes function arguments
     fn visit_arg(&mut self, arg: Full<tree::Arg>, doc: &Doc) -> Result<(), Self::Error> {
         let tree::Arg { name: key, value } = arg.0;
@@ -880,6 +2292,10 @@ impl Visitor for Engine {
 
         for id in &content.0 .0 {
             self.visit_inline(doc.full(*id), doc)?;
+
+            if self.flow.is_some() {
+                break;
+            }
         }
 
         let content = self.replace_inlines(inlines);
@@ -890,3 +2306,403 @@ impl Visitor for Engine {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tyd_syntax::{parser::parse, source::Source};
+
+    use super::*;
+    use crate::builtin::BuiltinPlugin;
+
+    /// Evaluates a source with the builtins in scope
+    fn eval_source(source: Source) -> EngineResult {
+        let ParseResult { doc, spans, errors } = parse(&source);
+        let tracer = Tracer::with_diagnostics(errors, source, spans);
+
+        let mut scope = Scope::empty();
+        scope.register(BuiltinPlugin);
+
+        Engine::new(scope, tracer, render::Target::Json).run(doc.unwrap())
+    }
+
+    /// Evaluates a document and returns its blocks
+    fn eval(text: &str) -> Vec<ir::Block> {
+        let result = eval_source(Source::new("test.tyd", "test.tyd", text));
+        assert!(
+            !result.tracer.has_errors(),
+            "{:?}",
+            result.tracer.into_inner()
+        );
+        result.pandoc.unwrap().blocks
+    }
+
+    /// Evaluates a document and returns the messages and help of its errors
+    fn errors(text: &str) -> Vec<(String, Option<String>)> {
+        let result = eval_source(Source::new("test.tyd", "test.tyd", text));
+        let (diags, _) = result.tracer.into_inner();
+
+        diags
+            .into_iter()
+            .map(|diag| (diag.message, diag.help))
+            .collect()
+    }
+
+    /// Evaluates a document and returns the text of its last paragraph
+    fn last_paragraph(text: &str) -> String {
+        eval(text)
+            .iter()
+            .rev()
+            .find_map(|block| match block {
+                ir::Block::Para(inlines) => Some(label::plain_text(inlines).to_string()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// A temporary directory which is removed when dropped
+    struct TempDir(std::path::PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Writes the files into a new directory and evaluates the first one
+    ///
+    /// The directory is unique to the test and the process and is removed afterwards.
+    fn eval_files(dir: &str, files: &[(&str, &str)]) -> Tracer {
+        let dir = TempDir(std::env::temp_dir().join(format!("{dir}-{}", std::process::id())));
+        fs::create_dir_all(&dir.0).unwrap();
+
+        for (name, content) in files {
+            fs::write(dir.0.join(name), content).unwrap();
+        }
+
+        eval_source(Source::from_path(dir.0.join(files[0].0)).unwrap()).tracer
+    }
+
+    /// Returns the captions of the tables of a document
+    fn table_captions(blocks: &[ir::Block]) -> Vec<Vec<ir::Block>> {
+        blocks
+            .iter()
+            .filter_map(|block| match block {
+                ir::Block::Table(_, (_, caption), ..) => Some(caption.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tables_are_not_numbered_by_default() {
+        let blocks = eval("| a | b |\n\n");
+        assert_eq!(table_captions(&blocks), [Vec::new()]);
+    }
+
+    #[test]
+    fn tables_are_numbered_once_enabled() {
+        let blocks = eval("#numbering(table: \"1\")\n\n| a | b |\n\n| c |\n\n");
+        let captions = table_captions(&blocks);
+
+        let caption = |number: &str| {
+            vec![ir::Block::Plain(vec![
+                ir::Inline::Str("Table".to_owned()),
+                ir::Inline::Space,
+                ir::Inline::Str(number.to_owned()),
+            ])]
+        };
+
+        assert_eq!(captions, [caption("1"), caption("2")]);
+    }
+
+    #[test]
+    fn imported_macro_reports_errors_in_its_own_file() {
+        let filler = "Some filler text to make the library larger than the caller.\n\n".repeat(50);
+        let lib = format!("= Library\n\n{filler}#macro broken(x) = [Value #nope]\n\n");
+
+        let tracer = eval_files(
+            "tyd-engine-imported-macro",
+            &[
+                (
+                    "main.tyd",
+                    "#let lib = import(\"lib.tyd\")\n\nCall #lib.broken(1) here.\n\n",
+                ),
+                ("lib.tyd", &lib),
+            ],
+        );
+
+        let (diags, _) = tracer.into_inner();
+        let diag = diags
+            .iter()
+            .find(|diag| diag.message.contains("nope"))
+            .unwrap();
+
+        assert_eq!(&lib[diag.span.start..diag.span.end], "nope");
+    }
+
+    #[test]
+    fn macros_with_defaults_and_content() {
+        let greet = "#macro greet(name, greeting: \"Hello\") = [#greeting #name!]\n\n";
+
+        assert_eq!(
+            last_paragraph(&format!("{greet}#greet(\"Peter\")\n\n")),
+            "Hello Peter!"
+        );
+        assert_eq!(
+            last_paragraph(&format!("{greet}#greet(\"Peter\", greeting: \"Hi\")\n\n")),
+            "Hi Peter!"
+        );
+        assert_eq!(
+            last_paragraph("#macro note(title)[body] = [#title: #body]\n\n#note(\"A\")[b]\n\n"),
+            "A: b"
+        );
+    }
+
+    #[test]
+    fn macro_definitions_produce_no_output() {
+        let blocks = eval("#macro f(x) = [#x!]\n\n#f(1)\n\n");
+
+        let [ir::Block::Para(inlines)] = blocks.as_slice() else {
+            panic!("unexpected {blocks:?}");
+        };
+        assert_eq!(label::plain_text(inlines), "1!");
+    }
+
+    #[test]
+    fn macros_capture_their_scope() {
+        let text = "#let x = 2\n\n#macro f() = x\n\n#let x = 3\n\n#f()\n\n";
+        assert_eq!(last_paragraph(text), "2");
+    }
+
+    #[test]
+    fn macro_errors() {
+        assert_eq!(
+            errors("#macro f(n) = f(n)\n\n#f(1)\n\n"),
+            [(
+                "Maximum call depth of 64 exceeded".to_owned(),
+                Some("Check that the recursion of macro 'f' terminates".to_owned())
+            )]
+        );
+        assert_eq!(
+            errors("#macro f(name) = [#name]\n\n#f()\n\n"),
+            [("Missing Argument name: Any".to_owned(), None)]
+        );
+    }
+
+    #[test]
+    fn destructuring() {
+        assert_eq!(
+            last_paragraph(
+                "#let (first, _, ..rest) = List(1, 2, 3, 4)\n\n#first #list.len(rest)\n\n"
+            ),
+            "1 2"
+        );
+        assert_eq!(
+            last_paragraph(
+                "#let (name, age: years, ..others) = Map(name: \"p\", age: 32, city: \"B\")\n\n\
+                 #name #years #others.city\n\n"
+            ),
+            "p 32 B"
+        );
+        assert_eq!(
+            last_paragraph("#for (k, v) in Map(a: 1, b: 2) [#k=#v ]\n\n"),
+            "a=1 b=2 "
+        );
+    }
+
+    #[test]
+    fn destructuring_errors() {
+        let error = |text: &str| errors(text).remove(0).0;
+
+        assert_eq!(
+            error("#let (a, b) = List(1)\n\n"),
+            "Not enough values to destructure, expected 2 but got 1"
+        );
+        assert_eq!(
+            error("#let (a, b) = List(1, 2, 3)\n\n"),
+            "Too many values to destructure, expected 2 but got 3"
+        );
+        assert_eq!(
+            error("#let (a, ..b, ..c) = List(1, 2, 3)\n\n"),
+            "Only one rest pattern '..' is allowed"
+        );
+        assert_eq!(
+            error("#let (missing) = Map(name: \"p\")\n\n"),
+            "Key 'missing' not found in map"
+        );
+        assert_eq!(
+            error("#let (a, b) = 5\n\n"),
+            "Wrong type of Int, expected: List Any"
+        );
+    }
+
+    /// Returns the text of the headings of a document
+    fn headings(text: &str) -> Vec<String> {
+        eval(text)
+            .iter()
+            .filter_map(|block| match block {
+                ir::Block::Header(_, _, inlines) => Some(label::plain_text(inlines).to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn set_rules() {
+        assert_eq!(
+            headings("#set heading(numbering: \"1.1\")\n\n= A\n\n== B\n\n"),
+            ["1 A", "1.1 B"]
+        );
+        assert_eq!(
+            headings("#set heading(numbering: \"1\")\n\n#heading(numbering: \"a\")[A]\n\n"),
+            ["a A"]
+        );
+        assert_eq!(
+            headings("#if true then [#set heading(numbering: \"1\")] else []\n\n= A\n\n"),
+            ["A"]
+        );
+    }
+
+    #[test]
+    fn set_rules_produce_no_output() {
+        let blocks = eval(
+            "#set image(width: \"50%\")\n\n#let x = 1\n\nText #set heading(level: 2) here\n\n",
+        );
+
+        let [ir::Block::Para(inlines)] = blocks.as_slice() else {
+            panic!("unexpected {blocks:?}");
+        };
+        assert_eq!(label::plain_text(inlines), "Text  here");
+    }
+
+    #[test]
+    fn set_rule_errors() {
+        let error = |text: &str| errors(text).remove(0).0;
+
+        assert_eq!(error("#set nope(x: 1)\n\n"), "Symbol 'nope' not found");
+        assert_eq!(error("#set heading(nope: 1)\n\n"), "Unknown Argument nope");
+        assert_eq!(
+            error("#set heading(numbering: 1)\n\n"),
+            "Wrong type of Argument numbering: Int, expected: Str"
+        );
+    }
+
+    #[test]
+    fn show_rules() {
+        assert_eq!(headings("#show heading: it => [#it!]\n\n= A\n\n"), ["A!"]);
+        assert_eq!(headings("#show heading: 5\n\n= A\n\n"), ["5"]);
+        assert_eq!(
+            headings("#show heading.where(level: 2): [x]\n\n= A\n\n== B\n\n"),
+            ["A", "x"]
+        );
+    }
+
+    #[test]
+    fn show_rules_select_words_without_punctuation() {
+        let blocks = eval("#show \"Rust\": [/Rust/]\n\nRust, (Rust) and Rusty.\n\n");
+        let Some(ir::Block::Para(inlines)) = blocks.last() else {
+            panic!("unexpected {blocks:?}");
+        };
+
+        let emphasized = inlines
+            .iter()
+            .filter(|inline| matches!(inline, ir::Inline::Emph(_)))
+            .count();
+        assert_eq!(emphasized, 2);
+        assert_eq!(label::plain_text(inlines), "Rust, (Rust) and Rusty.");
+    }
+
+    #[test]
+    fn show_rules_do_not_apply_to_their_output() {
+        assert_eq!(last_paragraph("#show \"a\": [a a]\n\nb a\n\n"), "b a a");
+    }
+
+    #[test]
+    fn show_rules_produce_no_output() {
+        let blocks = eval("#show heading: it => [#it!]\n\n#show \"foo\": [bar]\n\n= A\n\nfoo\n\n");
+
+        let [ir::Block::Header(_, _, title), ir::Block::Para(inlines)] = blocks.as_slice() else {
+            panic!("unexpected {blocks:?}");
+        };
+        assert_eq!(label::plain_text(title), "A!");
+        assert_eq!(label::plain_text(inlines), "bar");
+    }
+
+    #[test]
+    fn show_rule_errors() {
+        assert_eq!(
+            errors("#show nope: [x]\n\n")[0].0,
+            "Unknown element 'nope' in show rule"
+        );
+        assert_eq!(
+            errors("#show raw.where(nope: 1): [x]\n\n")[0].0,
+            "Element 'raw' has no field 'nope'"
+        );
+    }
+
+    #[test]
+    fn content_for_the_target_format() {
+        assert_eq!(
+            last_paragraph("#target() #when(target: \"json\")[yes]#when(target: \"html\")[no]\n\n"),
+            "json yes"
+        );
+        assert_eq!(
+            errors("#when(target: \"htm\")[x]\n\n"),
+            [(
+                "Unknown target format 'htm'".to_owned(),
+                Some("Did you mean 'html'?".to_owned())
+            )]
+        );
+
+        for target in render::Target::ALL {
+            assert_eq!(render::Target::from_name(target.name()), Some(target));
+        }
+    }
+
+    #[test]
+    fn loops() {
+        assert_eq!(last_paragraph("#for c in \"abc\" [#c ]\n\n"), "a b c ");
+        assert_eq!(
+            last_paragraph("#for i in range(1, 10, step: 3) [#i ]\n\n"),
+            "1 4 7 "
+        );
+        assert_eq!(
+            last_paragraph(
+                "#for b in List(false, false, true, false) [#if b then [#break] else [] x]\n\n"
+            ),
+            " x x"
+        );
+        assert_eq!(
+            last_paragraph(
+                "#for b in List(false, true, false) [#if b then [#continue] else [] x]\n\n"
+            ),
+            " x x"
+        );
+    }
+
+    #[test]
+    fn loop_errors() {
+        let error = |text: &str| errors(text).remove(0).0;
+
+        assert_eq!(
+            error("#for i in 5 [#i]\n\n"),
+            "Cannot iterate over value of type 'Int'"
+        );
+        assert_eq!(
+            error("#break\n\n"),
+            "'break' can only be used inside of a loop"
+        );
+        assert_eq!(
+            error("#continue\n\n"),
+            "'continue' can only be used inside of a loop"
+        );
+
+        // Macros called in a loop are not part of it
+        assert_eq!(
+            error("#macro f() = [#break]\n\n#for i in range(1) [#f()]\n\n"),
+            "'break' can only be used inside of a loop"
+        );
+    }
+}
@@ -9,6 +9,97 @@ pub enum EngineError {
     /// Error when an inline element was expected but not found.
     #[error("Expected element of type 'Inline'")]
     ExpectedInline,
+
+    /// Error when nested calls exceed the maximum call depth.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The maximum call depth that was exceeded.
+    #[error("Maximum call depth of {0} exceeded")]
+    CallDepthExceeded(usize),
+
+    /// Error when a value cannot be iterated by a for loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The type of the value that was iterated.
+    #[error("Cannot iterate over value of type '{0}'")]
+    NotIterable(Type),
+
+    /// Error when a file could not be read.
+    ///
+    /// # Fields
+    ///
+    /// * `path` - The path of the file.
+    /// * `message` - The reason why the file could not be read.
+    #[error("Could not read file '{path}': {message}")]
+    ReadFile { path: String, message: String },
+
+    /// Error when the content of a file could not be parsed.
+    ///
+    /// # Fields
+    ///
+    /// * `path` - The path of the file.
+    /// * `message` - The reason why the file could not be parsed.
+    #[error("Could not parse file '{path}': {message}")]
+    ParseFile { path: String, message: String },
+
+    /// Error when a file includes or imports itself, directly or indirectly.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The path of the file.
+    #[error("Cyclic include of file '{0}'")]
+    CyclicInclude(String),
+
+    /// Error when an included or imported file contains errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The path of the file.
+    #[error("File '{0}' contains errors")]
+    FileErrors(String),
+
+    /// Error when `break` is used outside of a loop.
+    #[error("'break' can only be used inside of a loop")]
+    BreakOutsideLoop,
+
+    /// Error when `continue` is used outside of a loop.
+    #[error("'continue' can only be used inside of a loop")]
+    ContinueOutsideLoop,
+
+    /// Error when a glossary is not followed by a terms block.
+    #[error("Expected a terms block after the glossary")]
+    ExpectedTerms,
+
+    /// Error when a show rule selects an element which does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The name of the element.
+    #[error("Unknown element '{0}' in show rule")]
+    UnknownElement(EcoString),
+
+    /// Error when a show rule filters elements by a field they do not have.
+    ///
+    /// # Fields
+    ///
+    /// * `element` - The name of the element.
+    /// * `field` - The name of the field.
+    #[error("Element '{element}' has no field '{field}'")]
+    UnknownField {
+        element: EcoString,
+        field: EcoString,
+    },
+
+    /// Warning when an input variable is named like a function, module or `sys`,
+    /// which it does not replace.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The name of the input variable.
+    #[error("Input '{0}' is only available as 'sys.inputs.{0}', as it would replace a builtin")]
+    HiddenInput(EcoString),
 }
 
 /// Errors related to symbol resolution.
@@ -21,6 +112,15 @@ pub enum SymbolError {
     /// * `0` - The name of the symbol that wasn't found.
     #[error("Symbol '{0}' not found")]
     NotFound(EcoString),
+
+    /// Error when an accessed field of a map does not exist.
+    ///
+    /// # Fields
+    ///
+    /// * `name` - The name of the map.
+    /// * `field` - The name of the missing field.
+    #[error("'{name}' has no field '{field}'")]
+    FieldNotFound { name: EcoString, field: EcoString },
 }
 
 /// Errors related to function or method arguments.
@@ -59,6 +159,118 @@ pub enum ArgumentError {
     /// * `pos` - The position of the unknown argument.
     #[error("Unknown Argument at {pos}")]
     UnknownPositional { pos: usize },
+
+    /// Error when an argument has the wrong type.
+    ///
+    /// # Fields
+    ///
+    /// * `name` - The name of the parameter.
+    /// * `got` - The actual type that was provided.
+    /// * `expected` - The type that was expected.
+    #[error("Wrong type of Argument {name}: {got}, expected: {expected}")]
+    WrongType {
+        name: EcoString,
+        got: Type,
+        expected: Type,
+    },
+
+    /// Error when a set rule or show rule filter has positional arguments or a content block.
+    #[error("Expected only named arguments")]
+    OnlyNamed,
+}
+
+/// Errors related to destructuring values into patterns.
+#[derive(Debug, Clone, Error)]
+pub enum PatternError {
+    /// Error when a list has fewer values than the pattern binds.
+    ///
+    /// # Fields
+    ///
+    /// * `expected` - The number of values the pattern binds.
+    /// * `got` - The number of values in the list.
+    #[error("Not enough values to destructure, expected {expected} but got {got}")]
+    NotEnoughValues { expected: usize, got: usize },
+
+    /// Error when a list has more values than the pattern binds.
+    ///
+    /// # Fields
+    ///
+    /// * `expected` - The number of values the pattern binds.
+    /// * `got` - The number of values in the list.
+    #[error("Too many values to destructure, expected {expected} but got {got}")]
+    TooManyValues { expected: usize, got: usize },
+
+    /// Error when a key of a pattern is missing in the destructured map.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The name of the missing key.
+    #[error("Key '{0}' not found in map")]
+    MissingKey(EcoString),
+
+    /// Error when a named pattern is used to destructure a list.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The key of the named pattern.
+    #[error("Named pattern '{0}' can only destructure maps")]
+    NamedInList(EcoString),
+
+    /// Error when a map is destructured by something other than an identifier.
+    #[error("Expected identifier or named pattern to destructure map")]
+    ExpectedKey,
+
+    /// Error when a pattern contains more than one rest item.
+    #[error("Only one rest pattern '..' is allowed")]
+    MultipleRest,
+}
+
+/// Errors related to labels and references to them.
+#[derive(Debug, Clone, Error)]
+pub enum ReferenceError {
+    /// Error when a reference points to a label that is not defined.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The referenced label.
+    #[error("Unknown label '{0}'")]
+    UnknownLabel(EcoString),
+
+    /// Error when the same label is defined more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The label that was defined again.
+    #[error("Duplicate label '{0}'")]
+    DuplicateLabel(EcoString),
+
+    /// Error when a citation points to a key which is not in the bibliography.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The cited key.
+    #[error("Unknown citation key '{0}'")]
+    UnknownCitation(EcoString),
+
+    /// Error when more than one bibliography is added to a document.
+    #[error("Only one bibliography can be added to a document")]
+    MultipleBibliographies,
+
+    /// Error when a term is used which is not defined by a glossary.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The used term.
+    #[error("Unknown term '{0}'")]
+    UnknownTerm(EcoString),
+
+    /// Error when the same term is defined by a glossary more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `0` - The term that was defined again.
+    #[error("Duplicate term '{0}'")]
+    DuplicateTerm(EcoString),
 }
 
 /// Errors related to type mismatches.
@@ -0,0 +1,284 @@
+use ecow::EcoString;
+use std::{fmt, sync::Arc};
+use tyd_core::prelude::*;
+use tyd_syntax::{source::Source, Span, Spans};
+
+use crate::{
+    ir,
+    scope::Scope,
+    signature::{Args, Signature},
+    state::State,
+    tracer::Tracer,
+    value::Value,
+};
+
+/// The implementation of a native function, which is called with the arguments
+/// bound to its signature, the source, the span of the call, the tracer and the
+/// state of the document.
+pub type NativeFn = dyn Fn(Args, Source, Span, &mut Tracer, &mut State) -> Value + Send + Sync;
+
+/// The implementation of a higher-order native function, which is called with the
+/// arguments bound to its signature, the span of the call and the caller, which
+/// calls the functions and closures passed as arguments.
+pub type HigherFn = dyn Fn(Args, Span, &mut dyn Caller) -> Value + Send + Sync;
+
+/// The context native functions are called in, which is implemented by the engine.
+pub trait Caller {
+    /// Returns the source of the call, the tracer and the state of the document.
+    fn context(&mut self) -> (Source, &mut Tracer, &mut State);
+
+    /// Calls a function or closure with positional arguments and returns its result.
+    ///
+    /// Values which are not callable are reported as type errors and return `Value::None`.
+    fn call(&mut self, callee: &Value, args: Vec<Value>, span: Span) -> Value;
+}
+
+/// The implementation of a native function.
+#[derive(Clone)]
+enum Implementation {
+    /// A function which only uses its arguments and the document state
+    Native(Arc<NativeFn>),
+    /// A function which calls other functions
+    Higher(Arc<HigherFn>),
+}
+
+/// A named function implemented in Rust.
+///
+/// Native functions may capture configuration or state, e.g. of the plugin
+/// which registered them, and are cheap to clone.
+#[derive(Clone)]
+pub struct Func {
+    /// The name the function is known by
+    name: EcoString,
+    /// The declared parameters of the function
+    signature: Arc<Signature>,
+    /// The implementation of the function
+    func: Implementation,
+}
+
+impl Func {
+    /// Creates a new native function.
+    ///
+    /// # Arguments
+    /// * `name` - The name the function is known by
+    /// * `signature` - The declared parameters of the function
+    /// * `func` - The implementation of the function
+    pub fn new(
+        name: impl Into<EcoString>,
+        signature: Signature,
+        func: impl Fn(Args, Source, Span, &mut Tracer, &mut State) -> Value + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            signature: Arc::new(signature),
+            func: Implementation::Native(Arc::new(func)),
+        }
+    }
+
+    /// Creates a new higher-order native function, which can call functions passed as arguments.
+    ///
+    /// # Arguments
+    /// * `name` - The name the function is known by
+    /// * `signature` - The declared parameters of the function
+    /// * `func` - The implementation of the function
+    pub fn higher(
+        name: impl Into<EcoString>,
+        signature: Signature,
+        func: impl Fn(Args, Span, &mut dyn Caller) -> Value + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            signature: Arc::new(signature),
+            func: Implementation::Higher(Arc::new(func)),
+        }
+    }
+
+    /// Returns the name of the function.
+    pub fn name(&self) -> &EcoString {
+        &self.name
+    }
+
+    /// Returns the declared parameters of the function.
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// Binds the arguments to the signature and calls the function.
+    ///
+    /// # Arguments
+    /// * `positional` - The positional arguments in call order
+    /// * `named` - The named arguments
+    /// * `content` - The trailing content block, if any
+    /// * `defaults` - The default arguments set by set rules
+    /// * `span` - The span of the call
+    /// * `caller` - The context of the call
+    ///
+    /// # Returns
+    /// The value returned by the function, or `Value::None` if the arguments did not match
+    pub fn call(
+        &self,
+        positional: Vec<Value>,
+        named: Scope,
+        content: Option<Value>,
+        defaults: &ir::Map,
+        span: Span,
+        caller: &mut dyn Caller,
+    ) -> Value {
+        let (_, tracer, _) = caller.context();
+
+        let Some(args) = self
+            .signature
+            .bind(positional, named, content, defaults, span, tracer)
+        else {
+            return Value::None;
+        };
+
+        match &self.func {
+            Implementation::Native(func) => {
+                let (source, tracer, state) = caller.context();
+                func(args, source, span, tracer, state)
+            }
+            Implementation::Higher(func) => func(args, span, caller),
+        }
+    }
+}
+
+impl fmt::Debug for Func {
+    /// Formats the function by its name, omitting the implementation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Func").field(&self.name).finish()
+    }
+}
+
+impl fmt::Display for Func {
+    /// Formats the function as `name()`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}()", self.name)
+    }
+}
+
+/// A user-defined function created by a `macro` definition.
+///
+/// Closures capture the scope they were defined in, so that they can refer
+/// to bindings which are visible at the definition site when called later on.
+#[derive(Clone)]
+pub struct Closure {
+    /// The name the closure was defined with
+    pub name: EcoString,
+    /// The parameters of the closure, with their evaluated default values
+    pub params: Vec<(EcoString, Option<Value>)>,
+    /// The name of the parameter receiving the content block, if any
+    pub content: Option<EcoString>,
+    /// The expression evaluated when the closure is called
+    pub body: NodeId<tree::Expr>,
+    /// The document the body expression belongs to
+    pub doc: Doc,
+    /// The spans of the document the closure was defined in
+    pub spans: Spans,
+    /// The source of the document the closure was defined in
+    pub source: Source,
+    /// The scope captured at the definition site
+    pub scope: Scope,
+}
+
+impl fmt::Debug for Closure {
+    /// Formats the closure by its signature, omitting the captured scope and document.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Closure")
+            .field("name", &self.name)
+            .field("params", &self.params)
+            .field("content", &self.content)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{builtin::tests::TestCaller, value::Type};
+
+    /// Creates a function adding one to its argument, which counts its calls
+    fn increment(calls: Arc<AtomicUsize>) -> Value {
+        let signature = Signature::new("Adds one", Type::Int).positional("n", Type::Int, "");
+
+        Value::Func(Func::new(
+            "increment",
+            signature,
+            move |mut args, _, _, _, _| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                Value::Int(args.take::<i64>("n") + 1)
+            },
+        ))
+    }
+
+    #[test]
+    fn native_functions_capture_state() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let func = increment(calls.clone());
+        let mut caller = TestCaller::new();
+
+        assert!(matches!(
+            caller.call_with(&func, vec![Value::Int(1)], Vec::new()),
+            Value::Int(2)
+        ));
+        assert!(matches!(
+            caller.call_with(&func.clone(), vec![Value::Int(2)], Vec::new()),
+            Value::Int(3)
+        ));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn unbound_arguments_are_not_passed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let func = increment(calls.clone());
+        let mut caller = TestCaller::new();
+
+        let value = caller.call_with(&func, vec![Value::Str("1".into())], Vec::new());
+
+        assert!(matches!(value, Value::None));
+        assert!(caller.failed());
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn higher_functions_call_their_arguments() {
+        let signature = Signature::new("Calls twice", Type::Any)
+            .positional("f", Type::Any, "")
+            .positional("n", Type::Int, "");
+
+        let twice = Value::Func(Func::higher(
+            "twice",
+            signature,
+            |mut args, span, caller| {
+                let f = args.take::<Value>("f");
+                let once = caller.call(&f, vec![args.take::<Value>("n")], span);
+                caller.call(&f, vec![once], span)
+            },
+        ));
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut caller = TestCaller::new();
+        let value = caller.call_with(
+            &twice,
+            vec![increment(calls.clone()), Value::Int(1)],
+            Vec::new(),
+        );
+
+        assert!(matches!(value, Value::Int(3)));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn formatted_by_name() {
+        let Value::Func(func) = increment(Arc::default()) else {
+            unreachable!()
+        };
+
+        assert_eq!(func.name(), "increment");
+        assert_eq!(func.to_string(), "increment()");
+        assert_eq!(format!("{func:?}"), "Func(\"increment\")");
+    }
+}
@@ -0,0 +1,655 @@
+use ecow::EcoString;
+use std::collections::BTreeSet;
+use tyd_syntax::{error::SourceDiagnostic, Span};
+
+use crate::{
+    error::{EngineError, ReferenceError},
+    ir,
+    label::{self, plain_text},
+    outline::slug,
+    tracer::Tracer,
+};
+
+/// The class of the blocks created for glossaries, which are filled after evaluation
+const GLOSSARY_CLASS: &str = "glossary";
+
+/// The class of links to the definition of a term
+const TERM_CLASS: &str = "gls";
+
+/// A term defined by a glossary.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// The key the term is used with, which is the abbreviation for abbreviations
+    key: EcoString,
+    /// The long form of an abbreviation, displayed on its first use
+    long: Option<EcoString>,
+    /// The term as it was written
+    term: ir::Content,
+    /// The description of the term
+    desc: Vec<Vec<ir::Block>>,
+    /// The identifier of the definition to link to
+    ident: String,
+    /// The index of the glossary defining the term
+    glossary: usize,
+}
+
+impl Entry {
+    /// Returns the content displayed for a use of the term.
+    ///
+    /// Abbreviations are expanded on their first use, e.g. `Application Programming Interface (API)`.
+    fn display(&self, text: &str, first: bool) -> ir::Content {
+        match &self.long {
+            Some(long) if first => {
+                let mut content = words(long);
+                content.push(ir::Inline::Space);
+                content.push(ir::Inline::Str(format!("({})", self.key)));
+                content
+            }
+            _ => words(text),
+        }
+    }
+
+    /// Returns whether a text is an occurrence of the term.
+    ///
+    /// Abbreviations have to match exactly, other terms regardless of case.
+    fn matches(&self, text: &str) -> bool {
+        if self.long.is_some() {
+            text == self.key
+        } else {
+            text.to_lowercase() == self.key.to_lowercase()
+        }
+    }
+}
+
+/// How a glossary is displayed.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The title displayed above the glossary, empty for none
+    pub title: EcoString,
+    /// Whether later occurrences of the terms are linked to their definition
+    pub link: bool,
+}
+
+/// The glossaries of the document and the uses of their terms.
+///
+/// A glossary takes the terms block following it. Terms may be used before they are
+/// defined, so uses are inserted as placeholders and displayed once all terms are known.
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    /// The defined terms
+    entries: Vec<Entry>,
+    /// The options of each glossary, indexed by their placeholder
+    glossaries: Vec<Options>,
+    /// The glossary waiting for its terms block, together with the span of its call
+    pending: Option<(usize, Span)>,
+    /// The used keys together with the span of the use
+    uses: Vec<(EcoString, Span)>,
+}
+
+impl Glossary {
+    /// Creates a placeholder for a glossary, which takes the next terms block of the document
+    /// and is filled by `Glossary::resolve`.
+    ///
+    /// # Arguments
+    /// * `options` - How the glossary is displayed
+    /// * `span` - The span of the glossary call
+    pub fn placeholder(&mut self, options: Options, span: Span) -> ir::Block {
+        let index = self.glossaries.len();
+        let attr = ir::AttrBuilder::new()
+            .class(GLOSSARY_CLASS)
+            .attr("index", index.to_string())
+            .build();
+
+        self.glossaries.push(options);
+        self.pending = Some((index, span));
+        ir::Block::Div(attr, Vec::new())
+    }
+
+    /// Returns whether a glossary is waiting for its terms block.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Defines the terms of the pending glossary.
+    ///
+    /// A term written like `API (Application Programming Interface)` is an abbreviation,
+    /// the shorter of both forms being the abbreviation.
+    ///
+    /// # Arguments
+    /// * `definitions` - The terms and descriptions of the terms block
+    ///
+    /// # Returns
+    /// The terms which were already defined
+    pub fn define(&mut self, definitions: Vec<ir::Definition>) -> Vec<EcoString> {
+        let Some((glossary, _)) = self.pending.take() else {
+            return Vec::new();
+        };
+
+        let mut duplicates = Vec::new();
+
+        for (term, desc) in definitions {
+            let text = plain_text(&term);
+            let text = text.trim();
+
+            let (key, long) = match text
+                .strip_suffix(')')
+                .and_then(|text| text.split_once(" ("))
+            {
+                Some((first, second)) if first.len() <= second.len() => (first, Some(second)),
+                Some((first, second)) => (second, Some(first)),
+                None => (text, None),
+            };
+
+            if self.get(key).is_some() {
+                duplicates.push(key.into());
+                continue;
+            }
+
+            self.entries.push(Entry {
+                key: key.into(),
+                long: long.map(EcoString::from),
+                term,
+                desc,
+                ident: format!("gls-{}", slug(key)),
+                glossary,
+            });
+        }
+
+        duplicates
+    }
+
+    /// Creates a use of a term, which is displayed by `Glossary::resolve`.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the used term
+    /// * `span` - The span of the use
+    pub fn reference(&mut self, key: EcoString, span: Span) -> ir::Inline {
+        let attr = ir::AttrBuilder::new()
+            .class(TERM_CLASS)
+            .attr("term", key.clone())
+            .build();
+
+        self.uses.push((key, span));
+        ir::Inline::Link(attr, Vec::new(), ("#".to_owned(), String::new()))
+    }
+
+    /// Reports an error for every use of a term which is not defined,
+    /// suggesting the most similar term, and for a glossary without terms block.
+    pub fn check(&self, tracer: &mut Tracer) {
+        if let Some((_, span)) = self.pending {
+            tracer.source_error(span, EngineError::ExpectedTerms);
+        }
+
+        for (key, span) in &self.uses {
+            if self.get(key).is_some() {
+                continue;
+            }
+
+            let error = ReferenceError::UnknownTerm(key.clone());
+            let mut diag = SourceDiagnostic::error(*span, error.to_string());
+
+            if let Some(similar) = label::similar(key, self.entries.iter().map(|e| &e.key)) {
+                diag.set_help(format!("Did you mean '{similar}'?"));
+            }
+
+            tracer.diagnose_source(diag);
+        }
+    }
+
+    /// Fills the glossaries and the uses of terms, and links later occurrences of the terms.
+    pub fn resolve(&self, blocks: &mut Vec<ir::Block>) {
+        let mut resolver = Resolver {
+            glossary: self,
+            active: Vec::new(),
+            used: BTreeSet::new(),
+            linking: true,
+        };
+
+        ir::MutVisitor::visit_vec_block(&mut resolver, blocks);
+    }
+
+    /// Returns the term with the given key.
+    fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.key == key)
+    }
+
+    /// Renders the glossary with the given index, sorting its terms alphabetically.
+    fn render(&self, index: usize, options: &Options) -> Vec<ir::Block> {
+        let mut blocks = Vec::new();
+
+        if !options.title.is_empty() {
+            let attr = ir::AttrBuilder::new().class("unnumbered").build();
+            let title = vec![ir::Inline::Str(options.title.to_string())];
+            blocks.push(ir::Block::Header(1, attr, title));
+        }
+
+        let mut entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.glossary == index)
+            .collect::<Vec<_>>();
+
+        entries.sort_by_key(|entry| entry.key.to_lowercase());
+
+        let definitions = entries
+            .into_iter()
+            .map(|entry| {
+                let attr = ir::AttrBuilder::new().ident(entry.ident.clone()).build();
+                let term = vec![ir::Inline::Span(attr, entry.term.clone())];
+                (term, entry.desc.clone())
+            })
+            .collect::<Vec<_>>();
+
+        if !definitions.is_empty() {
+            blocks.push(ir::Block::DefinitionList(definitions));
+        }
+
+        blocks
+    }
+}
+
+/// Converts text to inlines, separating words by spaces.
+fn words(text: &str) -> ir::Content {
+    let mut out = Vec::new();
+
+    for (i, word) in text.split_whitespace().enumerate() {
+        if i > 0 {
+            out.push(ir::Inline::Space);
+        }
+        out.push(ir::Inline::Str(word.to_owned()));
+    }
+
+    out
+}
+
+/// Creates a link to the definition of a term.
+fn link(entry: &Entry, content: ir::Content) -> ir::Inline {
+    let attr = ir::AttrBuilder::new().class(TERM_CLASS).build();
+    ir::Inline::Link(attr, content, (format!("#{}", entry.ident), String::new()))
+}
+
+/// An occurrence of a term in a sequence of inlines.
+struct Occurrence<'a> {
+    /// The occurring term
+    entry: &'a Entry,
+    /// The index of the last inline of the occurrence
+    end: usize,
+    /// The punctuation before the term
+    prefix: String,
+    /// The text of the term
+    text: String,
+    /// The punctuation after the term
+    suffix: String,
+}
+
+/// Fills the glossaries and the uses of terms in document order.
+struct Resolver<'a> {
+    /// The glossary defining the terms
+    glossary: &'a Glossary,
+    /// The terms defined before the current position which are linked, longest first
+    active: Vec<&'a Entry>,
+    /// The keys of the terms used so far
+    used: BTreeSet<EcoString>,
+    /// Whether occurrences of terms are linked at the current position
+    linking: bool,
+}
+
+impl<'a> Resolver<'a> {
+    /// Returns the content displayed for a use of a term, expanding the first use of an abbreviation.
+    fn display(&mut self, entry: &Entry, text: &str) -> ir::Content {
+        let first = self.used.insert(entry.key.clone());
+        entry.display(text, first)
+    }
+
+    /// Finds an occurrence of a linked term starting at the given inline.
+    ///
+    /// Terms are matched as whole words, which may be surrounded by punctuation.
+    fn find(&self, inlines: &[ir::Inline], start: usize) -> Option<Occurrence<'a>> {
+        if !matches!(inlines.get(start), Some(ir::Inline::Str(_))) {
+            return None;
+        }
+
+        'entries: for &entry in &self.active {
+            let mut text = String::new();
+            let mut end = start;
+
+            for i in 0..entry.key.split_whitespace().count() {
+                if i > 0 {
+                    if !matches!(
+                        inlines.get(end + 1),
+                        Some(ir::Inline::Space | ir::Inline::SoftBreak)
+                    ) {
+                        continue 'entries;
+                    }
+                    end += 2;
+                    text.push(' ');
+                }
+
+                match inlines.get(end) {
+                    Some(ir::Inline::Str(word)) => text.push_str(word),
+                    _ => continue 'entries,
+                }
+            }
+
+            let word = text.trim_start_matches(|c: char| !c.is_alphanumeric());
+            let prefix = &text[..text.len() - word.len()];
+            let core = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+
+            // The key itself may end with punctuation, e.g. `C++` of `C++!`
+            let Some(len) = word
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain([word.len()])
+                .filter(|len| *len >= core.len())
+                .find(|len| entry.matches(&word[..*len]))
+            else {
+                continue;
+            };
+
+            return Some(Occurrence {
+                entry,
+                end,
+                prefix: prefix.to_owned(),
+                text: word[..len].to_owned(),
+                suffix: word[len..].to_owned(),
+            });
+        }
+
+        None
+    }
+}
+
+impl ir::MutVisitor for Resolver<'_> {
+    fn visit_block(&mut self, block: &mut ir::Block) {
+        match block {
+            ir::Block::Div((_, classes, pairs), content) => {
+                let index = pairs
+                    .iter()
+                    .find(|(key, _)| key == "index")
+                    .and_then(|(_, index)| index.parse::<usize>().ok())
+                    .filter(|_| classes.iter().any(|class| class == GLOSSARY_CLASS));
+
+                let options =
+                    index.and_then(|index| Some((index, self.glossary.glossaries.get(index)?)));
+
+                if let Some((index, options)) = options {
+                    *content = self.glossary.render(index, options);
+
+                    if options.link {
+                        let entries = self.glossary.entries.iter();
+                        self.active
+                            .extend(entries.filter(|entry| entry.glossary == index));
+                        self.active
+                            .sort_by_key(|entry| std::cmp::Reverse(entry.key.len()));
+                    }
+
+                    return;
+                }
+
+                self.walk_block(block)
+            }
+            // Headings are not linked
+            ir::Block::Header(..) => {
+                let linking = std::mem::replace(&mut self.linking, false);
+                self.walk_block(block);
+                self.linking = linking;
+            }
+            _ => self.walk_block(block),
+        }
+    }
+
+    fn visit_inline(&mut self, inline: &mut ir::Inline) {
+        match inline {
+            ir::Inline::Link((_, classes, pairs), content, (href, _))
+                if classes.iter().any(|class| class == TERM_CLASS) =>
+            {
+                let glossary = self.glossary;
+                let entry = pairs
+                    .iter()
+                    .find(|(key, _)| key == "term")
+                    .and_then(|(_, key)| glossary.get(key));
+
+                if let Some(entry) = entry {
+                    *content = self.display(entry, &entry.key);
+                    *href = format!("#{}", entry.ident);
+                }
+            }
+            // Terms are not linked inside of links and citations
+            ir::Inline::Link(..) | ir::Inline::Image(..) | ir::Inline::Cite(..) => {
+                let linking = std::mem::replace(&mut self.linking, false);
+                self.walk_inline(inline);
+                self.linking = linking;
+            }
+            _ => self.walk_inline(inline),
+        }
+    }
+
+    fn visit_vec_inline(&mut self, inlines: &mut Vec<ir::Inline>) {
+        let mut i = 0;
+
+        while i < inlines.len() {
+            let occurrence = if self.linking {
+                self.find(inlines, i)
+            } else {
+                None
+            };
+
+            let Some(Occurrence {
+                entry,
+                end,
+                prefix,
+                text,
+                suffix,
+            }) = occurrence
+            else {
+                self.visit_inline(&mut inlines[i]);
+                i += 1;
+                continue;
+            };
+
+            let mut replacement = Vec::new();
+
+            if !prefix.is_empty() {
+                replacement.push(ir::Inline::Str(prefix));
+            }
+
+            let content = self.display(entry, &text);
+            replacement.push(link(entry, content));
+
+            if !suffix.is_empty() {
+                replacement.push(ir::Inline::Str(suffix));
+            }
+
+            let len = replacement.len();
+            inlines.splice(i..=end, replacement);
+            i += len;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tyd_syntax::{source::Source, Spans};
+
+    use super::*;
+
+    fn span() -> Span {
+        Source::new("test.tyd", "test.tyd", "").end_of_input()
+    }
+
+    fn definition(term: &str) -> ir::Definition {
+        (words(term), vec![vec![ir::Block::Plain(words("desc"))]])
+    }
+
+    fn glossary(link: bool, terms: &[&str]) -> (Glossary, ir::Block) {
+        let mut glossary = Glossary::default();
+        let options = Options {
+            title: EcoString::new(),
+            link,
+        };
+        let placeholder = glossary.placeholder(options, span());
+        assert!(glossary.is_pending());
+
+        let duplicates = glossary.define(terms.iter().map(|term| definition(term)).collect());
+        assert!(duplicates.is_empty());
+        assert!(!glossary.is_pending());
+
+        (glossary, placeholder)
+    }
+
+    /// Resolves the paragraph after the glossary, returning its text with links in brackets.
+    fn resolve(glossary: &Glossary, placeholder: ir::Block, inlines: ir::Content) -> String {
+        fn text(inlines: &[ir::Inline]) -> String {
+            inlines
+                .iter()
+                .map(|inline| match inline {
+                    ir::Inline::Link(_, content, _) => format!("[{}]", plain_text(content)),
+                    inline => plain_text(std::slice::from_ref(inline)).to_string(),
+                })
+                .collect()
+        }
+
+        let mut blocks = vec![placeholder, ir::Block::Para(inlines)];
+        glossary.resolve(&mut blocks);
+
+        match &blocks[1] {
+            ir::Block::Para(inlines) => text(inlines),
+            block => panic!("unexpected {block:?}"),
+        }
+    }
+
+    #[test]
+    fn abbreviations() {
+        let (glossary, _) = glossary(
+            false,
+            &[
+                "API (Application Programming Interface)",
+                "Portable Document Format (PDF)",
+                "Compiler",
+            ],
+        );
+
+        let api = glossary.get("API").unwrap();
+        assert_eq!(
+            api.long.as_deref(),
+            Some("Application Programming Interface")
+        );
+        assert_eq!(api.ident, "gls-api");
+        assert_eq!(
+            glossary.get("PDF").unwrap().long.as_deref(),
+            Some("Portable Document Format")
+        );
+        assert_eq!(glossary.get("Compiler").unwrap().long, None);
+    }
+
+    #[test]
+    fn duplicate_terms() {
+        let mut glossary = Glossary::default();
+        let options = Options {
+            title: EcoString::new(),
+            link: false,
+        };
+        glossary.placeholder(options, span());
+
+        let duplicates = glossary.define(vec![
+            definition("API (Application Programming Interface)"),
+            definition("API"),
+        ]);
+
+        assert_eq!(duplicates, ["API"]);
+    }
+
+    #[test]
+    fn terms_without_glossary_are_ignored() {
+        let mut glossary = Glossary::default();
+        assert!(glossary.define(vec![definition("API")]).is_empty());
+        assert!(glossary.get("API").is_none());
+    }
+
+    #[test]
+    fn errors() {
+        let source = Source::new("test.tyd", "test.tyd", "");
+        let mut tracer = Tracer::new(source, Spans::from(Vec::new()));
+
+        let (mut glossary, _) = glossary(false, &["Compiler"]);
+        glossary.reference("Compiler".into(), span());
+        glossary.reference("Compilr".into(), span());
+        glossary.placeholder(
+            Options {
+                title: EcoString::new(),
+                link: false,
+            },
+            span(),
+        );
+        glossary.check(&mut tracer);
+
+        let (diags, _) = tracer.into_inner();
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].message, EngineError::ExpectedTerms.to_string());
+        assert_eq!(diags[1].help.as_deref(), Some("Did you mean 'Compiler'?"));
+    }
+
+    #[test]
+    fn first_use_expands_abbreviations() {
+        let (mut glossary, placeholder) =
+            glossary(false, &["API (Application Programming Interface)"]);
+        let inlines = vec![
+            glossary.reference("API".into(), span()),
+            ir::Inline::Space,
+            glossary.reference("API".into(), span()),
+        ];
+
+        assert_eq!(
+            resolve(&glossary, placeholder, inlines),
+            "[Application Programming Interface (API)] [API]"
+        );
+    }
+
+    #[test]
+    fn occurrences_are_linked() {
+        let (glossary, placeholder) = glossary(
+            true,
+            &[
+                "API (Application Programming Interface)",
+                "Type System",
+                "C++",
+            ],
+        );
+        let inlines = words("(api) type system, the API. C++! types");
+
+        assert_eq!(
+            resolve(&glossary, placeholder, inlines),
+            "(api) [type system], the [Application Programming Interface (API)]. [C++]! types"
+        );
+    }
+
+    #[test]
+    fn occurrences_are_not_linked_without_link_option() {
+        let (glossary, placeholder) = glossary(false, &["Compiler"]);
+
+        assert_eq!(
+            resolve(&glossary, placeholder, words("the compiler")),
+            "the compiler"
+        );
+    }
+
+    #[test]
+    fn terms_are_sorted() {
+        let (glossary, placeholder) = glossary(false, &["beta", "Alpha", "gamma"]);
+        let mut blocks = vec![placeholder];
+        glossary.resolve(&mut blocks);
+
+        let ir::Block::Div(_, content) = &blocks[0] else {
+            panic!("unexpected {:?}", blocks[0]);
+        };
+        let ir::Block::DefinitionList(definitions) = &content[0] else {
+            panic!("unexpected {:?}", content[0]);
+        };
+        let terms: Vec<_> = definitions
+            .iter()
+            .map(|(term, _)| plain_text(term))
+            .collect();
+
+        assert_eq!(terms, ["Alpha", "beta", "gamma"]);
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::LazyLock;
+use syntect::{
+    easy::ScopeRangeIterator,
+    parsing::{ParseState, ScopeStack, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+use super::{push, Line, Token};
+
+/// The grammars of the languages syntect ships with, loaded on first use
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+/// The kinds of tokens of TextMate scopes, more specific scopes before their prefixes
+const SCOPES: &[(&str, Token)] = &[
+    ("comment", Token::Comment),
+    ("string", Token::String),
+    ("constant.numeric", Token::Number),
+    ("constant.character.escape", Token::SpecialChar),
+    ("constant", Token::Constant),
+    ("keyword.control", Token::ControlFlow),
+    ("keyword.operator", Token::Operator),
+    ("keyword", Token::Keyword),
+    ("storage.type", Token::DataType),
+    ("storage", Token::Keyword),
+    ("entity.name.function", Token::Function),
+    ("variable.function", Token::Function),
+    ("support.function", Token::BuiltIn),
+    ("entity.name", Token::DataType),
+    ("support.type", Token::DataType),
+    ("support.class", Token::DataType),
+    ("entity.other.attribute-name", Token::Attribute),
+    ("meta.preprocessor", Token::Preprocessor),
+    ("variable", Token::Variable),
+    ("support", Token::BuiltIn),
+];
+
+/// Highlights code with the grammar whose name or file extension is the language.
+///
+/// # Returns
+/// The lines of the code, or None if there is no grammar for the language
+pub fn highlight(lang: &str, text: &str) -> Option<Vec<Line>> {
+    let syntax = SYNTAXES.find_syntax_by_token(lang)?;
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut lines = Vec::new();
+
+    for line in LinesWithEndings::from(text) {
+        let ops = state.parse_line(line, &SYNTAXES).ok()?;
+        let mut tokens = Line::new();
+
+        for (range, op) in ScopeRangeIterator::new(&ops, line) {
+            stack.apply(op).ok()?;
+
+            let text = line[range].trim_end_matches(['\n', '\r']);
+
+            if !text.is_empty() {
+                push(&mut tokens, token(&stack), text);
+            }
+        }
+
+        lines.push(tokens);
+    }
+
+    Some(lines)
+}
+
+/// Returns the kind of token of the innermost scope which has one.
+fn token(stack: &ScopeStack) -> Option<Token> {
+    stack.as_slice().iter().rev().find_map(|scope| {
+        let scope = scope.build_string();
+
+        SCOPES.iter().find_map(|(prefix, token)| {
+            let matches = scope
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'));
+
+            matches.then_some(*token)
+        })
+    })
+}
@@ -0,0 +1,300 @@
+mod grammar;
+mod tyd;
+
+use crate::ir;
+
+/// The languages highlighted by the TypeDown highlighter instead of a grammar
+const TYD_LANGUAGES: &[&str] = &["tyd", "typedown"];
+
+/// The kind of a highlighted token, displayed as the class of its code element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    /// Keywords, e.g. `let`
+    Keyword,
+    /// Keywords controlling the flow of execution, e.g. `if`
+    ControlFlow,
+    /// Names of types, e.g. `i64`
+    DataType,
+    /// Number literals
+    Number,
+    /// Constants, e.g. `true`
+    Constant,
+    /// String literals
+    String,
+    /// Escape sequences and other special characters
+    SpecialChar,
+    /// Verbatim text, e.g. raw blocks of TypeDown
+    Verbatim,
+    /// Special strings, e.g. math of TypeDown
+    SpecialString,
+    /// Comments
+    Comment,
+    /// Names of functions
+    Function,
+    /// Names of variables
+    Variable,
+    /// Operators
+    Operator,
+    /// Attributes and labels
+    Attribute,
+    /// Preprocessor directives
+    Preprocessor,
+    /// Builtin functions and types
+    BuiltIn,
+}
+
+impl Token {
+    /// Returns the class of the token, which is the abbreviation pandoc uses for it,
+    /// so that stylesheets written for the highlighting of pandoc apply.
+    pub fn class(self) -> &'static str {
+        match self {
+            Self::Keyword => "kw",
+            Self::ControlFlow => "cf",
+            Self::DataType => "dt",
+            Self::Number => "dv",
+            Self::Constant => "cn",
+            Self::String => "st",
+            Self::SpecialChar => "sc",
+            Self::Verbatim => "vs",
+            Self::SpecialString => "ss",
+            Self::Comment => "co",
+            Self::Function => "fu",
+            Self::Variable => "va",
+            Self::Operator => "op",
+            Self::Attribute => "at",
+            Self::Preprocessor => "pp",
+            Self::BuiltIn => "bu",
+        }
+    }
+}
+
+/// A line of highlighted code, the text of its tokens with their kind if they are highlighted
+pub type Line = Vec<(Option<Token>, String)>;
+
+/// Splits code into the highlighted tokens of its lines.
+///
+/// TypeDown is highlighted with its own parser, other languages with the grammar
+/// whose name or file extension is the language, e.g. `rust` or `rs`.
+///
+/// # Returns
+/// The lines of the code, or None if the language is unknown
+pub fn highlight(lang: &str, text: &str) -> Option<Vec<Line>> {
+    if TYD_LANGUAGES.contains(&lang) {
+        tyd::highlight(text)
+    } else {
+        grammar::highlight(lang, text)
+    }
+}
+
+/// Highlights a raw block.
+///
+/// The block is a div with the classes `sourceCode` and the language, containing
+/// the lines of code, whose tokens are code elements with the class of their kind.
+/// The line breaks after the opening and before the closing fence are not part of the code.
+///
+/// # Returns
+/// The highlighted block, or None if the language is unknown
+pub fn block(lang: &str, text: &str) -> Option<ir::Block> {
+    let text = text.strip_prefix('\n').unwrap_or(text);
+    let text = text.strip_suffix('\n').unwrap_or(text);
+
+    let lines = highlight(lang, text)?
+        .into_iter()
+        .map(|line| line.into_iter().map(code).collect())
+        .collect();
+
+    let attr = ir::AttrBuilder::new()
+        .class("sourceCode")
+        .class(lang)
+        .build();
+
+    Some(ir::Block::Div(attr, vec![ir::Block::LineBlock(lines)]))
+}
+
+/// Highlights inline raw code.
+///
+/// The code is a span with the classes `sourceCode` and the language, containing
+/// the tokens as code elements with the class of their kind.
+///
+/// # Returns
+/// The highlighted code, or None if the language is unknown
+pub fn inline(lang: &str, text: &str) -> Option<ir::Inline> {
+    let tokens = highlight(lang, text)?
+        .into_iter()
+        .flatten()
+        .map(code)
+        .collect();
+
+    let attr = ir::AttrBuilder::new()
+        .class("sourceCode")
+        .class(lang)
+        .build();
+
+    Some(ir::Inline::Span(attr, tokens))
+}
+
+/// Converts a token into a code element with the class of its kind.
+fn code((token, text): (Option<Token>, String)) -> ir::Inline {
+    let attr = ir::AttrBuilder::new()
+        .class_opt(token.map(Token::class))
+        .build();
+
+    ir::Inline::Code(attr, text)
+}
+
+/// Appends a token to a line, merging it with the previous token of the same kind.
+fn push(line: &mut Line, token: Option<Token>, text: &str) {
+    match line.last_mut() {
+        Some((last, last_text)) if *last == token => last_text.push_str(text),
+        _ => line.push((token, text.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the highlighted tokens of the code, without the unhighlighted text.
+    fn tokens(lang: &str, text: &str) -> Vec<(Token, String)> {
+        highlight(lang, text)
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .filter_map(|(token, text)| Some((token?, text)))
+            .collect()
+    }
+
+    fn token(token: Token, text: &str) -> (Token, String) {
+        (token, text.to_owned())
+    }
+
+    #[test]
+    fn unknown_languages() {
+        assert!(highlight("no-such-language", "x").is_none());
+        assert!(block("no-such-language", "x").is_none());
+        assert!(inline("no-such-language", "x").is_none());
+    }
+
+    #[test]
+    fn grammars() {
+        assert_eq!(
+            tokens("rust", "let x = 1; // c"),
+            [
+                token(Token::DataType, "let"),
+                token(Token::Operator, "="),
+                token(Token::Number, "1"),
+                token(Token::Comment, "// c"),
+            ]
+        );
+
+        // Languages are found by their file extension too
+        assert_eq!(tokens("rs", "fn main() {}"), tokens("rust", "fn main() {}"));
+        assert!(tokens("python", "return 'a'").contains(&token(Token::String, "'a'")));
+    }
+
+    #[test]
+    fn lines() {
+        let lines = highlight("rust", "let x = 1;\n\n// c").unwrap();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].is_empty());
+        assert_eq!(lines[2], [(Some(Token::Comment), "// c".to_owned())]);
+    }
+
+    #[test]
+    fn typedown() {
+        assert_eq!(
+            tokens("tyd", "#let x = 1"),
+            [
+                token(Token::Keyword, "#let"),
+                token(Token::Variable, "x"),
+                token(Token::Number, "1"),
+            ]
+        );
+        assert_eq!(
+            tokens("typedown", "#f(1, \"s\") \\*"),
+            [
+                token(Token::Function, "#f"),
+                token(Token::Number, "1"),
+                token(Token::String, "\"s\""),
+                token(Token::SpecialChar, "\\*"),
+            ]
+        );
+        assert_eq!(
+            tokens("tyd", "#if true then [a] else [b]"),
+            [
+                token(Token::ControlFlow, "#if"),
+                token(Token::Constant, "true"),
+                token(Token::ControlFlow, "then"),
+                token(Token::ControlFlow, "else"),
+            ]
+        );
+        assert_eq!(tokens("tyd", "= Title"), [token(Token::Keyword, "=")]);
+        assert_eq!(
+            tokens("tyd", "% note\nText `raw` $x$"),
+            [
+                token(Token::Comment, "% note"),
+                token(Token::Verbatim, "`raw`"),
+                token(Token::SpecialString, "$x$"),
+            ]
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        let Some(ir::Block::Div((_, classes, _), content)) = block("rust", "\nlet x;\n// c\n")
+        else {
+            panic!("expected a div");
+        };
+        assert_eq!(classes, ["sourceCode", "rust"]);
+
+        // The line breaks after and before the fences are not lines of the code
+        let [ir::Block::LineBlock(lines)] = content.as_slice() else {
+            panic!("unexpected {content:?}");
+        };
+        assert_eq!(lines.len(), 2);
+        assert!(matches!(
+            &lines[1][..],
+            [ir::Inline::Code((_, classes, _), text)] if classes == &["co"] && text == "// c"
+        ));
+    }
+
+    #[test]
+    fn inlines() {
+        let Some(ir::Inline::Span((_, classes, _), tokens)) = inline("rs", "1 + 2") else {
+            panic!("expected a span");
+        };
+        assert_eq!(classes, ["sourceCode", "rs"]);
+        assert_eq!(tokens.len(), 5);
+        assert!(matches!(
+            &tokens[1],
+            ir::Inline::Code((_, classes, _), text) if classes.is_empty() && text == " "
+        ));
+    }
+
+    #[test]
+    fn tokens_of_the_same_kind_are_merged() {
+        let mut line = Line::new();
+        push(&mut line, None, "a");
+        push(&mut line, None, "b");
+        push(&mut line, Some(Token::Number), "1");
+        push(&mut line, Some(Token::Number), "2");
+        push(&mut line, Some(Token::String), "s");
+
+        assert_eq!(
+            line,
+            [
+                (None, "ab".to_owned()),
+                (Some(Token::Number), "12".to_owned()),
+                (Some(Token::String), "s".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn classes() {
+        assert_eq!(Token::Keyword.class(), "kw");
+        assert_eq!(Token::Number.class(), "dv");
+        assert_eq!(Token::Comment.class(), "co");
+    }
+}
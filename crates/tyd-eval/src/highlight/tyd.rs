@@ -0,0 +1,190 @@
+use std::ops::Range;
+use tyd_core::prelude::*;
+use tyd_syntax::{
+    parser::{parse, ParseResult},
+    source::Source,
+    Spans,
+};
+
+use super::{push, Line, Token};
+
+/// A token covering a range of the code, painted over tokens of lower priority
+type Paint = (u8, Range<usize>, Token);
+
+/// Highlights TypeDown code with the TypeDown parser.
+///
+/// Tokens are derived from the nodes of the parsed document, so that the code is
+/// highlighted like it is evaluated, e.g. identifiers of calls are functions.
+/// Code with syntax errors is highlighted as far as it could be parsed.
+///
+/// # Returns
+/// The lines of the code, or None if it could not be parsed at all
+pub fn highlight(text: &str) -> Option<Vec<Line>> {
+    let source = Source::new("", "", text);
+    let ParseResult { doc, spans, .. } = parse(&source);
+    let doc = doc?;
+
+    let mut paints = doc
+        .iter_full()
+        .flat_map(|(node, id)| paints(node, span(&spans, id), &spans, text))
+        .collect::<Vec<_>>();
+    paints.sort_by_key(|(priority, ..)| *priority);
+
+    let mut tokens = vec![None; text.len()];
+
+    for (_, range, token) in paints {
+        tokens[range].fill(Some(token));
+    }
+
+    // The `#` starting code is highlighted like the expression following it
+    for (node, id) in doc.iter_full() {
+        let start = span(&spans, id).start;
+
+        if matches!(node, Node::Code(_)) && text[start..].starts_with('#') {
+            tokens[start] = tokens.get(start + 1).copied().flatten();
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut line = Line::new();
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '\n' => lines.push(std::mem::take(&mut line)),
+            '\r' => {}
+            c => push(&mut line, tokens[i], c.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    lines.push(line);
+    Some(lines)
+}
+
+/// Returns the byte range of a node.
+fn span<T>(spans: &Spans, id: NodeId<T>) -> Range<usize> {
+    let span = spans.get(id).inner_copied();
+    span.start..span.end
+}
+
+/// Returns the tokens painted for a node.
+///
+/// Tokens of nested nodes have a higher priority than those of their parents,
+/// e.g. the language of a raw block is painted over the verbatim raw block.
+/// Keywords are the words of a node outside of its children, e.g. `let`.
+fn paints(node: &Node, range: Range<usize>, spans: &Spans, text: &str) -> Vec<Paint> {
+    let keywords = |children: Vec<Range<usize>>, token| keywords(&range, &children, token, text);
+
+    match node {
+        Node::Raw(_) | Node::RawInline(_) => vec![(0, range, Token::Verbatim)],
+        Node::MathInline(_) => vec![(0, range, Token::SpecialString)],
+        Node::Ident(_) => vec![(1, range, Token::Variable)],
+        Node::Call(call) => vec![(2, span(spans, call.ident), Token::Function)],
+        Node::Access(tree::Access {
+            fields,
+            args: Some(_),
+            ..
+        }) => fields
+            .last()
+            .map(|field| (2, span(spans, *field), Token::Function))
+            .into_iter()
+            .collect(),
+        Node::Literal(literal) => {
+            let token = match literal {
+                tree::Literal::Str(_) => Token::String,
+                tree::Literal::Int(_) | tree::Literal::Float(_) => Token::Number,
+                tree::Literal::Bool(_) => Token::Constant,
+            };
+            vec![(3, range, token)]
+        }
+        Node::Comment(_) => vec![(3, extend(range, '%', text), Token::Comment)],
+        Node::Escape(_) => vec![(3, extend(range, '\\', text), Token::SpecialChar)],
+        Node::Label(_) | Node::Tag(_) => vec![(3, range, Token::Attribute)],
+        Node::HeadingMarker(_) => vec![(3, range, Token::Keyword)],
+        Node::Let(let_) => keywords(
+            let_.0.iter().map(|id| span(spans, *id)).collect(),
+            Token::Keyword,
+        ),
+        Node::If(if_) => {
+            let children = vec![
+                span(spans, if_.predicate),
+                span(spans, if_.then),
+                span(spans, if_.or),
+            ];
+            keywords(children, Token::ControlFlow)
+        }
+        Node::For(for_) => {
+            let children = vec![
+                span(spans, for_.el),
+                span(spans, for_.inside),
+                span(spans, for_.content),
+            ];
+            keywords(children, Token::ControlFlow)
+        }
+        Node::Break(_) | Node::Continue(_) => vec![(4, range, Token::ControlFlow)],
+        Node::Macro(macro_) => {
+            let name = span(spans, macro_.name);
+            let children = vec![
+                name.clone(),
+                span(spans, macro_.params),
+                span(spans, macro_.body),
+            ];
+            let mut paints = keywords(children, Token::Keyword);
+            paints.push((2, name, Token::Function));
+            paints
+        }
+        Node::Set(set) => {
+            let ident = span(spans, set.ident);
+            let mut paints = keywords(vec![ident.clone(), span(spans, set.args)], Token::Keyword);
+            paints.push((2, ident, Token::Function));
+            paints
+        }
+        Node::Show(show) => {
+            let mut children = vec![span(spans, show.selector), span(spans, show.transform)];
+            children.extend(show.filter.map(|id| span(spans, id)));
+            children.extend(show.param.map(|id| span(spans, id)));
+            keywords(children, Token::Keyword)
+        }
+        Node::Include(include) => keywords(vec![span(spans, include.path)], Token::Keyword),
+        Node::Import(import) => keywords(vec![span(spans, import.path)], Token::Keyword),
+        _ => Vec::new(),
+    }
+}
+
+/// Paints the words of a node which are outside of its children as keywords.
+fn keywords(
+    range: &Range<usize>,
+    children: &[Range<usize>],
+    token: Token,
+    text: &str,
+) -> Vec<Paint> {
+    let mut paints = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text[range.clone()].char_indices() {
+        let i = range.start + i;
+        let inside = children.iter().any(|child| child.contains(&i));
+
+        match (start, c.is_ascii_alphabetic() && !inside) {
+            (None, true) => start = Some(i),
+            (Some(word), false) => {
+                paints.push((4, word..i, token));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(word) = start {
+        paints.push((4, word..range.end, token));
+    }
+
+    paints
+}
+
+/// Extends a range to the marker before it, e.g. the `%` of a comment.
+fn extend(range: Range<usize>, marker: char, text: &str) -> Range<usize> {
+    match text[..range.start].ends_with(marker) {
+        true => range.start - marker.len_utf8()..range.end,
+        false => range,
+    }
+}
@@ -0,0 +1,381 @@
+use ecow::EcoString;
+use std::collections::BTreeMap;
+
+use crate::ir;
+
+/// The class of the blocks created for indexes, which are filled after evaluation,
+/// and of the anchors and links of index entries
+const INDEX_CLASS: &str = "index";
+
+/// An occurrence of a term marked for the index.
+#[derive(Debug, Clone)]
+struct Anchor {
+    /// The marked term
+    term: EcoString,
+    /// The marked subterm, empty for none
+    subterm: EcoString,
+    /// The identifier of the anchor to link to
+    ident: String,
+    /// The section containing the anchor, displayed by links to it
+    section: Option<ir::Content>,
+}
+
+/// How an index is displayed.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The title displayed above the index, empty for none
+    pub title: EcoString,
+}
+
+/// The terms marked for the index and the indexes listing them.
+///
+/// Indexes may appear before the terms they list, so a placeholder is inserted
+/// while the document is evaluated and filled once all terms are known.
+#[derive(Debug, Clone, Default)]
+pub struct Index {
+    /// The anchors in document order
+    anchors: Vec<Anchor>,
+    /// The options of each index, indexed by their placeholder
+    indexes: Vec<Options>,
+}
+
+impl Index {
+    /// Marks an occurrence of a term for the index.
+    ///
+    /// # Arguments
+    /// * `term` - The marked term
+    /// * `subterm` - The marked subterm, empty for none
+    /// * `section` - The number or title of the section containing the occurrence, if any
+    ///
+    /// # Returns
+    /// The anchor to insert at the occurrence
+    pub fn mark(
+        &mut self,
+        term: EcoString,
+        subterm: EcoString,
+        section: Option<ir::Content>,
+    ) -> ir::Inline {
+        let ident = format!("idx-{}", self.anchors.len() + 1);
+        let attr = ir::AttrBuilder::new()
+            .ident(ident.clone())
+            .class(INDEX_CLASS)
+            .build();
+
+        self.anchors.push(Anchor {
+            term,
+            subterm,
+            ident,
+            section,
+        });
+
+        ir::Inline::Span(attr, Vec::new())
+    }
+
+    /// Creates a placeholder for an index, which is filled by `Index::resolve`.
+    pub fn placeholder(&mut self, options: Options) -> ir::Block {
+        let attr = ir::AttrBuilder::new()
+            .class(INDEX_CLASS)
+            .attr("index", self.indexes.len().to_string())
+            .build();
+
+        self.indexes.push(options);
+        ir::Block::Div(attr, Vec::new())
+    }
+
+    /// Fills all index placeholders with the alphabetized terms, grouped by their initial.
+    pub fn resolve(&self, blocks: &mut Vec<ir::Block>) {
+        ir::MutVisitor::visit_vec_block(&mut Resolver { index: self }, blocks);
+    }
+
+    /// Renders the index with the given options.
+    fn render(&self, options: &Options) -> Vec<ir::Block> {
+        let mut blocks = Vec::new();
+
+        if !options.title.is_empty() {
+            let attr = ir::AttrBuilder::new().class("unnumbered").build();
+            let title = vec![ir::Inline::Str(options.title.to_string())];
+            blocks.push(ir::Block::Header(1, attr, title));
+        }
+
+        // Terms sorted regardless of case, each with its subterms
+        let mut terms: BTreeMap<_, BTreeMap<_, Vec<&Anchor>>> = BTreeMap::new();
+
+        for anchor in &self.anchors {
+            terms
+                .entry((anchor.term.to_lowercase(), anchor.term.clone()))
+                .or_default()
+                .entry((anchor.subterm.to_lowercase(), anchor.subterm.clone()))
+                .or_default()
+                .push(anchor);
+        }
+
+        let mut groups: BTreeMap<String, Vec<Vec<ir::Block>>> = BTreeMap::new();
+
+        for ((_, term), subterms) in terms {
+            let mut content = vec![ir::Inline::Str(term.to_string())];
+            let mut items = Vec::new();
+
+            for ((_, subterm), anchors) in subterms {
+                if subterm.is_empty() {
+                    content.extend(links(&anchors));
+                } else {
+                    let mut content = vec![ir::Inline::Str(subterm.to_string())];
+                    content.extend(links(&anchors));
+                    items.push(vec![ir::Block::Plain(content)]);
+                }
+            }
+
+            let mut item = vec![ir::Block::Plain(content)];
+
+            if !items.is_empty() {
+                item.push(ir::Block::BulletList(items));
+            }
+
+            groups.entry(initial(&term)).or_default().push(item);
+        }
+
+        for (initial, items) in groups {
+            let letter = vec![ir::Inline::Strong(vec![ir::Inline::Str(initial)])];
+            blocks.push(ir::Block::Para(letter));
+            blocks.push(ir::Block::BulletList(items));
+        }
+
+        blocks
+    }
+}
+
+/// Returns the group of a term, which is its uppercase initial or `#` for symbols and numbers.
+fn initial(term: &str) -> String {
+    match term.chars().next() {
+        Some(c) if c.is_alphabetic() => c.to_uppercase().collect(),
+        _ => "#".to_owned(),
+    }
+}
+
+/// Creates the links to the occurrences of a term, e.g. `, 1.2, 3`.
+///
+/// Links display the section of the occurrence, or the number of the occurrence
+/// outside of sections. Occurrences in the same section are linked once.
+fn links(anchors: &[&Anchor]) -> ir::Content {
+    let mut out = Vec::new();
+    let mut sections = Vec::new();
+
+    for (i, anchor) in anchors.iter().enumerate() {
+        let content = match &anchor.section {
+            Some(section) => section.clone(),
+            None => vec![ir::Inline::Str((i + 1).to_string())],
+        };
+
+        if sections.contains(&content) {
+            continue;
+        }
+
+        sections.push(content.clone());
+
+        let attr = ir::AttrBuilder::new().class(INDEX_CLASS).build();
+        let target = (format!("#{}", anchor.ident), String::new());
+
+        out.push(ir::Inline::Str(",".to_owned()));
+        out.push(ir::Inline::Space);
+        out.push(ir::Inline::Link(attr, content, target));
+    }
+
+    out
+}
+
+/// Replaces the index placeholders with the rendered indexes.
+struct Resolver<'a> {
+    /// The index to render the placeholders with
+    index: &'a Index,
+}
+
+impl ir::MutVisitor for Resolver<'_> {
+    fn visit_block(&mut self, block: &mut ir::Block) {
+        if let ir::Block::Div((_, classes, pairs), content) = block {
+            let options = pairs
+                .iter()
+                .find(|(key, _)| key == "index")
+                .and_then(|(_, index)| index.parse::<usize>().ok())
+                .and_then(|index| self.index.indexes.get(index))
+                .filter(|_| classes.iter().any(|class| class == INDEX_CLASS));
+
+            if let Some(options) = options {
+                *content = self.index.render(options);
+                return;
+            }
+        }
+
+        self.walk_block(block)
+    }
+}
+
+/// Replaces the sections displayed by links of index entries with page numbers,
+/// which are only known to Typst when the document is laid out.
+///
+/// Anchors become Typst labels, and links display the page of the labelled anchor.
+pub fn page_references(pandoc: &mut ir::Pandoc) {
+    ir::MutVisitor::walk_pandoc(&mut PageReferences, pandoc);
+}
+
+/// Converts index anchors and links into Typst labels and page references.
+struct PageReferences;
+
+impl ir::MutVisitor for PageReferences {
+    fn visit_inline(&mut self, inline: &mut ir::Inline) {
+        match inline {
+            ir::Inline::Span((ident, classes, _), content)
+                if content.is_empty() && classes.iter().any(|class| class == INDEX_CLASS) =>
+            {
+                let label = format!("#metadata(none) <{ident}>");
+                *inline = ir::Inline::RawInline(ir::Format("typst".to_owned()), label);
+            }
+            ir::Inline::Link((_, classes, _), content, (href, _))
+                if classes.iter().any(|class| class == INDEX_CLASS) =>
+            {
+                let ident = href.trim_start_matches('#');
+                let page = format!("#context counter(page).at(<{ident}>).first()");
+                *content = vec![ir::Inline::RawInline(ir::Format("typst".to_owned()), page)];
+            }
+            _ => self.walk_inline(inline),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label::plain_text;
+
+    fn section(number: &str) -> Option<ir::Content> {
+        Some(vec![ir::Inline::Str(number.to_owned())])
+    }
+
+    /// Returns the text of the rendered index, one line per paragraph or list item.
+    fn lines(blocks: &[ir::Block]) -> Vec<String> {
+        fn walk(blocks: &[ir::Block], depth: usize, out: &mut Vec<String>) {
+            for block in blocks {
+                match block {
+                    ir::Block::Para(inlines) | ir::Block::Plain(inlines) => {
+                        out.push(format!("{}{}", "  ".repeat(depth), plain_text(inlines)))
+                    }
+                    ir::Block::BulletList(items) => {
+                        items.iter().for_each(|item| walk(item, depth + 1, out))
+                    }
+                    ir::Block::Header(_, _, title) => out.push(plain_text(title).to_string()),
+                    block => panic!("unexpected {block:?}"),
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        walk(blocks, 0, &mut out);
+        out
+    }
+
+    #[test]
+    fn initials() {
+        assert_eq!(initial("apple"), "A");
+        assert_eq!(initial("Éclair"), "É");
+        assert_eq!(initial("2D"), "#");
+        assert_eq!(initial("_x"), "#");
+        assert_eq!(initial(""), "#");
+    }
+
+    #[test]
+    fn anchors_are_unique() {
+        let mut index = Index::default();
+
+        let first = index.mark("a".into(), EcoString::new(), None);
+        let second = index.mark("a".into(), EcoString::new(), None);
+
+        assert!(matches!(first, ir::Inline::Span((ident, ..), _) if ident == "idx-1"));
+        assert!(matches!(second, ir::Inline::Span((ident, ..), _) if ident == "idx-2"));
+    }
+
+    #[test]
+    fn terms_are_grouped_and_sorted() {
+        let mut index = Index::default();
+        index.mark("compiler".into(), EcoString::new(), section("2"));
+        index.mark("Banana".into(), "ripe".into(), section("1"));
+        index.mark("apple".into(), EcoString::new(), section("1"));
+        index.mark("compiler".into(), EcoString::new(), section("2"));
+        index.mark("compiler".into(), EcoString::new(), section("3"));
+        index.mark("Banana".into(), "green".into(), section("4"));
+        index.mark("42".into(), EcoString::new(), None);
+
+        let blocks = index.render(&Options {
+            title: "Index".into(),
+        });
+
+        assert_eq!(
+            lines(&blocks),
+            [
+                "Index",
+                "#",
+                "  42, 1",
+                "A",
+                "  apple, 1",
+                "B",
+                "  Banana",
+                "    green, 4",
+                "    ripe, 1",
+                "C",
+                "  compiler, 2, 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_index() {
+        let blocks = Index::default().render(&Options {
+            title: EcoString::new(),
+        });
+
+        assert!(blocks.is_empty());
+    }
+
+    #[test]
+    fn placeholders_are_filled() {
+        let mut index = Index::default();
+        let mut blocks = vec![index.placeholder(Options {
+            title: EcoString::new(),
+        })];
+        index.mark("term".into(), EcoString::new(), None);
+
+        index.resolve(&mut blocks);
+
+        let ir::Block::Div(_, content) = &blocks[0] else {
+            panic!("unexpected {:?}", blocks[0]);
+        };
+        assert_eq!(lines(content), ["T", "  term, 1"]);
+    }
+
+    #[test]
+    fn page_references_for_typst() {
+        let mut index = Index::default();
+        let anchor = index.mark("term".into(), EcoString::new(), section("1"));
+        let link = links(&[&index.anchors[0]]).pop().unwrap();
+
+        let mut pandoc = ir::Pandoc {
+            meta: Default::default(),
+            blocks: vec![ir::Block::Para(vec![anchor, link])],
+            pandoc_api_version: Vec::new(),
+        };
+        page_references(&mut pandoc);
+
+        let ir::Block::Para(inlines) = &pandoc.blocks[0] else {
+            panic!("unexpected {:?}", pandoc.blocks[0]);
+        };
+        assert!(matches!(
+            &inlines[0],
+            ir::Inline::RawInline(_, label) if label == "#metadata(none) <idx-1>"
+        ));
+        assert!(matches!(
+            &inlines[1],
+            ir::Inline::Link(_, content, _) if matches!(
+                content.as_slice(),
+                [ir::Inline::RawInline(_, page)] if page.contains("counter(page).at(<idx-1>)")
+            )
+        ));
+    }
+}
@@ -1,9 +1,8 @@
 use std::collections::BTreeMap;
 
 use ecow::EcoString;
-use tyd_syntax::{source::Source, Span};
 
-use crate::{scope::Scope, stack::Stack, tracer::Tracer, value::Value};
+use crate::value::Value;
 
 pub use pandoc_ast::*;
 
@@ -17,9 +16,6 @@ pub type List = Vec<Value>;
 /// A collection of inline content elements.
 pub type Content = Vec<Inline>;
 
-/// A function type for evaluating expressions in the context of a stack, scope, source, and tracer.
-pub type Func = fn(Stack, Scope, Source, Span, &mut Tracer) -> Value;
-
 /// A builder for constructing Pandoc attributes.
 ///
 /// Provides a fluent interface for building attributes with identifiers,
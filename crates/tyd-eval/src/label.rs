@@ -0,0 +1,319 @@
+use ecow::EcoString;
+use std::collections::BTreeMap;
+use tyd_syntax::{error::SourceDiagnostic, Span};
+
+use crate::{bibliography::Bibliography, error::ReferenceError, ir, tracer::Tracer};
+
+/// The class of links created for references, which are resolved after evaluation
+pub(crate) const REF_CLASS: &str = "ref";
+
+/// An element of the document which can be referenced by its label.
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// The kind of the element displayed before its number, e.g. `Figure`
+    pub supplement: EcoString,
+    /// The displayed number of the element, if it is numbered
+    pub number: Option<EcoString>,
+    /// The text displayed for references if the element is not numbered
+    pub text: EcoString,
+    /// The span of the label
+    pub span: Span,
+}
+
+impl Target {
+    /// Returns the content displayed for references to this target,
+    /// e.g. `Figure 3`, or its text if it is not numbered.
+    pub fn display(&self) -> ir::Content {
+        match &self.number {
+            Some(number) => vec![
+                ir::Inline::Str(self.supplement.to_string()),
+                ir::Inline::Space,
+                ir::Inline::Str(number.to_string()),
+            ],
+            None => vec![ir::Inline::Str(self.text.to_string())],
+        }
+    }
+}
+
+/// The labels defined in the document and the references to them.
+///
+/// References may appear before the element they point to, so they are collected
+/// while the document is evaluated and resolved once all labels are known.
+#[derive(Debug, Clone, Default)]
+pub struct Labels {
+    /// The referenceable elements by label
+    targets: BTreeMap<EcoString, Target>,
+    /// The referenced labels together with the span of the reference
+    refs: Vec<(EcoString, Span)>,
+}
+
+impl Labels {
+    /// Defines a label, reporting an error if it is already defined.
+    ///
+    /// # Arguments
+    /// * `label` - The label of the element
+    /// * `target` - The element the label points to
+    /// * `tracer` - The tracer for reporting errors
+    pub fn define(&mut self, label: EcoString, target: Target, tracer: &mut Tracer) {
+        if let Some(first) = self.targets.get(&label) {
+            let diag = SourceDiagnostic::error(
+                target.span,
+                ReferenceError::DuplicateLabel(label).to_string(),
+            )
+            .with_trace([("First defined here".to_owned(), first.span)]);
+            tracer.diagnose_source(diag);
+            return;
+        }
+
+        self.targets.insert(label, target);
+    }
+
+    /// Returns the element with the given label.
+    pub fn get(&self, label: &str) -> Option<&Target> {
+        self.targets.get(label)
+    }
+
+    /// Creates a link to a label, which is resolved by `Labels::resolve`.
+    ///
+    /// # Arguments
+    /// * `label` - The referenced label
+    /// * `content` - The displayed content, or empty to display the target
+    /// * `span` - The span of the reference
+    pub fn reference(&mut self, label: EcoString, content: ir::Content, span: Span) -> ir::Inline {
+        let attr = ir::AttrBuilder::new().class(REF_CLASS).build();
+        let target = (format!("#{label}"), String::new());
+
+        self.refs.push((label, span));
+        ir::Inline::Link(attr, content, target)
+    }
+
+    /// Reports an error for every reference to a label which is not defined,
+    /// suggesting the most similar defined label.
+    ///
+    /// References to keys of the bibliography are citations and not reported.
+    pub fn check(&self, bibliography: &Bibliography, tracer: &mut Tracer) {
+        for (label, span) in &self.refs {
+            if self.targets.contains_key(label) || bibliography.contains(label) {
+                continue;
+            }
+
+            let mut diag = SourceDiagnostic::error(
+                *span,
+                ReferenceError::UnknownLabel(label.clone()).to_string(),
+            );
+
+            if let Some(similar) = similar(label, self.targets.keys()) {
+                diag.set_help(format!("Did you mean '{similar}'?"));
+            }
+
+            tracer.diagnose_source(diag);
+        }
+    }
+
+    /// Fills in the content of all references without custom content.
+    pub fn resolve(&self, blocks: &mut Vec<ir::Block>) {
+        ir::MutVisitor::visit_vec_block(&mut Resolver { labels: self }, blocks);
+    }
+}
+
+/// Replaces the empty content of reference links with the display of their target.
+struct Resolver<'a> {
+    /// The labels to resolve the references with
+    labels: &'a Labels,
+}
+
+impl ir::MutVisitor for Resolver<'_> {
+    fn visit_inline(&mut self, inline: &mut ir::Inline) {
+        if let ir::Inline::Link((_, classes, _), content, (href, _)) = inline {
+            let is_ref = classes.iter().any(|class| class == REF_CLASS);
+            let target = href
+                .strip_prefix('#')
+                .and_then(|label| self.labels.get(label));
+
+            if let Some(target) = target.filter(|_| is_ref && content.is_empty()) {
+                *content = target.display();
+            }
+        }
+
+        self.walk_inline(inline)
+    }
+}
+
+/// Returns the text of inline content without any formatting.
+pub fn plain_text(inlines: &[ir::Inline]) -> EcoString {
+    let mut out = EcoString::new();
+
+    for inline in inlines {
+        match inline {
+            ir::Inline::Str(s) | ir::Inline::Code(_, s) | ir::Inline::Math(_, s) => out.push_str(s),
+            ir::Inline::Space | ir::Inline::SoftBreak | ir::Inline::LineBreak => out.push(' '),
+            ir::Inline::Emph(inlines)
+            | ir::Inline::Underline(inlines)
+            | ir::Inline::Strong(inlines)
+            | ir::Inline::Strikeout(inlines)
+            | ir::Inline::Superscript(inlines)
+            | ir::Inline::Subscript(inlines)
+            | ir::Inline::SmallCaps(inlines)
+            | ir::Inline::Quoted(_, inlines)
+            | ir::Inline::Cite(_, inlines)
+            | ir::Inline::Link(_, inlines, _)
+            | ir::Inline::Span(_, inlines) => out.push_str(&plain_text(inlines)),
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Returns the candidate most similar to the given name, if any is similar enough.
+pub(crate) fn similar<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a EcoString>,
+) -> Option<&'a EcoString> {
+    let max = (name.chars().count() / 3).max(2);
+
+    candidates
+        .into_iter()
+        .map(|other| (distance(name, other), other))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, other)| other)
+}
+
+/// Returns the edit distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use tyd_syntax::{source::Source, Spans};
+
+    use super::*;
+
+    fn tracer() -> (Tracer, Span) {
+        let source = Source::new("test.tyd", "test.tyd", "");
+        let span = source.end_of_input();
+        (Tracer::new(source, Spans::from(Vec::new())), span)
+    }
+
+    fn target(number: Option<&str>, span: Span) -> Target {
+        Target {
+            supplement: "Figure".into(),
+            number: number.map(Into::into),
+            text: "The Figure".into(),
+            span,
+        }
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("fig", ""), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("größe", "grösse"), 2);
+    }
+
+    #[test]
+    fn similar_labels() {
+        let labels: Vec<EcoString> = vec!["fig-plot".into(), "tab-data".into(), "eq".into()];
+
+        assert_eq!(
+            similar("fig-plto", &labels).map(|l| l.as_str()),
+            Some("fig-plot")
+        );
+        assert_eq!(similar("ex", &labels).map(|l| l.as_str()), Some("eq"));
+        assert_eq!(similar("introduction", &labels), None);
+        assert_eq!(similar("fig", &[]), None);
+    }
+
+    #[test]
+    fn duplicate_labels() {
+        let (mut tracer, span) = tracer();
+        let mut labels = Labels::default();
+
+        labels.define("fig".into(), target(Some("1"), span), &mut tracer);
+        labels.define("fig".into(), target(Some("2"), span), &mut tracer);
+
+        assert!(tracer.has_errors());
+        assert_eq!(labels.get("fig").unwrap().number.as_deref(), Some("1"));
+
+        let (diags, _) = tracer.into_inner();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].trace.len(), 1);
+    }
+
+    #[test]
+    fn unknown_references() {
+        let (mut tracer, span) = tracer();
+        let mut labels = Labels::default();
+
+        labels.define("fig-plot".into(), target(Some("1"), span), &mut tracer);
+        labels.reference("fig-plot".into(), Vec::new(), span);
+        labels.reference("fig-plto".into(), Vec::new(), span);
+        labels.reference("unrelated".into(), Vec::new(), span);
+        labels.check(&Bibliography::default(), &mut tracer);
+
+        let (diags, _) = tracer.into_inner();
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].help.as_deref(), Some("Did you mean 'fig-plot'?"));
+        assert_eq!(diags[1].help, None);
+    }
+
+    #[test]
+    fn references_display_their_target() {
+        let (mut tracer, span) = tracer();
+        let mut labels = Labels::default();
+
+        labels.define("num".into(), target(Some("2"), span), &mut tracer);
+        labels.define("text".into(), target(None, span), &mut tracer);
+
+        let custom = vec![ir::Inline::Str("here".into())];
+        let inlines = vec![
+            labels.reference("num".into(), Vec::new(), span),
+            labels.reference("text".into(), Vec::new(), span),
+            labels.reference("num".into(), custom, span),
+        ];
+
+        let mut blocks = vec![ir::Block::Para(inlines)];
+        labels.resolve(&mut blocks);
+
+        assert_eq!(
+            plain_text(match &blocks[0] {
+                ir::Block::Para(inlines) => inlines,
+                block => panic!("unexpected {block:?}"),
+            }),
+            "Figure 2The Figurehere"
+        );
+    }
+
+    #[test]
+    fn plain_text_of_formatted_content() {
+        let inlines = vec![
+            ir::Inline::Strong(vec![ir::Inline::Str("a".into())]),
+            ir::Inline::Space,
+            ir::Inline::Emph(vec![ir::Inline::Str("b".into())]),
+            ir::Inline::Note(Vec::new()),
+        ];
+
+        assert_eq!(plain_text(&inlines), "a b");
+    }
+}
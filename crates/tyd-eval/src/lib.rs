@@ -2,20 +2,49 @@
 //!
 //! This library provides implementations for rendering content in various formats.
 
+/// Bibliographies and citations
+pub mod bibliography;
 /// Provides built-in functionality and standard plugins
 pub mod builtin;
+/// Counters and numbering of document elements
+pub mod counter;
+/// Conversion of JSON, CSV, TOML and YAML data into values
+pub mod data;
+/// Dates and times of day
+pub mod datetime;
 /// Core engine implementation
 pub mod engine;
 /// Error handling structures and utilities
 pub mod error;
+/// User-defined function values
+pub mod func;
+/// Glossaries of terms and abbreviations
+pub mod glossary;
+/// Syntax highlighting of raw blocks and inline raw code
+#[cfg(feature = "highlight")]
+pub mod highlight;
+/// Back-of-book index of marked terms
+pub mod index;
 /// Intermediate representation for parsed documents
 pub mod ir;
+/// Labels of document elements and references to them
+pub mod label;
+/// Conversion of math into MathML
+pub mod math;
+/// Table of contents listing the headings of a document
+pub mod outline;
 /// Output formatting and compilation for various formats
 pub mod render;
 /// Variable and function scoping management
 pub mod scope;
+/// Show rules transforming document elements
+pub mod show;
+/// Declarative signatures of native functions
+pub mod signature;
 /// Execution stack implementation
 pub mod stack;
+/// Document state shared with native functions
+pub mod state;
 /// Debug tracing capabilities
 pub mod tracer;
 /// Value and Type representation and manipulation
@@ -29,11 +58,14 @@ pub mod prelude {
     pub use crate::builtin::BuiltinPlugin;
     pub use crate::engine::{Engine, EngineResult};
     pub use crate::error::*;
+    pub use crate::func::{Closure, Func};
     pub use crate::ir;
     pub use crate::render::{
-        DocxCompiler, HtmlCompiler, Output, PandocCompiler, PdfCompiler, Render,
+        DocxCompiler, HtmlCompiler, Output, PandocCompiler, PdfCompiler, Render, Target,
     };
     pub use crate::scope::Scope;
+    pub use crate::signature::{Args, Param, ParamKind, Signature};
+    pub use crate::state::State;
     pub use crate::tracer::Tracer;
     pub use crate::value::{Type, TypeCast, TypeChecker, Typed, Value};
     pub use crate::Plugin;
@@ -44,11 +76,13 @@ pub mod prelude {
 /// Implementing this trait allows you to add new functions, types, and
 /// behaviors. Plugins are initialized with a scope
 /// which they can populate with their provided functionality.
+/// As plugins are values, they can carry configuration which is
+/// captured by the functions they register.
 pub trait Plugin {
     /// Initialize the plugin with the given scope
     ///
     /// # Arguments
     ///
     /// * `scope` - The scope to register plugin functionality with
-    fn init(scope: &mut crate::scope::Scope);
+    fn init(&self, scope: &mut crate::scope::Scope);
 }
@@ -0,0 +1,380 @@
+use std::fmt::Write;
+
+use tyd_syntax::math::{self, Columns, Math, Variant};
+
+use crate::ir;
+
+/// The namespace of MathML elements
+const NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Delimiters which do not stretch unless they enclose nodes with `\left` and `\right`
+const DELIMITERS: &[&str] = &[
+    "(", ")", "[", "]", "{", "}", "|", "‖", "⟨", "⟩", "⌊", "⌋", "⌈", "⌉",
+];
+
+/// Converts math written in LaTeX into MathML.
+///
+/// The LaTeX is kept as an annotation of the MathML, so that it can be copied.
+/// Commands which are not supported are displayed verbatim as errors.
+///
+/// # Arguments
+/// * `text` - The math written in LaTeX
+/// * `display` - Whether the math is displayed as a block instead of inline
+pub fn mathml(text: &str, display: bool) -> String {
+    let math = math::parse(text).math;
+    let display = if display { "block" } else { "inline" };
+
+    let mut out = format!(r#"<math xmlns="{NAMESPACE}" display="{display}"><semantics>"#);
+    render(&math, None, &mut out);
+    out.push_str(r#"<annotation encoding="application/x-tex">"#);
+    out.push_str(&escape(text));
+    out.push_str("</annotation></semantics></math>");
+
+    out
+}
+
+/// Replaces the math of the document with MathML, included as raw HTML.
+pub fn resolve(blocks: &mut Vec<ir::Block>) {
+    ir::MutVisitor::visit_vec_block(&mut Resolver, blocks);
+}
+
+/// Converts math into raw HTML containing MathML.
+struct Resolver;
+
+impl ir::MutVisitor for Resolver {
+    fn visit_inline(&mut self, inline: &mut ir::Inline) {
+        match inline {
+            ir::Inline::Math(kind, text) => {
+                let display = matches!(kind, ir::MathType::DisplayMath);
+                let html = mathml(text, display);
+
+                *inline = ir::Inline::RawInline(ir::Format("html".to_owned()), html);
+            }
+            _ => self.walk_inline(inline),
+        }
+    }
+}
+
+/// Renders a math node as a single MathML element.
+///
+/// # Arguments
+/// * `math` - The node to render
+/// * `variant` - The font variant of the node, e.g. inside of `\mathbf`
+/// * `out` - The MathML rendered so far
+fn render(math: &Math, variant: Option<Variant>, out: &mut String) {
+    match math {
+        Math::Row(nodes) if nodes.len() == 1 => render(&nodes[0], variant, out),
+        Math::Row(nodes) => {
+            out.push_str("<mrow>");
+            nodes.iter().for_each(|node| render(node, variant, out));
+            out.push_str("</mrow>");
+        }
+        Math::Ident(name) => match variant {
+            Some(Variant::Normal) => element(out, r#"mi mathvariant="normal""#, name),
+            Some(variant) => element(out, "mi", &styled(name, variant)),
+            None => element(out, "mi", name),
+        },
+        Math::Number(number) => match variant {
+            Some(variant) => element(out, "mn", &styled(number, variant)),
+            None => element(out, "mn", number),
+        },
+        Math::Operator(op) if DELIMITERS.contains(&op.as_str()) => {
+            element(out, r#"mo stretchy="false""#, op)
+        }
+        Math::Operator(op) | Math::LargeOperator { op, .. } => element(out, "mo", op),
+        Math::Function { name, limits: true } => {
+            element(out, r#"mo movablelimits="true" form="prefix""#, name)
+        }
+        Math::Function { name, .. } => element(out, "mi", name),
+        Math::Text(text) => match variant {
+            Some(variant) => element(out, "mtext", &styled(text, variant)),
+            None => element(out, "mtext", text),
+        },
+        Math::Space(width) => {
+            let _ = write!(out, r#"<mspace width="{width}em"/>"#);
+        }
+        Math::Frac { num, den, line } => {
+            out.push_str(if *line {
+                "<mfrac>"
+            } else {
+                r#"<mfrac linethickness="0">"#
+            });
+            render(num, variant, out);
+            render(den, variant, out);
+            out.push_str("</mfrac>");
+        }
+        Math::Root {
+            index: Some(index),
+            radicand,
+        } => {
+            out.push_str("<mroot>");
+            render(radicand, variant, out);
+            render(index, variant, out);
+            out.push_str("</mroot>");
+        }
+        Math::Root {
+            index: None,
+            radicand,
+        } => {
+            out.push_str("<msqrt>");
+            render(radicand, variant, out);
+            out.push_str("</msqrt>");
+        }
+        Math::Scripts { base, sub, sup } => {
+            // Limits are displayed as scripts in inline math, as operators with limits are movable
+            let limits = matches!(
+                **base,
+                Math::LargeOperator { limits: true, .. } | Math::Function { limits: true, .. }
+            );
+
+            let tag = match (sub.is_some(), sup.is_some(), limits) {
+                (true, true, false) => "msubsup",
+                (true, false, false) => "msub",
+                (false, _, false) => "msup",
+                (true, true, true) => "munderover",
+                (true, false, true) => "munder",
+                (false, _, true) => "mover",
+            };
+
+            let _ = write!(out, "<{tag}>");
+            render(base, variant, out);
+            sub.iter()
+                .chain(sup)
+                .for_each(|script| render(script, variant, out));
+            let _ = write!(out, "</{tag}>");
+        }
+        Math::Accent { base, accent, over } => {
+            let (tag, attr) = match over {
+                true => ("mover", "accent"),
+                false => ("munder", "accentunder"),
+            };
+
+            let _ = write!(out, r#"<{tag} {attr}="true">"#);
+            render(base, variant, out);
+            element(out, "mo", accent);
+            let _ = write!(out, "</{tag}>");
+        }
+        Math::Fenced { open, close, body } => {
+            out.push_str("<mrow>");
+            fence(out, open);
+            render(body, variant, out);
+            fence(out, close);
+            out.push_str("</mrow>");
+        }
+        Math::Styled { variant, body } => render(body, Some(*variant), out),
+        Math::Table {
+            rows,
+            open,
+            close,
+            columns,
+        } => {
+            out.push_str("<mrow>");
+            fence(out, open);
+            out.push_str("<mtable>");
+
+            for row in rows {
+                out.push_str("<mtr>");
+
+                for (i, cell) in row.iter().enumerate() {
+                    match (columns, i % 2) {
+                        (Columns::Centered, _) => out.push_str("<mtd>"),
+                        (Columns::Left, _) | (Columns::Aligned, 1) => {
+                            out.push_str(r#"<mtd columnalign="left">"#)
+                        }
+                        (Columns::Aligned, _) => out.push_str(r#"<mtd columnalign="right">"#),
+                    }
+
+                    render(cell, variant, out);
+                    out.push_str("</mtd>");
+                }
+
+                out.push_str("</mtr>");
+            }
+
+            out.push_str("</mtable>");
+            fence(out, close);
+            out.push_str("</mrow>");
+        }
+        Math::Unsupported(command) => {
+            out.push_str("<merror>");
+            element(out, "mtext", command);
+            out.push_str("</merror>");
+        }
+    }
+}
+
+/// Renders an element containing text, the tag may contain attributes.
+fn element(out: &mut String, tag: &str, text: &str) {
+    let name = tag.split(' ').next().unwrap_or(tag);
+    let _ = write!(out, "<{tag}>{}</{name}>", escape(text));
+}
+
+/// Renders a stretching delimiter, nothing for an invisible one.
+fn fence(out: &mut String, delimiter: &str) {
+    if !delimiter.is_empty() {
+        element(out, r#"mo fence="true" stretchy="true""#, delimiter);
+    }
+}
+
+/// Escapes the characters of text which are special in XML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Replaces letters and digits with the mathematical symbols of a font variant, e.g. `𝐱` for `x`.
+///
+/// Browsers only support the `normal` variant of MathML, so other variants
+/// are displayed with the symbols of the Mathematical Alphanumeric Symbols block.
+fn styled(text: &str, variant: Variant) -> String {
+    text.chars().map(|c| styled_char(c, variant)).collect()
+}
+
+/// Returns the mathematical symbol of a letter or digit in a font variant.
+fn styled_char(c: char, variant: Variant) -> char {
+    // Symbols which were encoded before the block and are missing from it
+    let encoded = match (variant, c) {
+        (Variant::Italic, 'h') => Some('ℎ'),
+        (Variant::Script, 'B') => Some('ℬ'),
+        (Variant::Script, 'E') => Some('ℰ'),
+        (Variant::Script, 'F') => Some('ℱ'),
+        (Variant::Script, 'H') => Some('ℋ'),
+        (Variant::Script, 'I') => Some('ℐ'),
+        (Variant::Script, 'L') => Some('ℒ'),
+        (Variant::Script, 'M') => Some('ℳ'),
+        (Variant::Script, 'R') => Some('ℛ'),
+        (Variant::Script, 'e') => Some('ℯ'),
+        (Variant::Script, 'g') => Some('ℊ'),
+        (Variant::Script, 'o') => Some('ℴ'),
+        (Variant::Fraktur, 'C') => Some('ℭ'),
+        (Variant::Fraktur, 'H') => Some('ℌ'),
+        (Variant::Fraktur, 'I') => Some('ℑ'),
+        (Variant::Fraktur, 'R') => Some('ℜ'),
+        (Variant::Fraktur, 'Z') => Some('ℨ'),
+        (Variant::DoubleStruck, 'C') => Some('ℂ'),
+        (Variant::DoubleStruck, 'H') => Some('ℍ'),
+        (Variant::DoubleStruck, 'N') => Some('ℕ'),
+        (Variant::DoubleStruck, 'P') => Some('ℙ'),
+        (Variant::DoubleStruck, 'Q') => Some('ℚ'),
+        (Variant::DoubleStruck, 'R') => Some('ℝ'),
+        (Variant::DoubleStruck, 'Z') => Some('ℤ'),
+        _ => None,
+    };
+
+    if let Some(encoded) = encoded {
+        return encoded;
+    }
+
+    // The first uppercase letter, lowercase letter and digit of the variant
+    let (upper, lower, digit) = match variant {
+        Variant::Normal => return c,
+        Variant::Bold => (0x1D400, 0x1D41A, Some(0x1D7CE)),
+        Variant::Italic => (0x1D434, 0x1D44E, None),
+        Variant::Script => (0x1D49C, 0x1D4B6, None),
+        Variant::Fraktur => (0x1D504, 0x1D51E, None),
+        Variant::DoubleStruck => (0x1D538, 0x1D552, Some(0x1D7D8)),
+        Variant::SansSerif => (0x1D5A0, 0x1D5BA, Some(0x1D7E2)),
+        Variant::Monospace => (0x1D670, 0x1D68A, Some(0x1D7F6)),
+    };
+
+    let code = match (c, digit) {
+        ('A'..='Z', _) => upper + (c as u32 - 'A' as u32),
+        ('a'..='z', _) => lower + (c as u32 - 'a' as u32),
+        ('0'..='9', Some(digit)) => digit + (c as u32 - '0' as u32),
+        _ => return c,
+    };
+
+    char::from_u32(code).unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders math without the surrounding `math` element and annotation.
+    fn body(text: &str) -> String {
+        let mut out = String::new();
+        render(&math::parse(text).math, None, &mut out);
+        out
+    }
+
+    #[test]
+    fn document() {
+        assert_eq!(
+            mathml("a<b", true),
+            format!(
+                r#"<math xmlns="{NAMESPACE}" display="block"><semantics><mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi></mrow><annotation encoding="application/x-tex">a&lt;b</annotation></semantics></math>"#
+            )
+        );
+        assert!(mathml("x", false).contains(r#"display="inline""#));
+    }
+
+    #[test]
+    fn scripts_and_limits() {
+        assert_eq!(
+            body("x_i^2"),
+            "<msubsup><mi>x</mi><mi>i</mi><mn>2</mn></msubsup>"
+        );
+        assert_eq!(body("\\sum_{i}"), "<munder><mo>∑</mo><mi>i</mi></munder>");
+        assert_eq!(
+            body("\\lim_{n}"),
+            r#"<munder><mo movablelimits="true" form="prefix">lim</mo><mi>n</mi></munder>"#
+        );
+    }
+
+    #[test]
+    fn fractions_and_roots() {
+        assert_eq!(body("\\frac12"), "<mfrac><mn>1</mn><mn>2</mn></mfrac>");
+        assert_eq!(body("\\sqrt[3]x"), "<mroot><mi>x</mi><mn>3</mn></mroot>");
+        assert_eq!(
+            body("\\binom nk"),
+            r#"<mrow><mo fence="true" stretchy="true">(</mo><mfrac linethickness="0"><mi>n</mi><mi>k</mi></mfrac><mo fence="true" stretchy="true">)</mo></mrow>"#
+        );
+    }
+
+    #[test]
+    fn delimiters() {
+        assert_eq!(body("("), r#"<mo stretchy="false">(</mo>"#);
+        assert_eq!(
+            body("\\left. x \\right|"),
+            r#"<mrow><mi>x</mi><mo fence="true" stretchy="true">|</mo></mrow>"#
+        );
+    }
+
+    #[test]
+    fn environments() {
+        assert_eq!(
+            body("\\begin{aligned} a &= b \\end{aligned}"),
+            r#"<mrow><mtable><mtr><mtd columnalign="right"><mi>a</mi></mtd><mtd columnalign="left"><mrow><mo>=</mo><mi>b</mi></mrow></mtd></mtr></mtable></mrow>"#
+        );
+    }
+
+    #[test]
+    fn variants() {
+        assert_eq!(body("\\mathbf{x1}"), "<mrow><mi>𝐱</mi><mn>𝟏</mn></mrow>");
+        assert_eq!(body("\\mathrm x"), r#"<mi mathvariant="normal">x</mi>"#);
+        assert_eq!(styled("RZ", Variant::DoubleStruck), "ℝℤ");
+        assert_eq!(styled("h1", Variant::Italic), "ℎ1");
+        assert_eq!(styled("α", Variant::Bold), "α");
+    }
+
+    #[test]
+    fn unsupported_commands() {
+        assert_eq!(body("\\foo"), "<merror><mtext>\\foo</mtext></merror>");
+        assert_eq!(
+            body("\\text{\"a\" & b}"),
+            "<mtext>&quot;a&quot; &amp; b</mtext>"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use ecow::EcoString;
+use std::collections::BTreeSet;
+
+use crate::{ir, label};
+
+/// The class of the blocks created for outlines, which are filled after evaluation
+const OUTLINE_CLASS: &str = "outline";
+
+/// A heading of the document listed by outlines.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// The level of the heading, starting at one
+    level: usize,
+    /// The displayed number of the heading, if it is numbered
+    number: Option<EcoString>,
+    /// The content of the heading without its number
+    content: ir::Content,
+    /// The identifier of the heading to link to
+    ident: String,
+}
+
+/// How an outline is displayed.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The title displayed above the outline, empty for none
+    pub title: EcoString,
+    /// The deepest level of headings listed
+    pub depth: usize,
+    /// Whether the numbers of numbered headings are displayed
+    pub numbered: bool,
+}
+
+/// The headings of the document and the outlines listing them.
+///
+/// Outlines may appear before the headings they list, so a placeholder is inserted
+/// while the document is evaluated and filled once all headings are known.
+#[derive(Debug, Clone, Default)]
+pub struct Outline {
+    /// The headings in document order
+    entries: Vec<Entry>,
+    /// The options of each outline, indexed by their placeholder
+    outlines: Vec<Options>,
+    /// The identifiers given to headings
+    idents: BTreeSet<String>,
+}
+
+impl Outline {
+    /// Adds a heading to the outline.
+    ///
+    /// Headings without a label are identified by their text, e.g. `getting-started`.
+    ///
+    /// # Arguments
+    /// * `level` - The level of the heading
+    /// * `number` - The displayed number of the heading, if it is numbered
+    /// * `content` - The content of the heading without its number
+    /// * `label` - The label of the heading, if any
+    ///
+    /// # Returns
+    /// The identifier of the heading
+    pub fn push(
+        &mut self,
+        level: usize,
+        number: Option<EcoString>,
+        mut content: ir::Content,
+        label: Option<&str>,
+    ) -> String {
+        // The space separating the heading from its label is not listed
+        while matches!(content.last(), Some(ir::Inline::Space)) {
+            content.pop();
+        }
+
+        let ident = match label {
+            Some(label) => label.to_owned(),
+            None => self.unique(slug(&label::plain_text(&content))),
+        };
+
+        self.idents.insert(ident.clone());
+        self.entries.push(Entry {
+            level,
+            number,
+            content,
+            ident: ident.clone(),
+        });
+
+        ident
+    }
+
+    /// Returns the number of the last heading, or its content if it is not numbered.
+    pub fn current(&self) -> Option<ir::Content> {
+        self.entries.last().map(|entry| match &entry.number {
+            Some(number) => vec![ir::Inline::Str(number.to_string())],
+            None => entry.content.clone(),
+        })
+    }
+
+    /// Creates a placeholder for an outline, which is filled by `Outline::resolve`.
+    pub fn placeholder(&mut self, options: Options) -> ir::Block {
+        let attr = ir::AttrBuilder::new()
+            .class(OUTLINE_CLASS)
+            .attr("index", self.outlines.len().to_string())
+            .build();
+
+        self.outlines.push(options);
+        ir::Block::Div(attr, Vec::new())
+    }
+
+    /// Fills all outline placeholders with a nested list of links to the headings.
+    pub fn resolve(&self, blocks: &mut Vec<ir::Block>) {
+        ir::MutVisitor::visit_vec_block(&mut Resolver { outline: self }, blocks);
+    }
+
+    /// Renders the outline with the given options.
+    fn render(&self, options: &Options) -> Vec<ir::Block> {
+        let mut blocks = Vec::new();
+
+        if !options.title.is_empty() {
+            let attr = ir::AttrBuilder::new().class("unnumbered").build();
+            let title = vec![ir::Inline::Str(options.title.to_string())];
+            blocks.push(ir::Block::Header(1, attr, title));
+        }
+
+        let entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.level <= options.depth)
+            .collect::<Vec<_>>();
+
+        if !entries.is_empty() {
+            blocks.push(ir::Block::BulletList(list_items(&entries, options)));
+        }
+
+        blocks
+    }
+
+    /// Makes an identifier unique by appending a number if it is already used.
+    fn unique(&self, ident: String) -> String {
+        if !self.idents.contains(&ident) {
+            return ident;
+        }
+
+        (1..)
+            .map(|n| format!("{ident}-{n}"))
+            .find(|candidate| !self.idents.contains(candidate))
+            .unwrap()
+    }
+}
+
+/// Creates the list items for headings, nesting deeper headings below the previous heading.
+fn list_items(entries: &[&Entry], options: &Options) -> Vec<Vec<ir::Block>> {
+    let mut items = Vec::new();
+    let mut i = 0;
+
+    while i < entries.len() {
+        let entry = entries[i];
+        let end = entries[i + 1..]
+            .iter()
+            .position(|next| next.level <= entry.level)
+            .map_or(entries.len(), |pos| i + 1 + pos);
+
+        let mut content = Vec::new();
+
+        if let Some(number) = entry.number.as_ref().filter(|_| options.numbered) {
+            content.push(ir::Inline::Str(number.to_string()));
+            content.push(ir::Inline::Space);
+        }
+
+        content.extend(entry.content.iter().cloned());
+
+        let target = (format!("#{}", entry.ident), String::new());
+        let link = ir::Inline::Link(ir::AttrBuilder::empty(), content, target);
+        let mut blocks = vec![ir::Block::Plain(vec![link])];
+
+        let children = &entries[i + 1..end];
+
+        if !children.is_empty() {
+            blocks.push(ir::Block::BulletList(list_items(children, options)));
+        }
+
+        items.push(blocks);
+        i = end;
+    }
+
+    items
+}
+
+/// Creates an identifier from text, e.g. `Getting Started!` becomes `getting-started`.
+pub(crate) fn slug(text: &str) -> String {
+    let mut out = String::new();
+
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            out.push(c);
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+
+    while out.ends_with('-') {
+        out.pop();
+    }
+
+    if out.is_empty() {
+        out.push_str("section");
+    }
+
+    out
+}
+
+/// Replaces the outline placeholders with the rendered outlines.
+struct Resolver<'a> {
+    /// The outline to render the placeholders with
+    outline: &'a Outline,
+}
+
+impl ir::MutVisitor for Resolver<'_> {
+    fn visit_block(&mut self, block: &mut ir::Block) {
+        if let ir::Block::Div((_, classes, pairs), content) = block {
+            let options = pairs
+                .iter()
+                .find(|(key, _)| key == "index")
+                .and_then(|(_, index)| index.parse::<usize>().ok())
+                .and_then(|index| self.outline.outlines.get(index))
+                .filter(|_| classes.iter().any(|class| class == OUTLINE_CLASS));
+
+            if let Some(options) = options {
+                *content = self.outline.render(options);
+                return;
+            }
+        }
+
+        self.walk_block(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> ir::Content {
+        vec![ir::Inline::Str(s.into())]
+    }
+
+    fn options(depth: usize, numbered: bool) -> Options {
+        Options {
+            title: EcoString::new(),
+            depth,
+            numbered,
+        }
+    }
+
+    /// Returns the link texts of a rendered outline, indented by their nesting.
+    fn lines(blocks: &[ir::Block]) -> Vec<String> {
+        fn walk(items: &[Vec<ir::Block>], depth: usize, out: &mut Vec<String>) {
+            for item in items {
+                for block in item {
+                    match block {
+                        ir::Block::Plain(inlines) => out.push(format!(
+                            "{}{}",
+                            "  ".repeat(depth),
+                            label::plain_text(inlines)
+                        )),
+                        ir::Block::BulletList(items) => walk(items, depth + 1, out),
+                        block => panic!("unexpected {block:?}"),
+                    }
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+
+        for block in blocks {
+            if let ir::Block::BulletList(items) = block {
+                walk(items, 0, &mut out);
+            }
+        }
+
+        out
+    }
+
+    fn outline() -> Outline {
+        let mut outline = Outline::default();
+        outline.push(1, Some("1".into()), text("Intro"), None);
+        outline.push(2, Some("1.1".into()), text("Goals"), None);
+        outline.push(3, None, text("Details"), None);
+        outline.push(1, Some("2".into()), text("Usage"), None);
+        outline
+    }
+
+    #[test]
+    fn slugs() {
+        assert_eq!(slug("Getting Started!"), "getting-started");
+        assert_eq!(slug("  Über  -- Größe "), "über-größe");
+        assert_eq!(slug("?!"), "section");
+        assert_eq!(slug(""), "section");
+    }
+
+    #[test]
+    fn unique_idents() {
+        let mut outline = Outline::default();
+
+        assert_eq!(outline.push(1, None, text("Intro"), None), "intro");
+        assert_eq!(outline.push(1, None, text("Intro"), None), "intro-1");
+        assert_eq!(outline.push(1, None, text("Intro"), None), "intro-2");
+        assert_eq!(
+            outline.push(1, None, text("Intro"), Some("custom")),
+            "custom"
+        );
+    }
+
+    #[test]
+    fn trailing_spaces_are_not_listed() {
+        let mut outline = Outline::default();
+        let mut content = text("Intro");
+        content.extend([ir::Inline::Space, ir::Inline::Space]);
+
+        outline.push(1, None, content, Some("intro"));
+
+        assert_eq!(outline.current(), Some(text("Intro")));
+    }
+
+    #[test]
+    fn current_heading() {
+        let mut outline = Outline::default();
+        assert_eq!(outline.current(), None);
+
+        outline.push(1, Some("3".into()), text("Intro"), None);
+        assert_eq!(outline.current(), Some(text("3")));
+    }
+
+    #[test]
+    fn nested_entries() {
+        let outline = outline();
+
+        assert_eq!(
+            lines(&outline.render(&options(3, true))),
+            ["1 Intro", "  1.1 Goals", "    Details", "2 Usage"]
+        );
+        assert_eq!(
+            lines(&outline.render(&options(1, false))),
+            ["Intro", "Usage"]
+        );
+        assert!(Outline::default().render(&options(3, true)).is_empty());
+    }
+
+    #[test]
+    fn placeholders_are_filled() {
+        let mut outline = outline();
+        let mut blocks = vec![outline.placeholder(Options {
+            title: "Contents".into(),
+            depth: 1,
+            numbered: true,
+        })];
+
+        outline.resolve(&mut blocks);
+
+        let ir::Block::Div(_, content) = &blocks[0] else {
+            panic!("unexpected {:?}", blocks[0]);
+        };
+        assert!(
+            matches!(&content[0], ir::Block::Header(1, _, title) if *title == text("Contents"))
+        );
+        assert_eq!(lines(content), ["1 Intro", "2 Usage"]);
+    }
+}
@@ -39,3 +39,43 @@ pub enum Output {
     /// Output to standard output
     Stdout,
 }
+
+/// The output format a document is compiled to.
+///
+/// The target is known while the document is evaluated, so that content
+/// can be included for some formats only, e.g. interactive content in HTML.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Target {
+    /// HTML format
+    #[default]
+    Html,
+    /// PDF format
+    Pdf,
+    /// Microsoft Word DOCX format
+    Docx,
+    /// JSON format (Pandoc intermediate representation)
+    Json,
+}
+
+impl Target {
+    /// All output formats
+    pub const ALL: [Self; 4] = [Self::Html, Self::Pdf, Self::Docx, Self::Json];
+
+    /// Returns the name of the target, e.g. `html`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Pdf => "pdf",
+            Self::Docx => "docx",
+            Self::Json => "json",
+        }
+    }
+
+    /// Returns the target with the name, e.g. `html`.
+    ///
+    /// # Returns
+    /// The target, or None if there is no target with the name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| target.name() == name)
+    }
+}
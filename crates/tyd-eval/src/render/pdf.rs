@@ -1,6 +1,6 @@
 use pandoc::{InputFormat, InputKind, OutputFormat, OutputKind, Pandoc, PandocOption};
 
-use crate::{ir, tracer::Tracer};
+use crate::{index, ir, tracer::Tracer};
 
 use super::{Output, Render};
 
@@ -26,7 +26,8 @@ impl Render for PdfCompiler {
     /// - Does not support stdout output
     /// - Uses the Typst PDF engine
     /// - Applies the "templates/default.typst" template
-    fn render(pandoc: ir::Pandoc, output: Output, tracer: &mut Tracer) {
+    /// - Displays page numbers in the index
+    fn render(mut pandoc: ir::Pandoc, output: Output, tracer: &mut Tracer) {
         let dest = match output {
             Output::File(path) => path,
             Output::Stdout => {
@@ -35,6 +36,8 @@ impl Render for PdfCompiler {
             }
         };
 
+        index::page_references(&mut pandoc);
+
         let contents = pandoc.to_json();
         let mut pandoc = Pandoc::new();
 
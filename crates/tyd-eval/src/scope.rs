@@ -1,11 +1,17 @@
-use ecow::EcoString;
-use std::collections::{
-    btree_map::{IntoKeys, IntoValues, Keys, Values},
-    BTreeMap,
+use ecow::{eco_format, EcoString};
+use std::{
+    collections::{
+        btree_map::{IntoKeys, IntoValues, Keys, Values},
+        BTreeMap,
+    },
+    iter,
+    sync::Arc,
 };
 
 use crate::{
+    func::Func,
     ir,
+    show::ShowRule,
     value::{Type, TypeCast, Value},
     Plugin,
 };
@@ -14,12 +20,19 @@ use crate::{
 ///
 /// A `Scope` can have a parent scope, enabling nested scope chains for variable
 /// lookups. Values are stored in a tree map for ordered access.
+///
+/// Each scope level also holds the default arguments of functions set by set rules
+/// and the show rules, which apply within the level and the levels nested in it.
 #[derive(Debug, Clone, Default)]
 pub struct Scope {
     /// Optional parent scope for hierarchical lookups
     parent: Option<Box<Self>>,
     /// The map of names to values in the current scope level
     scope: BTreeMap<EcoString, Value>,
+    /// The default arguments set in the current scope level, by function name
+    defaults: BTreeMap<EcoString, ir::Map>,
+    /// The show rules of the current scope level in definition order
+    shows: Vec<Arc<ShowRule>>,
 }
 
 impl Scope {
@@ -33,6 +46,8 @@ impl Scope {
         Self {
             parent: Some(Box::new(from)),
             scope: ir::Map::new(),
+            defaults: BTreeMap::new(),
+            shows: Vec::new(),
         }
     }
 
@@ -43,13 +58,13 @@ impl Scope {
 
     /// Initializes this scope with a plugin's predefined values.
     ///
-    /// # Type Parameters
-    /// * `P` - A type that implements the `Plugin` trait
+    /// # Arguments
+    /// * `plugin` - The plugin, which may carry its own configuration
     ///
     /// # Returns
     /// * `&mut Self` - Self reference for method chaining
-    pub fn register<P: Plugin>(&mut self) -> &mut Self {
-        P::init(self);
+    pub fn register(&mut self, plugin: impl Plugin) -> &mut Self {
+        plugin.init(self);
         self
     }
 
@@ -66,6 +81,64 @@ impl Scope {
         self
     }
 
+    /// Adds input variables given as text, e.g. on the command line, to the scope.
+    ///
+    /// The inputs are available in the `sys.inputs` map and as top-level names,
+    /// which replace default values of the same name, e.g. a default `title`.
+    /// Inputs named like a function or module, e.g. `image`, or `sys` itself,
+    /// do not replace it and are only available in `sys.inputs`.
+    /// The type of each value is inferred from its text: `true` and `false` are booleans,
+    /// numbers which are displayed exactly like their text are integers or floats,
+    /// and other text, or text in double quotes, is a string.
+    ///
+    /// # Arguments
+    /// * `inputs` - The names and the text of the values
+    ///
+    /// # Returns
+    /// * `Vec<EcoString>` - The names of the inputs which are only available in `sys.inputs`
+    pub fn insert_inputs<K, V>(
+        &mut self,
+        inputs: impl IntoIterator<Item = (K, V)>,
+    ) -> Vec<EcoString>
+    where
+        K: Into<EcoString>,
+        V: AsRef<str>,
+    {
+        let mut sys = match self.scope.remove("sys") {
+            Some(Value::Map(sys)) => sys,
+            _ => ir::Map::new(),
+        };
+        let mut map = match sys.remove("inputs") {
+            Some(Value::Map(map)) => map,
+            _ => ir::Map::new(),
+        };
+        let mut hidden = Vec::new();
+
+        for (name, text) in inputs {
+            let name = name.into();
+            let value = infer(text.as_ref());
+
+            let reserved = name == "sys"
+                || matches!(
+                    self.get(&name),
+                    Some(Value::Func(_) | Value::Closure(_) | Value::Map(_))
+                );
+
+            if reserved {
+                hidden.push(name.clone());
+            } else {
+                self.insert(name.clone(), value.clone());
+            }
+
+            map.insert(name, value);
+        }
+
+        sys.insert("inputs".into(), Value::Map(map));
+        self.insert("sys".into(), sys);
+
+        hidden
+    }
+
     /// Removes all name-value pairs from the current scope level.
     pub fn clear(&mut self) {
         self.scope.clear();
@@ -91,6 +164,18 @@ impl Scope {
         self.scope.into_values()
     }
 
+    /// Returns the functions of the current scope level with their names, including the
+    /// functions of modules, which are named by their path, e.g. `str.upper`.
+    pub fn functions(&self) -> Vec<(EcoString, Func)> {
+        let mut functions = Vec::new();
+
+        for (name, value) in &self.scope {
+            collect_functions(name.clone(), value, &mut functions);
+        }
+
+        functions
+    }
+
     /// Looks up a value by name, searching in parent scopes if not found in current scope.
     ///
     /// # Arguments
@@ -186,6 +271,47 @@ impl Scope {
         self.scope.extend(iter);
     }
 
+    /// Sets default arguments of a function in the current scope level.
+    ///
+    /// Arguments which were set before are replaced, others are kept.
+    ///
+    /// # Arguments
+    /// * `func` - The name of the function
+    /// * `args` - The default arguments by parameter name
+    pub fn set_defaults(&mut self, func: EcoString, args: ir::Map) {
+        self.defaults.entry(func).or_default().extend(args);
+    }
+
+    /// Returns the default arguments of a function set in this and all parent scope levels,
+    /// where arguments set in nested levels take precedence.
+    ///
+    /// # Arguments
+    /// * `func` - The name of the function
+    pub fn defaults(&self, func: &str) -> ir::Map {
+        let mut defaults = match self.parent.as_ref() {
+            Some(parent) => parent.defaults(func),
+            None => ir::Map::new(),
+        };
+
+        if let Some(args) = self.defaults.get(func) {
+            defaults.extend(args.clone());
+        }
+
+        defaults
+    }
+
+    /// Adds a show rule to the current scope level.
+    pub fn add_show(&mut self, rule: ShowRule) {
+        self.shows.push(Arc::new(rule));
+    }
+
+    /// Returns the show rules of this and all parent scope levels,
+    /// starting with the most recently added rule of the innermost level.
+    pub fn shows(&self) -> impl Iterator<Item = &Arc<ShowRule>> {
+        iter::successors(Some(self), |scope| scope.parent.as_deref())
+            .flat_map(|scope| scope.shows.iter().rev())
+    }
+
     /// Creates a new nested scope level, making the current scope the parent.
     pub fn enter(&mut self) {
         let parent = std::mem::replace(self, Self::empty());
@@ -210,3 +336,120 @@ impl Scope {
         self.scope
     }
 }
+
+/// Infers the value of an input variable from its text.
+///
+/// Numbers are only inferred if they are displayed exactly like the text,
+/// so that e.g. `1.10` or `007` stay strings instead of losing digits.
+fn infer(text: &str) -> Value {
+    match text {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') => {
+            Value::Str(text[1..text.len() - 1].into())
+        }
+        _ => match (text.parse::<i64>(), text.parse::<f64>()) {
+            (Ok(int), _) if int.to_string() == text => Value::Int(int),
+            (_, Ok(float)) if float.is_finite() && float.to_string() == text => Value::Float(float),
+            _ => Value::Str(text.into()),
+        },
+    }
+}
+
+/// Collects the value if it is a function, or the functions of the value if it is a module.
+fn collect_functions(name: EcoString, value: &Value, functions: &mut Vec<(EcoString, Func)>) {
+    match value {
+        Value::Func(func) => functions.push((name, func.clone())),
+        Value::Map(module) => {
+            for (field, value) in module {
+                collect_functions(eco_format!("{name}.{field}"), value, functions);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::BuiltinPlugin;
+
+    fn inputs(scope: &Scope) -> ir::Map {
+        match scope.get("sys") {
+            Some(Value::Map(mut sys)) => match sys.remove("inputs") {
+                Some(Value::Map(inputs)) => inputs,
+                other => panic!("{other:?} is not a map"),
+            },
+            other => panic!("{other:?} is not a map"),
+        }
+    }
+
+    #[test]
+    fn infer_scalars() {
+        assert!(matches!(infer("true"), Value::Bool(true)));
+        assert!(matches!(infer("false"), Value::Bool(false)));
+        assert!(matches!(infer("12"), Value::Int(12)));
+        assert!(matches!(infer("-3"), Value::Int(-3)));
+        assert!(matches!(infer("1.5"), Value::Float(f) if f == 1.5));
+    }
+
+    #[test]
+    fn infer_keeps_text_which_does_not_round_trip() {
+        for text in [
+            "1.10", "007", "+5", "1e3", "1.0", "NaN", "inf", "1.2.3", "",
+        ] {
+            assert!(matches!(infer(text), Value::Str(s) if s == text), "{text}");
+        }
+    }
+
+    #[test]
+    fn infer_quoted_strings() {
+        assert!(matches!(infer("\"1.10\""), Value::Str(s) if s == "1.10"));
+        assert!(matches!(infer("\""), Value::Str(s) if s == "\""));
+    }
+
+    #[test]
+    fn inputs_replace_defaults() {
+        let mut scope = Scope::empty();
+        scope.with("title", "Default title");
+
+        let hidden = scope.insert_inputs([("title", "Manual"), ("version", "1.10")]);
+
+        assert!(hidden.is_empty());
+        assert!(matches!(scope.get("title"), Some(Value::Str(s)) if s == "Manual"));
+        assert!(matches!(scope.get("version"), Some(Value::Str(s)) if s == "1.10"));
+        assert!(matches!(&inputs(&scope)["title"], Value::Str(s) if s == "Manual"));
+    }
+
+    #[test]
+    fn inputs_do_not_replace_builtins() {
+        let mut scope = Scope::empty();
+        scope.register(BuiltinPlugin);
+
+        let hidden = scope.insert_inputs([("image", "1"), ("str", "2"), ("sys", "3")]);
+
+        assert_eq!(hidden, ["image", "str", "sys"]);
+        assert!(matches!(scope.get("image"), Some(Value::Func(_))));
+        assert!(matches!(scope.get("str"), Some(Value::Map(_))));
+        assert!(matches!(&inputs(&scope)["image"], Value::Int(1)));
+        assert!(matches!(&inputs(&scope)["sys"], Value::Int(3)));
+    }
+
+    #[test]
+    fn functions_of_modules() {
+        let mut scope = Scope::empty();
+        scope.register(BuiltinPlugin);
+        scope.with("title", "Default title");
+
+        let names: Vec<EcoString> = scope
+            .functions()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        for name in ["image", "str.upper", "list.map", "map.keys", "calc.round"] {
+            assert!(names.iter().any(|n| n == name), "{name}");
+        }
+        assert!(!names.iter().any(|n| n == "title" || n == "str"));
+    }
+}
@@ -0,0 +1,99 @@
+use ecow::EcoString;
+use tyd_core::kind::NodeKind;
+
+use crate::{ir, value::Value};
+
+/// The elements show rules can select, with their name and the fields they can be filtered by
+const ELEMENTS: &[(&str, NodeKind, &[&str])] = &[
+    ("heading", NodeKind::Heading, &["level", "label"]),
+    ("raw", NodeKind::Raw, &["lang", "text"]),
+    ("table", NodeKind::Table, &["columns", "label"]),
+    ("list", NodeKind::List, &[]),
+    ("enum", NodeKind::Enum, &[]),
+    ("terms", NodeKind::Terms, &[]),
+    ("paragraph", NodeKind::Paragraph, &[]),
+    ("quote", NodeKind::Quote, &[]),
+    ("strikeout", NodeKind::Strikeout, &[]),
+    ("emphasis", NodeKind::Emphasis, &[]),
+    ("strong", NodeKind::Strong, &[]),
+    ("subscript", NodeKind::Subscript, &[]),
+    ("supscript", NodeKind::Supscript, &[]),
+    ("link", NodeKind::Link, &["url"]),
+    ("ref", NodeKind::Ref, &["label"]),
+    ("raw_inline", NodeKind::RawInline, &["lang", "text"]),
+    ("math_inline", NodeKind::MathInline, &["text"]),
+];
+
+/// Returns the names of all elements show rules can select.
+pub fn element_names() -> impl Iterator<Item = &'static str> {
+    ELEMENTS.iter().map(|(name, ..)| *name)
+}
+
+/// Returns the kind of element with the given name and the fields it can be filtered by.
+pub fn element(name: &str) -> Option<(NodeKind, &'static [&'static str])> {
+    ELEMENTS
+        .iter()
+        .find(|(element, ..)| *element == name)
+        .map(|(_, kind, fields)| (*kind, *fields))
+}
+
+/// The elements selected by a show rule.
+#[derive(Debug, Clone)]
+pub enum Selector {
+    /// Elements of a kind whose fields are equal to the given values
+    Kind(NodeKind, ir::Map),
+    /// Words equal to the text, ignoring the punctuation around them
+    Text(EcoString),
+}
+
+impl Selector {
+    /// Returns whether the selector matches an element.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of the element
+    /// * `text` - The text of the element, if it is a word
+    /// * `fields` - Computes the fields of the element, which is only done for filtered selectors
+    pub fn matches(
+        &self,
+        kind: NodeKind,
+        text: Option<&str>,
+        fields: impl Fn() -> ir::Map,
+    ) -> bool {
+        match self {
+            Self::Kind(selected, filter) if *selected == kind => {
+                if filter.is_empty() {
+                    return true;
+                }
+
+                let fields = fields();
+
+                filter.iter().all(|(name, expected)| {
+                    fields
+                        .get(name)
+                        .is_some_and(|value| equals(value, expected))
+                })
+            }
+            Self::Text(selected) => kind == NodeKind::Word && text == Some(selected.as_str()),
+            Self::Kind(..) => false,
+        }
+    }
+}
+
+/// Returns whether two field values are equal, which are strings, integers or booleans.
+fn equals(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Str(a), Value::Str(b)) => a == b,
+        (Value::Int(a), Value::Int(b)) => a == b,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// A show rule, which transforms the selected elements.
+#[derive(Debug, Clone)]
+pub struct ShowRule {
+    /// The selected elements
+    pub selector: Selector,
+    /// A function called with the selected element, or the value replacing it
+    pub transform: Value,
+}
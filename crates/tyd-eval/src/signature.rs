@@ -0,0 +1,646 @@
+use ecow::EcoString;
+use std::{collections::VecDeque, fmt::Write, mem};
+use tyd_syntax::Span;
+
+use crate::{
+    error::ArgumentError,
+    ir,
+    scope::Scope,
+    tracer::Tracer,
+    value::{Type, TypeCast, Value},
+};
+
+/// Describes how an argument is passed to a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    /// Passed by position, or by name
+    Positional,
+    /// Passed by name only
+    Named,
+    /// Passed as the trailing content block, or by position or name
+    Content,
+    /// Collects all remaining positional arguments into a list
+    Rest,
+    /// Collects all remaining named arguments into a map
+    NamedRest,
+}
+
+/// A single parameter of a function signature.
+#[derive(Debug, Clone)]
+pub struct Param {
+    /// The name of the parameter
+    pub name: EcoString,
+    /// How arguments are passed to the parameter
+    pub kind: ParamKind,
+    /// The expected type, for rest parameters the type of each element
+    pub ty: Type,
+    /// The default value, the parameter is required if there is none
+    pub default: Option<Value>,
+    /// A description of the parameter
+    pub docs: EcoString,
+}
+
+impl Param {
+    /// Returns whether an argument has to be passed for this parameter.
+    pub fn is_required(&self) -> bool {
+        self.default.is_none() && !matches!(self.kind, ParamKind::Rest | ParamKind::NamedRest)
+    }
+}
+
+/// The declared parameters and return type of a native function.
+///
+/// Arguments of a call are bound to the parameters and type checked
+/// before the function is called, so that it receives them as `Args`.
+///
+/// # Example
+///
+/// ```ignore
+/// let signature = Signature::new("Embeds an image.", Type::Inline)
+///     .named("src", Type::Str, "Path to the image file")
+///     .named_or("alt", Type::Str, "", "Alternative text");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Signature {
+    /// A description of the function
+    pub docs: EcoString,
+    /// The parameters in declaration order
+    pub params: Vec<Param>,
+    /// The type of the returned value
+    pub returns: Type,
+}
+
+impl Signature {
+    /// Creates a new signature without parameters.
+    ///
+    /// # Arguments
+    /// * `docs` - A description of the function
+    /// * `returns` - The type of the returned value
+    pub fn new(docs: impl Into<EcoString>, returns: Type) -> Self {
+        Self {
+            docs: docs.into(),
+            params: Vec::new(),
+            returns,
+        }
+    }
+
+    /// Adds a parameter to the signature.
+    fn param(
+        mut self,
+        name: &str,
+        kind: ParamKind,
+        ty: Type,
+        default: Option<Value>,
+        docs: &str,
+    ) -> Self {
+        self.params.push(Param {
+            name: name.into(),
+            kind,
+            ty,
+            default,
+            docs: docs.into(),
+        });
+        self
+    }
+
+    /// Adds a required positional parameter.
+    pub fn positional(self, name: &str, ty: Type, docs: &str) -> Self {
+        self.param(name, ParamKind::Positional, ty, None, docs)
+    }
+
+    /// Adds an optional positional parameter with a default value.
+    ///
+    /// An optional positional parameter only takes an argument if enough arguments
+    /// are left for the required positional parameters following it.
+    pub fn positional_or(
+        self,
+        name: &str,
+        ty: Type,
+        default: impl Into<Value>,
+        docs: &str,
+    ) -> Self {
+        self.param(name, ParamKind::Positional, ty, Some(default.into()), docs)
+    }
+
+    /// Adds a required named parameter.
+    pub fn named(self, name: &str, ty: Type, docs: &str) -> Self {
+        self.param(name, ParamKind::Named, ty, None, docs)
+    }
+
+    /// Adds an optional named parameter with a default value.
+    pub fn named_or(self, name: &str, ty: Type, default: impl Into<Value>, docs: &str) -> Self {
+        self.param(name, ParamKind::Named, ty, Some(default.into()), docs)
+    }
+
+    /// Adds a required content parameter, which receives the trailing content block.
+    pub fn content(self, name: &str, docs: &str) -> Self {
+        self.param(name, ParamKind::Content, Type::Content, None, docs)
+    }
+
+    /// Adds a parameter collecting the remaining positional arguments of the given type.
+    pub fn rest(self, name: &str, ty: Type, docs: &str) -> Self {
+        self.param(name, ParamKind::Rest, ty, None, docs)
+    }
+
+    /// Adds a parameter collecting the remaining named arguments of the given type.
+    pub fn named_rest(self, name: &str, ty: Type, docs: &str) -> Self {
+        self.param(name, ParamKind::NamedRest, ty, None, docs)
+    }
+
+    /// Returns the parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<&Param> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Checks the default arguments of a set rule.
+    ///
+    /// Only positional and named parameters can be set, with arguments of their type.
+    ///
+    /// # Arguments
+    /// * `args` - The default arguments by parameter name
+    /// * `span` - The span of the set rule for error reporting
+    /// * `tracer` - The tracer for reporting errors
+    ///
+    /// # Returns
+    /// Whether all arguments can be set
+    pub fn check_defaults(&self, args: &ir::Map, span: Span, tracer: &mut Tracer) -> bool {
+        let mut valid = true;
+
+        for (name, value) in args {
+            let param = self
+                .get(name)
+                .filter(|param| matches!(param.kind, ParamKind::Positional | ParamKind::Named));
+
+            let Some(param) = param else {
+                tracer.source_error(span, ArgumentError::UnknownNamed { name: name.clone() });
+                valid = false;
+                continue;
+            };
+
+            // `none` resets a parameter with a default of `none` to it
+            let unset = matches!((value, &param.default), (Value::None, Some(Value::None)));
+
+            if value.ty() != param.ty && !unset {
+                tracer.source_error(
+                    span,
+                    ArgumentError::WrongType {
+                        name: name.clone(),
+                        got: value.ty(),
+                        expected: param.ty.clone(),
+                    },
+                );
+                valid = false;
+            }
+        }
+
+        valid
+    }
+
+    /// Binds the arguments of a call to the parameters.
+    ///
+    /// Missing required arguments and arguments of the wrong type are reported as errors,
+    /// arguments not matching any parameter as warnings. If the signature has no content
+    /// parameter, the content block is passed as the last positional argument.
+    /// Parameters without argument take the default set by a set rule, if any,
+    /// before their declared default.
+    ///
+    /// # Arguments
+    /// * `positional` - The positional arguments in call order
+    /// * `named` - The named arguments
+    /// * `content` - The trailing content block, if any
+    /// * `defaults` - The default arguments set by set rules
+    /// * `span` - The span of the call for error reporting
+    /// * `tracer` - The tracer for reporting errors
+    ///
+    /// # Returns
+    /// The bound arguments, or `None` if an error occurred
+    pub fn bind(
+        &self,
+        positional: Vec<Value>,
+        named: Scope,
+        mut content: Option<Value>,
+        defaults: &ir::Map,
+        span: Span,
+        tracer: &mut Tracer,
+    ) -> Option<Args> {
+        let mut positional = VecDeque::from(positional);
+        let mut named = named.into_inner();
+
+        if !self
+            .params
+            .iter()
+            .any(|param| param.kind == ParamKind::Content)
+        {
+            positional.extend(content.take());
+        }
+
+        let given = positional.len();
+        let mut values = ir::Map::new();
+        let mut failed = false;
+
+        for (i, param) in self.params.iter().enumerate() {
+            let name = &param.name;
+
+            let value = match param.kind {
+                ParamKind::Positional => named.remove(name).or_else(|| {
+                    let required = self.params[i + 1..]
+                        .iter()
+                        .filter(|param| param.kind == ParamKind::Positional && param.is_required())
+                        .count();
+
+                    if param.is_required() || positional.len() > required {
+                        positional.pop_front()
+                    } else {
+                        None
+                    }
+                }),
+                ParamKind::Named => named.remove(name),
+                ParamKind::Content => content
+                    .take()
+                    .or_else(|| named.remove(name))
+                    .or_else(|| positional.pop_front()),
+                ParamKind::Rest => Some(Value::List(positional.drain(..).collect())),
+                ParamKind::NamedRest => Some(Value::Map(mem::take(&mut named))),
+            };
+
+            let value = value.or_else(|| defaults.get(name).cloned());
+
+            // Defaults are not type checked, so that `none` can mark an unset parameter
+            let value = match (value, &param.default) {
+                (Some(value), _) => value,
+                (None, Some(default)) => {
+                    values.insert(name.clone(), default.clone());
+                    continue;
+                }
+                (None, None) => {
+                    tracer.source_error(
+                        span,
+                        ArgumentError::MissingRequired {
+                            name: name.clone(),
+                            ty: param.ty.clone(),
+                        },
+                    );
+                    failed = true;
+                    continue;
+                }
+            };
+
+            let wrong = match &value {
+                Value::List(items) if param.kind == ParamKind::Rest => {
+                    items.iter().map(Value::ty).find(|ty| *ty != param.ty)
+                }
+                Value::Map(items) if param.kind == ParamKind::NamedRest => {
+                    items.values().map(Value::ty).find(|ty| *ty != param.ty)
+                }
+                value => Some(value.ty()).filter(|ty| *ty != param.ty),
+            };
+
+            if let Some(got) = wrong {
+                tracer.source_error(
+                    span,
+                    ArgumentError::WrongType {
+                        name: name.clone(),
+                        got,
+                        expected: param.ty.clone(),
+                    },
+                );
+                failed = true;
+                continue;
+            }
+
+            values.insert(name.clone(), value);
+        }
+
+        let consumed = given - positional.len();
+
+        for pos in consumed..given {
+            tracer.source_warn(span, ArgumentError::UnknownPositional { pos });
+        }
+
+        for name in named.into_keys() {
+            tracer.source_warn(span, ArgumentError::UnknownNamed { name });
+        }
+
+        (!failed).then_some(Args { values })
+    }
+
+    /// Renders the signature as a single line, e.g. `range(start: Int = 0, end: Int) -> List`.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the function
+    pub fn header(&self, name: &str) -> String {
+        let params = self
+            .params
+            .iter()
+            .map(|param| {
+                let prefix = match param.kind {
+                    ParamKind::Rest | ParamKind::NamedRest => "..",
+                    _ => "",
+                };
+
+                match &param.default {
+                    Some(default) => {
+                        format!("{prefix}{}: {} = {}", param.name, param.ty, repr(default))
+                    }
+                    None => format!("{prefix}{}: {}", param.name, param.ty),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{name}({params}) -> {}", self.returns)
+    }
+
+    /// Renders the signature and its documentation as markdown,
+    /// e.g. for hover information or generated reference docs.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the function
+    pub fn markdown(&self, name: &str) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "```tyd\n{}\n```\n", self.header(name));
+        let _ = writeln!(out, "{}", self.docs);
+
+        if !self.params.is_empty() {
+            let _ = writeln!(out, "\n**Parameters**\n");
+        }
+
+        for param in &self.params {
+            let how = match param.kind {
+                ParamKind::Positional => "positional",
+                ParamKind::Named => "named",
+                ParamKind::Content => "content",
+                ParamKind::Rest => "remaining positional",
+                ParamKind::NamedRest => "remaining named",
+            };
+            let required = if param.is_required() {
+                ", required"
+            } else {
+                ""
+            };
+
+            let _ = writeln!(
+                out,
+                "- `{}`: {} ({how}{required}) - {}",
+                param.name, param.ty, param.docs
+            );
+        }
+
+        out
+    }
+}
+
+/// Renders a default value the way it would be written in a document.
+fn repr(value: &Value) -> String {
+    match value {
+        Value::Str(s) => format!("{s:?}"),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::None => "none".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+/// The arguments of a call after they were bound to a `Signature`.
+///
+/// Every parameter of the signature has a value of the declared type,
+/// rest parameters are bound to a list or map of the collected arguments.
+#[derive(Debug, Clone)]
+pub struct Args {
+    /// The bound values by parameter name
+    values: ir::Map,
+}
+
+impl Args {
+    /// Takes the value bound to a parameter.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the parameter
+    ///
+    /// # Panics
+    /// If the parameter is not declared in the signature or
+    /// `T` does not match the declared type of the parameter.
+    pub fn take<T: TypeCast>(&mut self, name: &str) -> T {
+        let value = self
+            .values
+            .remove(name)
+            .unwrap_or_else(|| panic!("parameter '{name}' is not declared in the signature"));
+
+        match T::try_downcast(value) {
+            Ok(value) => value,
+            Err(ty) => panic!("parameter '{name}' of type {ty} does not match its declaration"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tyd_syntax::{source::Source, Spans};
+
+    use super::*;
+
+    fn signature() -> Signature {
+        Signature::new("Test function", Type::None)
+            .positional_or("start", Type::Int, 0, "Optional first")
+            .positional("end", Type::Int, "Required")
+            .named_or("step", Type::Int, 1, "Named")
+    }
+
+    /// Binds the arguments, returning the bound values and the diagnostic messages.
+    fn bind(
+        signature: &Signature,
+        positional: Vec<Value>,
+        named: Vec<(&str, Value)>,
+        content: Option<Value>,
+        defaults: &ir::Map,
+    ) -> (Option<ir::Map>, Vec<String>) {
+        let source = Source::new("test.tyd", "test.tyd", "");
+        let mut tracer = Tracer::new(source.clone(), Spans::from(Vec::new()));
+
+        let mut scope = Scope::empty();
+        for (name, value) in named {
+            scope.insert(name.into(), value);
+        }
+
+        let args = signature.bind(
+            positional,
+            scope,
+            content,
+            defaults,
+            source.end_of_input(),
+            &mut tracer,
+        );
+        let messages = tracer
+            .into_inner()
+            .0
+            .into_iter()
+            .map(|diag| diag.message)
+            .collect();
+
+        (args.map(|args| args.values), messages)
+    }
+
+    fn int(values: &ir::Map, name: &str) -> i64 {
+        match values.get(name) {
+            Some(Value::Int(i)) => *i,
+            value => panic!("{name} is {value:?}"),
+        }
+    }
+
+    #[test]
+    fn optional_positional_needs_enough_arguments() {
+        let (values, messages) = bind(
+            &signature(),
+            vec![Value::Int(5)],
+            vec![],
+            None,
+            &ir::Map::new(),
+        );
+        let values = values.unwrap();
+        assert_eq!((int(&values, "start"), int(&values, "end")), (0, 5));
+        assert!(messages.is_empty());
+
+        let args = vec![Value::Int(2), Value::Int(5)];
+        let (values, _) = bind(
+            &signature(),
+            args,
+            vec![("step", Value::Int(3))],
+            None,
+            &ir::Map::new(),
+        );
+        let values = values.unwrap();
+        assert_eq!(
+            (
+                int(&values, "start"),
+                int(&values, "end"),
+                int(&values, "step")
+            ),
+            (2, 5, 3)
+        );
+    }
+
+    #[test]
+    fn positional_by_name() {
+        let named = vec![("end", Value::Int(5)), ("start", Value::Int(1))];
+        let (values, messages) = bind(&signature(), vec![], named, None, &ir::Map::new());
+        let values = values.unwrap();
+        assert_eq!((int(&values, "start"), int(&values, "end")), (1, 5));
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn defaults_of_set_rules() {
+        let mut defaults = ir::Map::new();
+        defaults.insert("step".into(), Value::Int(2));
+
+        let (values, _) = bind(&signature(), vec![Value::Int(5)], vec![], None, &defaults);
+        assert_eq!(int(&values.unwrap(), "step"), 2);
+
+        let named = vec![("step", Value::Int(4))];
+        let (values, _) = bind(&signature(), vec![Value::Int(5)], named, None, &defaults);
+        assert_eq!(int(&values.unwrap(), "step"), 4);
+    }
+
+    #[test]
+    fn missing_and_wrong_arguments_fail() {
+        let (values, messages) = bind(&signature(), vec![], vec![], None, &ir::Map::new());
+        assert!(values.is_none());
+        assert_eq!(messages, ["Missing Argument end: Int"]);
+
+        let args = vec![Value::Str("a".into())];
+        let (values, messages) = bind(&signature(), args, vec![], None, &ir::Map::new());
+        assert!(values.is_none());
+        assert_eq!(messages.len(), 1);
+    }
+
+    #[test]
+    fn unknown_arguments_are_warnings() {
+        let args = vec![Value::Int(1), Value::Int(2), Value::Int(3)];
+        let named = vec![("other", Value::Int(4))];
+        let (values, messages) = bind(&signature(), args, named, None, &ir::Map::new());
+
+        assert!(values.is_some());
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].contains("other"));
+    }
+
+    #[test]
+    fn content_is_the_last_positional_without_content_parameter() {
+        let content = Some(Value::Int(5));
+        let (values, messages) = bind(&signature(), vec![], vec![], content, &ir::Map::new());
+        assert_eq!(int(&values.unwrap(), "end"), 5);
+        assert!(messages.is_empty());
+
+        let signature = Signature::new("Test function", Type::None)
+            .positional("level", Type::Int, "Level")
+            .content("body", "Body");
+        let content = Some(Value::Content(Vec::new()));
+        let (values, _) = bind(
+            &signature,
+            vec![Value::Int(1)],
+            vec![],
+            content,
+            &ir::Map::new(),
+        );
+        assert!(matches!(
+            values.unwrap().get("body"),
+            Some(Value::Content(_))
+        ));
+    }
+
+    #[test]
+    fn rest_parameters() {
+        let signature = Signature::new("Test function", Type::None)
+            .rest("items", Type::Int, "Items")
+            .named_rest("options", Type::Str, "Options");
+
+        let named = vec![("a", Value::Str("x".into()))];
+        let (values, messages) = bind(
+            &signature,
+            vec![Value::Int(1), Value::Int(2)],
+            named,
+            None,
+            &ir::Map::new(),
+        );
+        let values = values.unwrap();
+        assert!(matches!(values.get("items"), Some(Value::List(items)) if items.len() == 2));
+        assert!(
+            matches!(values.get("options"), Some(Value::Map(options)) if options.contains_key("a"))
+        );
+        assert!(messages.is_empty());
+
+        let args = vec![Value::Int(1), Value::Str("b".into())];
+        let (values, messages) = bind(&signature, args, vec![], None, &ir::Map::new());
+        assert!(values.is_none());
+        assert_eq!(messages.len(), 1);
+    }
+
+    #[test]
+    fn check_defaults() {
+        let source = Source::new("test.tyd", "test.tyd", "");
+        let mut tracer = Tracer::new(source.clone(), Spans::from(Vec::new()));
+        let span = source.end_of_input();
+
+        let mut valid = ir::Map::new();
+        valid.insert("step".into(), Value::Int(2));
+        assert!(signature().check_defaults(&valid, span, &mut tracer));
+
+        let mut wrong = ir::Map::new();
+        wrong.insert("step".into(), Value::Str("2".into()));
+        assert!(!signature().check_defaults(&wrong, span, &mut tracer));
+
+        let mut unknown = ir::Map::new();
+        unknown.insert("other".into(), Value::Int(2));
+        assert!(!signature().check_defaults(&unknown, span, &mut tracer));
+    }
+
+    #[test]
+    #[should_panic(expected = "parameter 'missing' is not declared")]
+    fn take_undeclared_parameter() {
+        let mut args = Args {
+            values: ir::Map::new(),
+        };
+        args.take::<i64>("missing");
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{
+    bibliography::Bibliography, counter::Counters, glossary::Glossary, index::Index, label::Labels,
+    outline::Outline, render::Target,
+};
+
+/// State of the document which is built up while it is evaluated.
+///
+/// The state is owned by the engine and passed to native functions,
+/// so that they can read and update it, e.g. to number figures.
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    /// The counters of the document
+    pub counters: Counters,
+    /// The labels of the document and the references to them
+    pub labels: Labels,
+    /// The headings of the document listed by outlines
+    pub outline: Outline,
+    /// The bibliography of the document and the citations of its entries
+    pub bibliography: Bibliography,
+    /// The glossaries of the document and the uses of their terms
+    pub glossary: Glossary,
+    /// The terms marked for the index
+    pub index: Index,
+    /// The output format the document is compiled to
+    pub target: Target,
+}
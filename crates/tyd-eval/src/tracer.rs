@@ -1,26 +1,69 @@
 use std::fmt;
 
-use miette::{Diagnostic, MietteDiagnostic, Severity};
+use miette::{Diagnostic, MietteDiagnostic, Severity, SourceCode};
 use tyd_core::{id::NodeId, meta::MetaContainer};
-use tyd_syntax::{error::SourceDiagnostic, source::Source, Span, Spans};
+use tyd_syntax::{
+    db::SourceDb,
+    error::SourceDiagnostic,
+    source::{FileId, Source},
+    Span, Spans,
+};
+
+/// A source diagnostic together with the source it refers to.
+///
+/// The source is resolved from the file id of the span when the diagnostic is added,
+/// so that every diagnostic is rendered against the right file.
+#[derive(Debug)]
+struct Located {
+    /// The diagnostic
+    diag: SourceDiagnostic,
+    /// The source the span of the diagnostic belongs to, if known
+    source: Option<Source>,
+}
+
+impl fmt::Display for Located {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.diag.fmt(f)
+    }
+}
+
+impl std::error::Error for Located {}
+
+impl Diagnostic for Located {
+    fn severity(&self) -> Option<Severity> {
+        self.diag.severity()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.diag.help()
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        self.diag.labels()
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.source.as_ref().map(|source| source as &dyn SourceCode)
+    }
+}
 
 /// `Tracer` is a diagnostic tool that collects and manages error messages,
 /// warnings, and informational messages during parsing and compilation.
 ///
 /// It maintains references to the source code and span metadata to provide
-/// context-aware error reporting.
-#[derive(Debug, Diagnostic)]
+/// context-aware error reporting. Diagnostics may refer to any source in its
+/// `SourceDb`, e.g. of included files, and are rendered against that file.
+#[derive(Debug)]
 pub struct Tracer {
-    /// The source code being analyzed
-    #[source_code]
-    pub(crate) source: Source,
+    /// The id of the source code being analyzed
+    id: FileId,
     /// Source spans for nodes
     pub(crate) spans: Spans,
+    /// All sources which diagnostics may refer to, including the one being analyzed
+    sources: SourceDb,
     /// Collection of source-specific diagnostics
-    #[related]
-    source_diags: Vec<SourceDiagnostic>,
+    source_diags: Vec<Located>,
     /// Collection of general diagnostics
-    #[related]
     diags: Vec<Box<dyn Diagnostic + Send + Sync>>,
 }
 
@@ -31,12 +74,7 @@ impl Tracer {
     /// * `source` - The source code being analyzed
     /// * `spans` - Metadata about the spans in the source
     pub fn new(source: Source, spans: Spans) -> Self {
-        Self {
-            source,
-            spans,
-            source_diags: Vec::new(),
-            diags: Vec::new(),
-        }
+        Self::with_diagnostics(Vec::new(), source, spans)
     }
 
     /// Creates a new `Tracer` with pre-existing diagnostics.
@@ -50,12 +88,48 @@ impl Tracer {
         source: Source,
         spans: Spans,
     ) -> Self {
-        Self {
-            source,
+        let mut sources = SourceDb::new();
+        let id = sources.insert(source);
+
+        let mut tracer = Self {
+            id,
             spans,
-            source_diags: diagnostics,
+            sources,
+            source_diags: Vec::new(),
             diags: Vec::new(),
+        };
+
+        for diag in diagnostics {
+            tracer.diagnose_source(diag);
         }
+
+        tracer
+    }
+
+    /// Returns the source code being analyzed.
+    pub(crate) fn source(&self) -> &Source {
+        &self.sources[self.id]
+    }
+
+    /// Returns all sources which diagnostics of this tracer may refer to.
+    ///
+    /// # Returns
+    /// The sources, which can be looked up by the file id of a span
+    pub fn sources(&self) -> &SourceDb {
+        &self.sources
+    }
+
+    /// Moves all sources and diagnostics of another tracer into this one.
+    ///
+    /// This is used to merge the diagnostics of another file, e.g. an included one,
+    /// which are still rendered against their own source.
+    ///
+    /// # Arguments
+    /// * `other` - The tracer to merge into this one
+    pub fn append(&mut self, other: Tracer) {
+        self.sources.extend(other.sources);
+        self.source_diags.extend(other.source_diags);
+        self.diags.extend(other.diags);
     }
 
     /// Checks if the tracer contains any error-level diagnostics.
@@ -63,7 +137,9 @@ impl Tracer {
     /// # Returns
     /// `true` if any errors have been recorded, `false` otherwise.
     pub fn has_errors(&self) -> bool {
-        self.source_diags.iter().any(SourceDiagnostic::is_error)
+        self.source_diags
+            .iter()
+            .any(|located| located.diag.is_error())
             || self.diags.iter().any(|diag| {
                 diag.severity()
                     .map(|s| s == Severity::Error)
@@ -119,7 +195,7 @@ impl Tracer {
     #[inline]
     pub fn source_error(&mut self, span: Span, message: impl ToString) {
         let diag = SourceDiagnostic::error(span, message.to_string());
-        self.source_diags.push(diag);
+        self.diagnose_source(diag);
     }
 
     /// Adds a source-specific warning diagnostic at the given span.
@@ -130,7 +206,7 @@ impl Tracer {
     #[inline]
     pub fn source_warn(&mut self, span: Span, message: impl ToString) {
         let diag = SourceDiagnostic::warn(span, message.to_string());
-        self.source_diags.push(diag);
+        self.diagnose_source(diag);
     }
 
     /// Adds a source-specific informational diagnostic at the given span.
@@ -141,17 +217,20 @@ impl Tracer {
     #[inline]
     pub fn source_info(&mut self, span: Span, message: impl ToString) {
         let diag = SourceDiagnostic::info(span, message.to_string());
-        self.source_diags.push(diag);
+        self.diagnose_source(diag);
     }
 
     /// Adds a pre-constructed source diagnostic.
     ///
+    /// The diagnostic is rendered against the source its span belongs to.
+    ///
     /// # Arguments
     /// * `diag` - The source diagnostic to add
     #[inline]
     pub fn diagnose_source(&mut self, diag: impl Into<SourceDiagnostic>) {
         let diag = diag.into();
-        self.source_diags.push(diag);
+        let source = self.sources.get(diag.span.context).cloned();
+        self.source_diags.push(Located { diag, source });
     }
 
     /// Adds a source error diagnostic for a specific node by its ID.
@@ -204,7 +283,13 @@ impl Tracer {
         Vec<SourceDiagnostic>,
         Vec<Box<dyn Diagnostic + Send + Sync>>,
     ) {
-        (self.source_diags, self.diags)
+        let source_diags = self
+            .source_diags
+            .into_iter()
+            .map(|located| located.diag)
+            .collect();
+
+        (source_diags, self.diags)
     }
 }
 
@@ -215,3 +300,20 @@ impl fmt::Display for Tracer {
 }
 
 impl std::error::Error for Tracer {}
+
+impl Diagnostic for Tracer {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(self.source())
+    }
+
+    /// Relates both the source-specific and the general diagnostics.
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        let source_diags = self.source_diags.iter().map(|diag| diag as &dyn Diagnostic);
+        let diags = self
+            .diags
+            .iter()
+            .map(|diag| diag.as_ref() as &dyn Diagnostic);
+
+        Some(Box::new(source_diags.chain(diags)))
+    }
+}
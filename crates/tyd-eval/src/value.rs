@@ -1,10 +1,15 @@
 use derive_more::From;
 use ecow::EcoString;
-use std::fmt::{self, Debug};
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
 use tyd_syntax::Span;
 
 use crate::{
+    datetime::Datetime,
     error::{ArgumentError, TypeError},
+    func::{Closure, Func},
     ir,
     scope::Scope,
     stack::Stack,
@@ -46,6 +51,9 @@ pub enum Type {
     /// Generic content type that can hold various forms of content.
     Content,
 
+    /// Calendar date type, optionally with a time of day.
+    Datetime,
+
     /// Special type that can match any other type (used for type checking).
     Any,
 
@@ -92,6 +100,7 @@ impl fmt::Display for Type {
             Type::Inline => write!(f, "Inline"),
             Type::Block => write!(f, "Block"),
             Type::Content => write!(f, "Content"),
+            Type::Datetime => write!(f, "Datetime"),
             Type::Any => write!(f, "Any"),
             Type::None => write!(f, "None"),
             Type::Func => write!(f, "Func"),
@@ -117,6 +126,7 @@ impl PartialEq for Type {
             (Inline, Inline) => true,
             (Block, Block) => true,
             (Content, Content) => true,
+            (Datetime, Datetime) => true,
             (Func, Func) => true,
             (Any, _) => true,
             (_, Any) => true,
@@ -152,7 +162,11 @@ pub enum Value {
     /// A content element
     Content(ir::Content),
     /// A function value
-    Func(ir::Func),
+    Func(Func),
+    /// A user-defined function value
+    Closure(Arc<Closure>),
+    /// A date, optionally with a time of day
+    Datetime(Datetime),
     /// Represents the absence of a value
     None,
 }
@@ -186,10 +200,65 @@ impl Value {
             Self::Int(_) => Type::Int,
             Self::Inline(_) => Type::Inline,
             Self::Block(_) => Type::Block,
-            Self::Func(_) => Type::Func,
+            Self::Func(_) | Self::Closure(_) => Type::Func,
+            Self::Datetime(_) => Type::Datetime,
             Self::None => Type::None,
         }
     }
+
+    /// Converts this value into the items it consists of, e.g. when iterated by a for loop.
+    ///
+    /// Maps yield their entries as `List(key, value)` pairs, strings their characters
+    /// and list or enum blocks their items.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(items)` if the value can be iterated
+    /// * `Err(ty)` with the type of the value otherwise
+    pub fn into_items(self) -> Result<ir::List, Type> {
+        match self {
+            Self::List(list) => Ok(list),
+            Self::Map(map) => Ok(map
+                .into_iter()
+                .map(|(key, value)| Self::List(vec![Self::Str(key), value]))
+                .collect()),
+            Self::Str(s) => Ok(s.chars().map(|c| Self::Str(c.into())).collect()),
+            Self::Block(ir::Block::BulletList(items) | ir::Block::OrderedList(_, items)) => {
+                Ok(items
+                    .into_iter()
+                    .map(|item| Self::Block(ir::Block::Div(ir::AttrBuilder::empty(), item)))
+                    .collect())
+            }
+            other => Err(other.ty()),
+        }
+    }
+    /// Displays this value as an inline element.
+    pub fn display(self) -> ir::Inline {
+        match self {
+            Self::Inline(inline) => inline,
+            Self::Content(content) => ir::Inline::Span(ir::AttrBuilder::empty(), content),
+            Self::Block(block) => ir::Inline::Str(format!("{block:?}")),
+            Self::Bool(b) => ir::Inline::Str(format!("{b}")),
+            Self::Float(f) => ir::Inline::Str(format!("{f}")),
+            Self::Int(i) => ir::Inline::Str(format!("{i}")),
+            Self::Str(s) => ir::Inline::Str(s.to_string()),
+            Self::List(l) => ir::Inline::Str(format!("{l:?}")),
+            Self::Map(m) => ir::Inline::Str(format!("{m:?}")),
+            Self::None => ir::Inline::Str(format!("{:?}", None::<()>)),
+            Self::Func(f) => ir::Inline::Str(format!("{f}")),
+            Self::Closure(c) => ir::Inline::Str(format!("{c:?}")),
+            Self::Datetime(d) => ir::Inline::Str(d.to_string()),
+        }
+    }
+
+    /// Converts this value to the inline elements displaying it, an empty string displays nothing.
+    pub fn into_content(self) -> ir::Content {
+        match self {
+            Self::Content(content) => content,
+            Self::Str(s) if s.is_empty() => Vec::new(),
+            value => vec![value.display()],
+        }
+    }
 }
 
 /// Implements conversion from string literals to Value.
@@ -358,6 +427,17 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// Reports a missing required named argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the missing argument
+    /// * `ty` - The expected type of the missing argument
+    pub fn missing_required(&mut self, name: EcoString, ty: Type) {
+        self.tracer
+            .source_error(self.span, ArgumentError::MissingRequired { name, ty });
+    }
+
     /// Warns about unknown positional arguments in a stack.
     ///
     /// This method generates warnings for any remaining positional arguments
@@ -429,9 +509,20 @@ impl_typed!(
     Inline(ir::Inline),
     Block(ir::Block),
     Content(ir::Content),
-    Func(ir::Func)
+    Datetime(Datetime),
+    Func(Func)
 );
 
+/// Custom implementation of Typed for Closure type.
+///
+/// Closures are callable just like native functions and
+/// therefore share the Func type.
+impl Typed for Arc<Closure> {
+    fn type_name() -> Type {
+        Type::Func
+    }
+}
+
 /// Custom implementation of Typed for List type.
 ///
 /// Lists have a parameterized type, so we use a more general
@@ -518,7 +609,9 @@ impl_type_cast!(
     Inline(ir::Inline),
     Block(ir::Block),
     Content(ir::Content),
-    Func(ir::Func)
+    Func(Func),
+    Closure(Arc<Closure>),
+    Datetime(Datetime)
 );
 
 /// Implementation of TypeCast for Value itself.
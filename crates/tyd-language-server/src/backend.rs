@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
 use dashmap::DashMap;
 use ropey::Rope;
 use tower_lsp::{jsonrpc::Result, lsp_types::*, Client, LanguageServer};
@@ -64,6 +66,78 @@ impl Backend {
         }))
     }
 
+    pub async fn on_completion(&self) -> Option<CompletionResponse> {
+        // Functions of modules are completed by their path, e.g. `str.upper`
+        let functions = self
+            .global_scope
+            .functions()
+            .into_iter()
+            .map(|(name, func)| CompletionItem {
+                label: name.to_string(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some(func.signature().header(&name)),
+                documentation: Some(Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: func.signature().markdown(&name),
+                })),
+                ..Default::default()
+            });
+
+        let variables = self
+            .global_scope
+            .symbols()
+            .zip(self.global_scope.values())
+            .filter(|(_, value)| !matches!(value, Value::Func(_)))
+            .map(|(name, value)| CompletionItem {
+                label: name.to_string(),
+                kind: Some(CompletionItemKind::VARIABLE),
+                detail: Some(value.ty().to_string()),
+                ..Default::default()
+            });
+
+        let items = functions.chain(variables).collect();
+
+        Some(CompletionResponse::Array(items))
+    }
+
+    pub async fn on_hover(&self, uri: Url, position: Position) -> Option<Hover> {
+        let source = self.sources.get(&uri)?;
+        let rope = source.as_rope();
+
+        let line = rope.get_line(position.line as usize)?;
+        let chars: Vec<char> = line.chars().collect();
+
+        // The character of the position is an offset in UTF-16 code units
+        let line_start = rope.line_to_char(position.line as usize);
+        let offset = rope.char_to_utf16_cu(line_start) + position.character as usize;
+        let column = rope
+            .utf16_cu_to_char(offset.min(rope.len_utf16_cu()))
+            .saturating_sub(line_start)
+            .min(chars.len());
+
+        // Functions of modules are accessed by their path, e.g. `str.upper`
+        let is_path = |c: &&char| c.is_alphanumeric() || **c == '_' || **c == '.';
+        let start = column - chars[..column].iter().rev().take_while(is_path).count();
+        let end = column + chars[column..].iter().take_while(is_path).count();
+
+        let path: String = chars[start..end].iter().collect();
+        let path = path.trim_matches('.');
+
+        let (name, func) = self
+            .global_scope
+            .functions()
+            .into_iter()
+            .find(|(name, _)| name == path)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: func.signature().markdown(&name),
+            }),
+            range: None,
+        })
+    }
+
     pub async fn on_change(&self, uri: Url, source: String, version: i32) {
         let path = uri.to_file_path().unwrap();
         let name = path.file_name().unwrap().to_str().unwrap();
@@ -81,15 +155,23 @@ impl Backend {
         if let Some(doc) = doc {
             self.documents.insert(uri.clone(), doc.clone());
 
-            tracer = Engine::new(self.global_scope.clone(), tracer)
+            tracer = Engine::new(self.global_scope.clone(), tracer, Target::default())
                 .run(doc)
                 .tracer;
         }
 
-        let diags = tracer_into_diagnostic(tracer, &source.as_rope());
+        let mut diags = tracer_into_diagnostics(tracer);
+        let current = diags.remove(&*source.path()).unwrap_or_default();
+
+        // Diagnostics of included or imported files are published for those files
+        for (path, diags) in diags {
+            if let Ok(uri) = Url::from_file_path(path) {
+                self.client.publish_diagnostics(uri, diags, None).await;
+            }
+        }
 
         self.client
-            .publish_diagnostics(uri, diags, Some(version))
+            .publish_diagnostics(uri, current, Some(version))
             .await;
     }
 }
@@ -128,6 +210,8 @@ impl LanguageServer for Backend {
                         },
                     ),
                 ),
+                completion_provider: Some(CompletionOptions::default()),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
                 ..ServerCapabilities::default()
             },
         })
@@ -193,26 +277,57 @@ impl LanguageServer for Backend {
         let uri = params.text_document.uri;
         Ok(self.on_semantic_tokens_range(uri).await)
     }
+
+    async fn completion(&self, _: CompletionParams) -> Result<Option<CompletionResponse>> {
+        Ok(self.on_completion().await)
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position_params;
+
+        Ok(self.on_hover(text_document.uri, position).await)
+    }
 }
 
-fn tracer_into_diagnostic(tracer: Tracer, rope: &Rope) -> Vec<Diagnostic> {
-    tracer
-        .into_inner()
-        .0
-        .into_iter()
-        .filter_map(|message| {
-            Some(Diagnostic {
-                range: range_conversion(message.span.into_range(), &rope)?,
-                severity: match message.severity {
-                    miette::Severity::Error => Some(DiagnosticSeverity::ERROR),
-                    miette::Severity::Warning => Some(DiagnosticSeverity::WARNING),
-                    miette::Severity::Advice => Some(DiagnosticSeverity::INFORMATION),
-                },
-                message: message.to_string(),
-                ..Default::default()
-            })
-        })
-        .collect()
+/// Converts the source diagnostics of the tracer, grouped by the path of the file they belong to.
+fn tracer_into_diagnostics(tracer: Tracer) -> BTreeMap<PathBuf, Vec<Diagnostic>> {
+    let sources = tracer.sources().clone();
+    let mut diags: BTreeMap<PathBuf, Vec<Diagnostic>> = BTreeMap::new();
+
+    for source in sources.iter() {
+        diags.insert(source.path().to_path_buf(), Vec::new());
+    }
+
+    for message in tracer.into_inner().0 {
+        let Some(source) = sources.get(message.span.context) else {
+            continue;
+        };
+
+        let Some(range) = range_conversion(message.span.into_range(), &source.as_rope()) else {
+            continue;
+        };
+
+        let diag = Diagnostic {
+            range,
+            severity: match message.severity {
+                miette::Severity::Error => Some(DiagnosticSeverity::ERROR),
+                miette::Severity::Warning => Some(DiagnosticSeverity::WARNING),
+                miette::Severity::Advice => Some(DiagnosticSeverity::INFORMATION),
+            },
+            message: message.to_string(),
+            ..Default::default()
+        };
+
+        diags
+            .entry(source.path().to_path_buf())
+            .or_default()
+            .push(diag);
+    }
+
+    diags
 }
 
 fn range_conversion(span: std::ops::Range<usize>, rope: &Rope) -> Option<Range> {
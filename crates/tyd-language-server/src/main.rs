@@ -14,7 +14,7 @@ async fn main() {
     let mut scope = Scope::empty();
 
     scope
-        .register::<BuiltinPlugin>()
+        .register(BuiltinPlugin)
         .with("title", "Default title")
         .with("author", vec![Value::from("Max Mustermann")]);
 
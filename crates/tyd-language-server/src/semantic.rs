@@ -42,7 +42,7 @@ impl SemanticTokenKind {
                 tree::Literal::Int(_) | tree::Literal::Float(_) => SemanticTokenKind::Number,
                 tree::Literal::Bool(_) => SemanticTokenKind::Keyword,
             },
-            Node::Label(_) | Node::Ref(_) => SemanticTokenKind::Decorator,
+            Node::Label(_) => SemanticTokenKind::Decorator,
             Node::Escape(_) => SemanticTokenKind::Modifier,
             Node::HeadingMarker(_) => SemanticTokenKind::Operator,
             // HeadingMarker | ListMarker | EnumMarker | TermMarker | SubscriptMarker
@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, ops::Index};
+
+use crate::source::{FileId, Source};
+
+/// Holds all sources loaded while processing a document.
+///
+/// Spans carry the `FileId` of the file they belong to, which is used to look
+/// up the matching source, e.g. when rendering diagnostics.
+#[derive(Debug, Clone, Default)]
+pub struct SourceDb {
+    /// The sources, indexed by their id
+    sources: BTreeMap<FileId, Source>,
+}
+
+impl SourceDb {
+    /// Creates a new, empty `SourceDb`.
+    ///
+    /// # Returns
+    /// A new `SourceDb` instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a source to the database.
+    ///
+    /// # Arguments
+    /// * `source` - The source to add
+    ///
+    /// # Returns
+    /// The id of the added source
+    pub fn insert(&mut self, source: Source) -> FileId {
+        let id = source.id();
+        self.sources.insert(id, source);
+        id
+    }
+
+    /// Returns the source with the given id.
+    ///
+    /// # Arguments
+    /// * `id` - The id of the source
+    ///
+    /// # Returns
+    /// The source, or `None` if no source with this id was added
+    pub fn get(&self, id: FileId) -> Option<&Source> {
+        self.sources.get(&id)
+    }
+
+    /// Returns an iterator over all sources in the database.
+    pub fn iter(&self) -> impl Iterator<Item = &Source> {
+        self.sources.values()
+    }
+}
+
+impl Index<FileId> for SourceDb {
+    type Output = Source;
+
+    /// Returns the source with the given id.
+    ///
+    /// # Panics
+    /// If no source with this id was added
+    fn index(&self, id: FileId) -> &Self::Output {
+        &self.sources[&id]
+    }
+}
+
+impl Extend<Source> for SourceDb {
+    fn extend<T: IntoIterator<Item = Source>>(&mut self, iter: T) {
+        for source in iter {
+            self.insert(source);
+        }
+    }
+}
+
+impl IntoIterator for SourceDb {
+    type Item = Source;
+    type IntoIter = std::collections::btree_map::IntoValues<FileId, Source>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.sources.into_values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_by_id() {
+        let main = Source::new("main.tyd", "main.tyd", "main");
+        let lib = Source::new("lib.tyd", "lib.tyd", "lib");
+        let other = Source::new("other.tyd", "other.tyd", "");
+
+        let mut db = SourceDb::new();
+        let id = db.insert(main);
+        db.extend([lib.clone()]);
+
+        assert_eq!(db[id].as_str(), "main");
+        assert_eq!(db.get(lib.id()).map(Source::name), Some("lib.tyd"));
+        assert!(db.get(other.id()).is_none());
+        assert_eq!(db.iter().count(), 2);
+        assert_eq!(db.into_iter().count(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_of_unknown_source() {
+        let db = SourceDb::new();
+        let _ = &db[Source::new("main.tyd", "main.tyd", "").id()];
+    }
+}
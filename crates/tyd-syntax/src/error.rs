@@ -139,8 +139,8 @@ impl SourceDiagnostic {
 }
 
 /// Converts a Chumsky parser error into a source diagnostic.
-impl<'src> From<Rich<'src, char>> for SourceDiagnostic {
-    fn from(e: Rich<'src, char>) -> Self {
+impl<'src> From<Rich<'src, char, Span>> for SourceDiagnostic {
+    fn from(e: Rich<'src, char, Span>) -> Self {
         let message = e.to_string();
         let span = *e.span();
         let trace = e
@@ -184,14 +184,19 @@ impl Diagnostic for SourceDiagnostic {
 
     /// Returns the labeled spans for this diagnostic.
     ///
-    /// This includes the primary span with the main message and any trace spans.
+    /// This includes the primary span with the main message and any trace spans
+    /// which belong to the same file as the primary span.
     fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
         let primary =
             LabeledSpan::new_primary_with_span(Some(self.message.clone()), self.span.into_range());
 
-        let trace = self.trace.iter().map(|(label, span)| {
-            LabeledSpan::new_with_span(Some(label.clone()), span.into_range())
-        });
+        let trace = self
+            .trace
+            .iter()
+            .filter(|(_, span)| span.context == self.span.context)
+            .map(|(label, span)| {
+                LabeledSpan::new_with_span(Some(label.clone()), span.into_range())
+            });
 
         let iter = Box::new(std::iter::once(primary).chain(trace))
             as Box<dyn Iterator<Item = LabeledSpan>>;
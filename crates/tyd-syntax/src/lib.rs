@@ -8,8 +8,14 @@
 use chumsky::span::SimpleSpan;
 use tyd_core::meta::{Metadata, UniformPhase};
 
+use crate::source::FileId;
+
+/// Provides the `SourceDb` holding all loaded sources.
+pub mod db;
 /// Contains error types and utilities for handling parsing errors.
 pub mod error;
+/// Provides a parser for math written in a subset of LaTeX.
+pub mod math;
 /// Provides the core parsing functionality for structured text documents.
 pub mod parser;
 /// Defines the `Source` struct and related utilities for working with source text.
@@ -19,16 +25,18 @@ pub mod source;
 pub mod prelude {
     pub use crate::error::*;
     // pub use crate::kind::SyntaxKind;
+    pub use crate::db::SourceDb;
     pub use crate::parser::*;
-    pub use crate::source::Source;
+    pub use crate::source::{FileId, Source};
     pub use crate::{LocationPhase, Span, Spans};
 }
 
 /// Represents span metadata created during the syntax processing phase.
 pub type Spans = Metadata<LocationPhase>;
 
-/// Represents a span of text in the source document, identified by byte indices.
-pub type Span = SimpleSpan<usize>;
+/// Represents a span of text in a source document, identified by byte indices
+/// and the file the text belongs to.
+pub type Span = SimpleSpan<usize, FileId>;
 
 /// A value paired with location information showing where in the source it came from.
 pub type Spanned<T> = (T, Span);
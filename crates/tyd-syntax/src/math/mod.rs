@@ -0,0 +1,441 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+
+use crate::{Span, error::SourceDiagnostic};
+
+mod parser;
+mod symbols;
+
+/// A node of a parsed math formula.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Math {
+    /// A sequence of nodes, e.g. the contents of braces
+    Row(Vec<Math>),
+    /// An identifier, e.g. `x` or `\alpha`
+    Ident(EcoString),
+    /// A number, e.g. `3.14`
+    Number(EcoString),
+    /// An operator, e.g. `+`, `(` or `\le`
+    Operator(EcoString),
+    /// A large operator, e.g. `\sum` or `\int`
+    LargeOperator {
+        /// The symbol of the operator
+        op: EcoString,
+        /// Whether scripts are placed below and above the operator in display math
+        limits: bool,
+    },
+    /// A named function, e.g. `\sin` or `\lim`
+    Function {
+        /// The name of the function
+        name: EcoString,
+        /// Whether scripts are placed below and above the name in display math
+        limits: bool,
+    },
+    /// Text written in `\text{...}`
+    Text(EcoString),
+    /// Horizontal space, e.g. `\quad`, with its width in em
+    Space(f32),
+    /// A fraction, e.g. `\frac{1}{2}`, or a binomial coefficient without a line
+    Frac {
+        /// The numerator
+        num: Box<Math>,
+        /// The denominator
+        den: Box<Math>,
+        /// Whether the fraction line is drawn
+        line: bool,
+    },
+    /// A root, e.g. `\sqrt{x}` or `\sqrt[3]{x}`
+    Root {
+        /// The index of the root, none for square roots
+        index: Option<Box<Math>>,
+        /// The radicand
+        radicand: Box<Math>,
+    },
+    /// A node with a subscript and/or a superscript, e.g. `x_i^2`
+    Scripts {
+        /// The node the scripts are attached to
+        base: Box<Math>,
+        /// The subscript
+        sub: Option<Box<Math>>,
+        /// The superscript
+        sup: Option<Box<Math>>,
+    },
+    /// An accent above or below a node, e.g. `\hat{x}` or `\underline{x}`
+    Accent {
+        /// The accented node
+        base: Box<Math>,
+        /// The symbol of the accent
+        accent: EcoString,
+        /// Whether the accent is above the node
+        over: bool,
+    },
+    /// A node enclosed in stretching delimiters, e.g. `\left( x \right)`
+    Fenced {
+        /// The opening delimiter, empty if invisible
+        open: EcoString,
+        /// The closing delimiter, empty if invisible
+        close: EcoString,
+        /// The enclosed node
+        body: Box<Math>,
+    },
+    /// A node in another font variant, e.g. `\mathbf{x}`
+    Styled {
+        /// The font variant
+        variant: Variant,
+        /// The styled node
+        body: Box<Math>,
+    },
+    /// The rows of cells of an environment, e.g. `\begin{pmatrix} a & b \end{pmatrix}`
+    Table {
+        /// The rows of cells
+        rows: Vec<Vec<Math>>,
+        /// The opening delimiter, empty if invisible
+        open: EcoString,
+        /// The closing delimiter, empty if invisible
+        close: EcoString,
+        /// The alignment of the columns
+        columns: Columns,
+    },
+    /// A command which is not supported, displayed verbatim
+    Unsupported(EcoString),
+}
+
+/// A font variant of math, e.g. the bold variant of `\mathbf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// Upright letters, e.g. `\mathrm`
+    Normal,
+    /// Bold letters, e.g. `\mathbf`
+    Bold,
+    /// Italic letters, e.g. `\mathit`
+    Italic,
+    /// Double-struck letters, e.g. `\mathbb`
+    DoubleStruck,
+    /// Script letters, e.g. `\mathcal`
+    Script,
+    /// Fraktur letters, e.g. `\mathfrak`
+    Fraktur,
+    /// Sans-serif letters, e.g. `\mathsf`
+    SansSerif,
+    /// Monospace letters, e.g. `\mathtt`
+    Monospace,
+}
+
+/// The alignment of the columns of an environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Columns {
+    /// Centered columns, e.g. of matrices
+    Centered,
+    /// Columns aligned to the left, e.g. of `cases`
+    Left,
+    /// Columns aligned alternately to the right and the left, e.g. of `aligned` at `&`
+    Aligned,
+}
+
+/// A warning about a command which is not supported or malformed math.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MathWarning {
+    /// The byte range of the math the warning refers to
+    pub range: Range<usize>,
+    /// The warning message
+    pub message: String,
+}
+
+impl MathWarning {
+    /// Converts the warning into a diagnostic in the source of the math.
+    ///
+    /// # Arguments
+    /// * `span` - The span of the math in its source, without the enclosing `$`
+    pub fn into_diagnostic(self, span: Span) -> SourceDiagnostic {
+        let span = Span {
+            start: span.start + self.range.start,
+            end: span.start + self.range.end,
+            context: span.context,
+        };
+
+        SourceDiagnostic::warn(span, self.message)
+    }
+}
+
+/// Result of parsing math.
+///
+/// Math is always parsed, commands which are not supported are kept as
+/// `Math::Unsupported` and reported as warnings, like malformed math.
+#[derive(Debug, Clone)]
+pub struct MathResult {
+    /// The parsed math
+    pub math: Math,
+    /// Warnings about unsupported commands and malformed math
+    pub warnings: Vec<MathWarning>,
+}
+
+/// Parses math written in a subset of LaTeX.
+///
+/// # Arguments
+/// * `text` - The math, without the enclosing `$`
+///
+/// # Returns
+/// A MathResult containing the parsed math and the warnings
+pub fn parse(text: &str) -> MathResult {
+    parser::Parser::new(text).parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(nodes: Vec<Math>) -> Math {
+        Math::Row(nodes)
+    }
+
+    fn ident(name: &str) -> Math {
+        Math::Ident(name.into())
+    }
+
+    fn number(text: &str) -> Math {
+        Math::Number(text.into())
+    }
+
+    /// Parses math which must not produce warnings, returning its nodes.
+    fn nodes(text: &str) -> Vec<Math> {
+        let result = parse(text);
+        assert_eq!(result.warnings, [], "warnings for {text:?}");
+
+        match result.math {
+            Math::Row(nodes) => nodes,
+            math => panic!("expected a row, found {math:?}"),
+        }
+    }
+
+    fn messages(text: &str) -> Vec<String> {
+        parse(text)
+            .warnings
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect()
+    }
+
+    #[test]
+    fn atoms() {
+        assert_eq!(
+            nodes("x + 3.14 - \\alpha \\le 2."),
+            [
+                ident("x"),
+                Math::Operator("+".into()),
+                number("3.14"),
+                Math::Operator("−".into()),
+                ident("α"),
+                Math::Operator("≤".into()),
+                number("2"),
+                Math::Operator(".".into()),
+            ]
+        );
+        assert_eq!(nodes(""), []);
+        assert_eq!(nodes("\\quad"), [Math::Space(1.0)]);
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(
+            nodes("x_i^2"),
+            [Math::Scripts {
+                base: Box::new(ident("x")),
+                sub: Some(Box::new(ident("i"))),
+                sup: Some(Box::new(number("2"))),
+            }]
+        );
+        assert_eq!(
+            nodes("f'^{n}"),
+            [Math::Scripts {
+                base: Box::new(ident("f")),
+                sub: None,
+                sup: Some(Box::new(row(vec![
+                    Math::Operator("′".into()),
+                    row(vec![ident("n")]),
+                ]))),
+            }]
+        );
+        assert_eq!(
+            nodes("^2"),
+            [Math::Scripts {
+                base: Box::new(row(Vec::new())),
+                sub: None,
+                sup: Some(Box::new(number("2"))),
+            }]
+        );
+    }
+
+    #[test]
+    fn fractions_and_roots() {
+        assert_eq!(
+            nodes("\\frac12"),
+            [Math::Frac {
+                num: Box::new(number("1")),
+                den: Box::new(number("2")),
+                line: true,
+            }]
+        );
+        assert_eq!(
+            nodes("\\binom{n}{k}"),
+            [Math::Fenced {
+                open: "(".into(),
+                close: ")".into(),
+                body: Box::new(Math::Frac {
+                    num: Box::new(row(vec![ident("n")])),
+                    den: Box::new(row(vec![ident("k")])),
+                    line: false,
+                }),
+            }]
+        );
+        assert_eq!(
+            nodes("\\sqrt[3]{x}"),
+            [Math::Root {
+                index: Some(Box::new(row(vec![number("3")]))),
+                radicand: Box::new(row(vec![ident("x")])),
+            }]
+        );
+    }
+
+    #[test]
+    fn commands_with_text() {
+        assert_eq!(
+            nodes("\\text{a {b} c} \\operatorname{rank}"),
+            [
+                Math::Text("a {b} c".into()),
+                Math::Function {
+                    name: "rank".into(),
+                    limits: false,
+                },
+            ]
+        );
+        assert_eq!(
+            nodes("\\mathbf x"),
+            [Math::Styled {
+                variant: Variant::Bold,
+                body: Box::new(ident("x")),
+            }]
+        );
+    }
+
+    #[test]
+    fn fenced() {
+        assert_eq!(
+            nodes("\\left\\langle x \\right."),
+            [Math::Fenced {
+                open: "⟨".into(),
+                close: "".into(),
+                body: Box::new(row(vec![ident("x")])),
+            }]
+        );
+        assert_eq!(
+            messages("\\left( x"),
+            ["`\\left` is not closed by `\\right`"]
+        );
+        assert_eq!(messages("x \\right)"), ["`\\right` without `\\left`"]);
+        assert_eq!(
+            messages("\\left\\foo x \\right"),
+            [
+                "Unsupported delimiter `\\foo` after `\\left`",
+                "Missing delimiter after `\\right`",
+            ]
+        );
+    }
+
+    #[test]
+    fn environments() {
+        assert_eq!(
+            nodes("\\begin{pmatrix} a & b \\\\ c & d \\\\ \\end{pmatrix}"),
+            [Math::Table {
+                rows: vec![
+                    vec![row(vec![ident("a")]), row(vec![ident("b")])],
+                    vec![row(vec![ident("c")]), row(vec![ident("d")])],
+                ],
+                open: "(".into(),
+                close: ")".into(),
+                columns: Columns::Centered,
+            }]
+        );
+        assert_eq!(
+            messages("\\begin{cases} x"),
+            ["`\\begin{cases}` is not closed by `\\end{cases}`"]
+        );
+        assert_eq!(
+            messages("\\begin{cases} x \\end{pmatrix}"),
+            ["`\\begin{cases}` is closed by `\\end{pmatrix}`"]
+        );
+        assert_eq!(
+            messages("\\begin{foo} x \\end{foo}"),
+            ["Unsupported environment `foo`"]
+        );
+        assert_eq!(messages("x \\end{foo}"), ["`\\end` without `\\begin`"]);
+    }
+
+    #[test]
+    fn unsupported_commands() {
+        let result = parse("a \\foo b");
+
+        assert_eq!(
+            result.math,
+            row(vec![
+                ident("a"),
+                Math::Unsupported("\\foo".into()),
+                ident("b"),
+            ])
+        );
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].range, 2..6);
+        assert_eq!(result.warnings[0].message, "Unsupported command `\\foo`");
+    }
+
+    #[test]
+    fn malformed_math() {
+        assert_eq!(messages("{x"), ["Unclosed `{`"]);
+        assert_eq!(messages("x}"), ["Unmatched `}`"]);
+        assert_eq!(
+            messages("a & b"),
+            ["Unexpected `&` outside of an environment"]
+        );
+        assert_eq!(
+            messages("a \\\\ b"),
+            ["Unexpected line break outside of an environment"]
+        );
+        assert_eq!(
+            messages("\\sqrt[3 x"),
+            ["Unclosed `[`", "Missing argument of `\\sqrt`"]
+        );
+        assert_eq!(messages("\\frac{1}"), ["Missing argument of `\\frac`"]);
+        assert_eq!(messages("\\text"), ["Missing argument of `\\text`"]);
+        assert_eq!(messages("\\text{x"), ["Unclosed `{`"]);
+        assert_eq!(messages("x^"), ["Missing argument of the superscript"]);
+        assert_eq!(
+            messages("x_1_2"),
+            ["Double subscript, only the last one is displayed"]
+        );
+        assert_eq!(messages("x^^2"), ["Double superscript"]);
+        assert_eq!(messages("x_^2"), ["Missing argument of the subscript"]);
+    }
+
+    #[test]
+    fn double_superscript_keeps_the_last_one() {
+        let math = parse("x^^2");
+
+        assert_eq!(math.warnings[0].range, 1..3);
+        assert_eq!(math.math, parse("x^2").math);
+    }
+
+    #[test]
+    fn warnings_are_offset_into_the_source() {
+        let warning = parse("a \\foo").warnings.remove(0);
+        let span = Span {
+            start: 10,
+            end: 16,
+            context: crate::source::Source::new("test.tyd", "test.tyd", "").id(),
+        };
+
+        let diagnostic = warning.into_diagnostic(span);
+
+        assert_eq!(diagnostic.span.start, 12);
+        assert_eq!(diagnostic.span.end, 16);
+    }
+}
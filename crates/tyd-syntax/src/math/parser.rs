@@ -0,0 +1,594 @@
+use std::{mem, ops::Range};
+
+use ecow::EcoString;
+
+use super::{Columns, Math, MathResult, MathWarning, symbols::*};
+
+/// A recursive descent parser for math.
+///
+/// Malformed math is recovered from by reporting a warning and parsing as much
+/// of it as possible, e.g. an unclosed `{` is closed at the end of the math.
+pub struct Parser<'a> {
+    /// The math being parsed
+    text: &'a str,
+    /// The byte offset of the next character
+    pos: usize,
+    /// The warnings reported so far
+    warnings: Vec<MathWarning>,
+}
+
+impl<'a> Parser<'a> {
+    /// Creates a parser for the math.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Parses the whole math.
+    ///
+    /// Delimiters which end a construct without belonging to one, e.g. an unmatched `}`,
+    /// are reported and skipped.
+    pub fn parse(mut self) -> MathResult {
+        let mut nodes = Vec::new();
+
+        loop {
+            nodes.extend(self.row(false));
+
+            let start = self.pos;
+
+            let message = if self.eat('}') {
+                "Unmatched `}`"
+            } else if self.eat('&') {
+                "Unexpected `&` outside of an environment"
+            } else if self.eat_str("\\\\") {
+                "Unexpected line break outside of an environment"
+            } else if self.eat_command("right") {
+                self.delimiter("`\\right`", start);
+                "`\\right` without `\\left`"
+            } else if self.eat_command("end") {
+                self.raw_group("`\\end`", start);
+                "`\\end` without `\\begin`"
+            } else {
+                break;
+            };
+
+            self.warn(start..self.pos, message);
+        }
+
+        MathResult {
+            math: Math::Row(nodes),
+            warnings: self.warnings,
+        }
+    }
+
+    /// Parses nodes until the end of the math or of the construct containing them,
+    /// e.g. a `}` or `\right`, which is left to the construct.
+    ///
+    /// # Arguments
+    /// * `bracket` - Whether a `]` ends the nodes, e.g. in the index of `\sqrt[3]{x}`
+    fn row(&mut self, bracket: bool) -> Vec<Math> {
+        let mut nodes = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            if self.at_end(bracket) {
+                return nodes;
+            }
+
+            // Scripts without a base, e.g. `^2`, are attached to an empty row
+            let base = match self.peek() {
+                Some('^' | '_') => Math::Row(Vec::new()),
+                _ => self.atom(),
+            };
+
+            nodes.push(self.scripts(base));
+        }
+    }
+
+    /// Checks if the next character ends the nodes of a construct.
+    fn at_end(&self, bracket: bool) -> bool {
+        let rest = &self.text[self.pos..];
+
+        rest.is_empty()
+            || rest.starts_with(['}', '&'])
+            || rest.starts_with("\\\\")
+            || (bracket && rest.starts_with(']'))
+            || matches!(self.command_name(), Some("right" | "end"))
+    }
+
+    /// Parses a single node without scripts, e.g. `x`, `42`, `{...}` or `\frac{1}{2}`.
+    fn atom(&mut self) -> Math {
+        let start = self.pos;
+
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                let nodes = self.row(false);
+
+                if !self.eat('}') {
+                    self.warn(start..start + 1, "Unclosed `{`");
+                }
+
+                Math::Row(nodes)
+            }
+            Some('\\') => self.command(),
+            Some(c) if c.is_ascii_digit() => self.number(),
+            Some(c) => {
+                self.pos += c.len_utf8();
+                Self::char(c)
+            }
+            None => Math::Row(Vec::new()),
+        }
+    }
+
+    /// Parses the subscript, superscript and primes attached to a node, e.g. `x_i^2` or `f'`.
+    fn scripts(&mut self, base: Math) -> Math {
+        let mut sub: Option<Box<Math>> = None;
+        let mut sup: Option<Box<Math>> = None;
+        let mut primes = EcoString::new();
+
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+
+            let (script, name) = match self.peek() {
+                Some('_') => (&mut sub, "subscript"),
+                Some('^') => (&mut sup, "superscript"),
+                Some('\'') => {
+                    self.pos += 1;
+                    primes.push('′');
+                    continue;
+                }
+                _ => break,
+            };
+
+            let marker = self.peek();
+            self.pos += 1;
+            self.skip_whitespace();
+
+            // A script marker is not an argument, e.g. the second `^` of `x^^2`
+            if let Some(next @ ('_' | '^')) = self.peek() {
+                let message = match Some(next) == marker {
+                    true => format!("Double {name}"),
+                    false => format!("Missing argument of the {name}"),
+                };
+                self.warn(start..self.pos + 1, message);
+                continue;
+            }
+
+            let node = self.argument(&format!("the {name}"), start);
+
+            if script.is_some() {
+                let message = format!("Double {name}, only the last one is displayed");
+                self.warn(start..start + 1, message);
+            }
+
+            *script = Some(Box::new(node));
+        }
+
+        // Primes are combined with the superscript, e.g. `f'^2`
+        if !primes.is_empty() {
+            let primes = Math::Operator(primes);
+
+            sup = Some(Box::new(match sup.take() {
+                Some(sup) => Math::Row(vec![primes, *sup]),
+                None => primes,
+            }));
+        }
+
+        if sub.is_none() && sup.is_none() {
+            return base;
+        }
+
+        Math::Scripts {
+            base: Box::new(base),
+            sub,
+            sup,
+        }
+    }
+
+    /// Parses the argument of a command or script, which is a group,
+    /// a command or a single character, e.g. `{12}`, `\pi` or `1` of `\frac12`.
+    ///
+    /// # Arguments
+    /// * `of` - The description of what the argument belongs to, for warnings
+    /// * `start` - The start of what the argument belongs to, for warnings
+    fn argument(&mut self, of: &str, start: usize) -> Math {
+        self.skip_whitespace();
+
+        if self.at_end(false) {
+            self.warn(start..self.pos, format!("Missing argument of {of}"));
+            return Math::Row(Vec::new());
+        }
+
+        match self.peek() {
+            Some('{' | '\\') | None => self.atom(),
+            Some(c) => {
+                self.pos += c.len_utf8();
+                Self::char(c)
+            }
+        }
+    }
+
+    /// Parses a number, e.g. `42` or `3.14`.
+    fn number(&mut self) -> Math {
+        let rest = &self.text[self.pos..];
+        let digits = |text: &str| {
+            text.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(text.len())
+        };
+
+        let mut len = digits(rest);
+
+        if let Some(fraction) = rest[len..].strip_prefix('.')
+            && fraction.starts_with(|c: char| c.is_ascii_digit())
+        {
+            len += 1 + digits(fraction);
+        }
+
+        self.pos += len;
+        Math::Number(rest[..len].into())
+    }
+
+    /// Returns the node of a single character, e.g. an identifier for a letter.
+    fn char(c: char) -> Math {
+        match c {
+            c if c.is_ascii_digit() => Math::Number(c.into()),
+            c if c.is_alphabetic() => Math::Ident(c.into()),
+            '-' => Math::Operator("−".into()),
+            '*' => Math::Operator("∗".into()),
+            c => Math::Operator(c.into()),
+        }
+    }
+
+    /// Parses a command and its arguments, e.g. `\alpha` or `\frac{1}{2}`.
+    fn command(&mut self) -> Math {
+        let start = self.pos;
+        let name = self.command_name().unwrap_or_default();
+        self.pos += 1 + name.len();
+
+        let command = format!("`\\{name}`");
+
+        if let Some(symbol) = lookup(IDENTS, name) {
+            return Math::Ident(symbol.into());
+        }
+
+        if let Some(symbol) = lookup(OPERATORS, name) {
+            return Math::Operator(symbol.into());
+        }
+
+        if let Some(width) = lookup(SPACES, name) {
+            return Math::Space(width);
+        }
+
+        if let Some(limits) = lookup(FUNCTIONS, name) {
+            return Math::Function {
+                name: name.into(),
+                limits,
+            };
+        }
+
+        if let Some(&(_, op, limits)) = LARGE_OPERATORS.iter().find(|(op, ..)| *op == name) {
+            return Math::LargeOperator {
+                op: op.into(),
+                limits,
+            };
+        }
+
+        if let Some(&(_, accent, over)) = ACCENTS.iter().find(|(accent, ..)| *accent == name) {
+            let base = self.argument(&command, start);
+
+            return Math::Accent {
+                base: Box::new(base),
+                accent: accent.into(),
+                over,
+            };
+        }
+
+        if let Some(variant) = lookup(VARIANTS, name) {
+            let body = self.argument(&command, start);
+
+            return Math::Styled {
+                variant,
+                body: Box::new(body),
+            };
+        }
+
+        if let Some(variant) = lookup(TEXTS, name) {
+            let text = Math::Text(self.raw_group(&command, start).into());
+
+            return match variant {
+                Some(variant) => Math::Styled {
+                    variant,
+                    body: Box::new(text),
+                },
+                None => text,
+            };
+        }
+
+        match name {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let num = self.argument(&command, start);
+                let den = self.argument(&command, start);
+
+                Math::Frac {
+                    num: Box::new(num),
+                    den: Box::new(den),
+                    line: true,
+                }
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let num = self.argument(&command, start);
+                let den = self.argument(&command, start);
+
+                let frac = Math::Frac {
+                    num: Box::new(num),
+                    den: Box::new(den),
+                    line: false,
+                };
+
+                Math::Fenced {
+                    open: "(".into(),
+                    close: ")".into(),
+                    body: Box::new(frac),
+                }
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                let bracket = self.pos;
+
+                let index = self.eat('[').then(|| {
+                    let nodes = self.row(true);
+
+                    if !self.eat(']') {
+                        self.warn(bracket..bracket + 1, "Unclosed `[`");
+                    }
+
+                    Box::new(Math::Row(nodes))
+                });
+
+                let radicand = self.argument(&command, start);
+
+                Math::Root {
+                    index,
+                    radicand: Box::new(radicand),
+                }
+            }
+            "operatorname" => Math::Function {
+                name: self.raw_group(&command, start).into(),
+                limits: false,
+            },
+            "left" => self.fenced(start),
+            "begin" => self.environment(start),
+            _ => {
+                self.warn(start..self.pos, format!("Unsupported command {command}"));
+                Math::Unsupported(self.text[start..self.pos].into())
+            }
+        }
+    }
+
+    /// Parses the nodes enclosed by `\left` and `\right`, after the `\left`.
+    fn fenced(&mut self, start: usize) -> Math {
+        let open = self.delimiter("`\\left`", start);
+        let body = self.row(false);
+        let right = self.pos;
+
+        let close = if self.eat_command("right") {
+            self.delimiter("`\\right`", right)
+        } else {
+            self.warn(start..start + 5, "`\\left` is not closed by `\\right`");
+            EcoString::new()
+        };
+
+        Math::Fenced {
+            open,
+            close,
+            body: Box::new(Math::Row(body)),
+        }
+    }
+
+    /// Parses the delimiter after `\left` or `\right`, e.g. `(` or `\langle`.
+    ///
+    /// # Returns
+    /// The symbol of the delimiter, empty for the invisible delimiter `.`
+    fn delimiter(&mut self, of: &str, start: usize) -> EcoString {
+        self.skip_whitespace();
+        let delim = self.pos;
+
+        match self.peek() {
+            Some('\\') => {
+                let name = self.command_name().unwrap_or_default();
+                self.pos += 1 + name.len();
+
+                match lookup(DELIMITERS, name) {
+                    Some(symbol) => symbol.into(),
+                    None => {
+                        let message = format!("Unsupported delimiter `\\{name}` after {of}");
+                        self.warn(delim..self.pos, message);
+                        EcoString::new()
+                    }
+                }
+            }
+            Some(c @ ('.' | '(' | ')' | '[' | ']' | '|' | '/' | '<' | '>')) => {
+                self.pos += 1;
+
+                match c {
+                    '.' => EcoString::new(),
+                    '<' => "⟨".into(),
+                    '>' => "⟩".into(),
+                    c => c.into(),
+                }
+            }
+            _ => {
+                self.warn(start..self.pos, format!("Missing delimiter after {of}"));
+                EcoString::new()
+            }
+        }
+    }
+
+    /// Parses the rows of cells of an environment, after the `\begin`.
+    ///
+    /// Cells are separated by `&` and rows by `\\`, unknown environments are
+    /// reported and displayed like matrices.
+    fn environment(&mut self, start: usize) -> Math {
+        let name = self.raw_group("`\\begin`", start);
+        let begin = start..self.pos;
+
+        let (open, close, columns) = match ENVIRONMENTS.iter().find(|(env, ..)| *env == name) {
+            Some(&(_, open, close, columns)) => (open, close, columns),
+            None => {
+                self.warn(begin.clone(), format!("Unsupported environment `{name}`"));
+                ("", "", Columns::Centered)
+            }
+        };
+
+        // The column specification of arrays is not displayed
+        if name == "array" {
+            self.raw_group("`\\begin{array}`", start);
+        }
+
+        let mut rows = Vec::new();
+        let mut cells = Vec::new();
+
+        loop {
+            cells.push(Math::Row(self.row(false)));
+
+            if self.eat('&') {
+                continue;
+            }
+
+            if self.eat_str("\\\\") {
+                rows.push(mem::take(&mut cells));
+                continue;
+            }
+
+            break;
+        }
+
+        // A line break after the last row does not start another row
+        if cells != [Math::Row(Vec::new())] {
+            rows.push(cells);
+        }
+
+        let end = self.pos;
+
+        if self.eat_command("end") {
+            let closing = self.raw_group("`\\end`", end);
+
+            if closing != name {
+                let message = format!("`\\begin{{{name}}}` is closed by `\\end{{{closing}}}`");
+                self.warn(end..self.pos, message);
+            }
+        } else {
+            let message = format!("`\\begin{{{name}}}` is not closed by `\\end{{{name}}}`");
+            self.warn(begin, message);
+        }
+
+        Math::Table {
+            rows,
+            open: open.into(),
+            close: close.into(),
+            columns,
+        }
+    }
+
+    /// Reads the text of a group verbatim, e.g. the text of `\text{...}`.
+    ///
+    /// # Arguments
+    /// * `of` - The description of what the group belongs to, for warnings
+    /// * `start` - The start of what the group belongs to, for warnings
+    fn raw_group(&mut self, of: &str, start: usize) -> &'a str {
+        self.skip_whitespace();
+
+        if !self.eat('{') {
+            self.warn(start..self.pos, format!("Missing argument of {of}"));
+            return "";
+        }
+
+        let open = self.pos;
+        let mut depth = 0;
+
+        for (i, c) in self.text[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    self.pos = open + i + 1;
+                    return &self.text[open..open + i];
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        self.warn(open - 1..open, "Unclosed `{`");
+        self.pos = self.text.len();
+        &self.text[open..]
+    }
+
+    /// Returns the name of the command at the next character, without consuming it.
+    ///
+    /// Names are either letters, e.g. `alpha` of `\alpha`, or a single other character,
+    /// e.g. `,` of `\,`.
+    fn command_name(&self) -> Option<&'a str> {
+        let rest = self.text[self.pos..].strip_prefix('\\')?;
+
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+
+        match len {
+            0 => rest.chars().next().map(|c| &rest[..c.len_utf8()]),
+            len => Some(&rest[..len]),
+        }
+    }
+
+    /// Consumes the command with the name, if it is the next one.
+    fn eat_command(&mut self, name: &str) -> bool {
+        let found = self.command_name() == Some(name);
+
+        if found {
+            self.pos += 1 + name.len();
+        }
+
+        found
+    }
+
+    /// Returns the next character without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    /// Consumes the character, if it is the next one.
+    fn eat(&mut self, c: char) -> bool {
+        self.eat_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Consumes the text, if it follows.
+    fn eat_str(&mut self, text: &str) -> bool {
+        let found = self.text[self.pos..].starts_with(text);
+
+        if found {
+            self.pos += text.len();
+        }
+
+        found
+    }
+
+    /// Skips whitespace, which is not displayed in math.
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Reports a warning for a range of the math.
+    fn warn(&mut self, range: Range<usize>, message: impl Into<String>) {
+        self.warnings.push(MathWarning {
+            range,
+            message: message.into(),
+        });
+    }
+}
@@ -0,0 +1,295 @@
+use super::{Columns, Variant};
+
+/// Commands of identifiers and the symbols they display
+pub const IDENTS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ϵ"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("vartheta", "ϑ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("varpi", "ϖ"),
+    ("rho", "ρ"),
+    ("varrho", "ϱ"),
+    ("sigma", "σ"),
+    ("varsigma", "ς"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "ϕ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Upsilon", "Υ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("infty", "∞"),
+    ("partial", "∂"),
+    ("nabla", "∇"),
+    ("emptyset", "∅"),
+    ("varnothing", "∅"),
+    ("hbar", "ℏ"),
+    ("ell", "ℓ"),
+    ("aleph", "ℵ"),
+    ("Re", "ℜ"),
+    ("Im", "ℑ"),
+    ("wp", "℘"),
+];
+
+/// Commands of operators and the symbols they display
+pub const OPERATORS: &[(&str, &str)] = &[
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("times", "×"),
+    ("div", "÷"),
+    ("cdot", "⋅"),
+    ("ast", "∗"),
+    ("star", "⋆"),
+    ("circ", "∘"),
+    ("bullet", "∙"),
+    ("oplus", "⊕"),
+    ("ominus", "⊖"),
+    ("otimes", "⊗"),
+    ("le", "≤"),
+    ("leq", "≤"),
+    ("ge", "≥"),
+    ("geq", "≥"),
+    ("ne", "≠"),
+    ("neq", "≠"),
+    ("ll", "≪"),
+    ("gg", "≫"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("simeq", "≃"),
+    ("cong", "≅"),
+    ("propto", "∝"),
+    ("perp", "⊥"),
+    ("parallel", "∥"),
+    ("mid", "∣"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("ni", "∋"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("supset", "⊃"),
+    ("supseteq", "⊇"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("setminus", "∖"),
+    ("land", "∧"),
+    ("wedge", "∧"),
+    ("lor", "∨"),
+    ("vee", "∨"),
+    ("neg", "¬"),
+    ("lnot", "¬"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("gets", "←"),
+    ("leftrightarrow", "↔"),
+    ("Rightarrow", "⇒"),
+    ("Leftarrow", "⇐"),
+    ("Leftrightarrow", "⇔"),
+    ("implies", "⟹"),
+    ("iff", "⟺"),
+    ("mapsto", "↦"),
+    ("uparrow", "↑"),
+    ("downarrow", "↓"),
+    ("ldots", "…"),
+    ("dots", "…"),
+    ("cdots", "⋯"),
+    ("vdots", "⋮"),
+    ("ddots", "⋱"),
+    ("angle", "∠"),
+    ("prime", "′"),
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("lbrace", "{"),
+    ("rbrace", "}"),
+    ("vert", "|"),
+    ("Vert", "‖"),
+    ("|", "‖"),
+    ("{", "{"),
+    ("}", "}"),
+    ("%", "%"),
+    ("$", "$"),
+    ("#", "#"),
+    ("&", "&"),
+    ("_", "_"),
+];
+
+/// Commands of large operators, their symbols and whether they have limits
+pub const LARGE_OPERATORS: &[(&str, &str, bool)] = &[
+    ("sum", "∑", true),
+    ("prod", "∏", true),
+    ("coprod", "∐", true),
+    ("bigcup", "⋃", true),
+    ("bigcap", "⋂", true),
+    ("bigoplus", "⨁", true),
+    ("bigotimes", "⨂", true),
+    ("int", "∫", false),
+    ("iint", "∬", false),
+    ("iiint", "∭", false),
+    ("oint", "∮", false),
+];
+
+/// Commands of named functions and whether they have limits
+pub const FUNCTIONS: &[(&str, bool)] = &[
+    ("sin", false),
+    ("cos", false),
+    ("tan", false),
+    ("cot", false),
+    ("sec", false),
+    ("csc", false),
+    ("arcsin", false),
+    ("arccos", false),
+    ("arctan", false),
+    ("sinh", false),
+    ("cosh", false),
+    ("tanh", false),
+    ("log", false),
+    ("ln", false),
+    ("lg", false),
+    ("exp", false),
+    ("deg", false),
+    ("dim", false),
+    ("ker", false),
+    ("arg", false),
+    ("hom", false),
+    ("lim", true),
+    ("liminf", true),
+    ("limsup", true),
+    ("max", true),
+    ("min", true),
+    ("sup", true),
+    ("inf", true),
+    ("det", true),
+    ("gcd", true),
+    ("Pr", true),
+];
+
+/// Commands of accents, their symbols and whether they are above the accented node
+pub const ACCENTS: &[(&str, &str, bool)] = &[
+    ("hat", "^", true),
+    ("widehat", "^", true),
+    ("bar", "¯", true),
+    ("overline", "‾", true),
+    ("vec", "→", true),
+    ("overrightarrow", "→", true),
+    ("dot", "˙", true),
+    ("ddot", "¨", true),
+    ("tilde", "~", true),
+    ("widetilde", "~", true),
+    ("check", "ˇ", true),
+    ("breve", "˘", true),
+    ("acute", "´", true),
+    ("grave", "`", true),
+    ("overbrace", "⏞", true),
+    ("underline", "_", false),
+    ("underbrace", "⏟", false),
+];
+
+/// Commands of spaces and their width in em
+pub const SPACES: &[(&str, f32)] = &[
+    (",", 0.1667),
+    (":", 0.2222),
+    (">", 0.2222),
+    (";", 0.2778),
+    (" ", 0.3333),
+    ("quad", 1.0),
+    ("qquad", 2.0),
+    ("!", -0.1667),
+];
+
+/// Commands of font variants
+pub const VARIANTS: &[(&str, Variant)] = &[
+    ("mathrm", Variant::Normal),
+    ("mathbf", Variant::Bold),
+    ("boldsymbol", Variant::Bold),
+    ("mathit", Variant::Italic),
+    ("mathbb", Variant::DoubleStruck),
+    ("mathcal", Variant::Script),
+    ("mathscr", Variant::Script),
+    ("mathfrak", Variant::Fraktur),
+    ("mathsf", Variant::SansSerif),
+    ("mathtt", Variant::Monospace),
+];
+
+/// Commands of text and the font variant of the text, if it is not upright
+pub const TEXTS: &[(&str, Option<Variant>)] = &[
+    ("text", None),
+    ("textrm", None),
+    ("textnormal", None),
+    ("mbox", None),
+    ("textbf", Some(Variant::Bold)),
+    ("textit", Some(Variant::Italic)),
+    ("textsf", Some(Variant::SansSerif)),
+    ("texttt", Some(Variant::Monospace)),
+];
+
+/// Environments, the delimiters enclosing their cells and the alignment of their columns
+pub const ENVIRONMENTS: &[(&str, &str, &str, Columns)] = &[
+    ("matrix", "", "", Columns::Centered),
+    ("pmatrix", "(", ")", Columns::Centered),
+    ("bmatrix", "[", "]", Columns::Centered),
+    ("Bmatrix", "{", "}", Columns::Centered),
+    ("vmatrix", "|", "|", Columns::Centered),
+    ("Vmatrix", "‖", "‖", Columns::Centered),
+    ("smallmatrix", "", "", Columns::Centered),
+    ("array", "", "", Columns::Centered),
+    ("cases", "{", "", Columns::Left),
+    ("aligned", "", "", Columns::Aligned),
+    ("gathered", "", "", Columns::Centered),
+    ("split", "", "", Columns::Aligned),
+];
+
+/// Delimiters after `\left` and `\right` which are commands, and the symbols they display
+pub const DELIMITERS: &[(&str, &str)] = &[
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("lbrace", "{"),
+    ("rbrace", "}"),
+    ("vert", "|"),
+    ("Vert", "‖"),
+    ("|", "‖"),
+    ("{", "{"),
+    ("}", "}"),
+];
+
+/// Looks up the value of a command in a table.
+pub fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(command, _)| *command == name)
+        .map(|(_, value)| *value)
+}
@@ -4,7 +4,11 @@ use chumsky::{
 };
 use tyd_core::prelude::*;
 
-use super::{ext::ParserExt, extra::Extra, markup};
+use super::{
+    ext::ParserExt,
+    extra::{Extra, Input},
+    markup,
+};
 
 /// Parses code expressions that start with a hash (#) symbol
 ///
@@ -17,9 +21,9 @@ use super::{ext::ParserExt, extra::Extra, markup};
 /// A parser that recognizes code expressions and produces a `tree::Code` node
 pub fn code_parser<'src, I>(
     inline: I,
-) -> impl Parser<'src, &'src str, NodeId<tree::Code>, Extra<'src>>
+) -> impl Parser<'src, Input<'src>, NodeId<tree::Code>, Extra<'src>>
 where
-    I: Parser<'src, &'src str, NodeId<tree::Inline>, Extra<'src>> + Clone + 'src,
+    I: Parser<'src, Input<'src>, NodeId<tree::Inline>, Extra<'src>> + Clone + 'src,
 {
     just("#")
         .ignore_then(expr_parser(inline))
@@ -31,7 +35,7 @@ where
 /// # Returns
 ///
 /// A parser that recognizes valid identifiers and produces a `tree::Ident` node
-pub fn ident_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Ident>, Extra<'src>> {
+pub fn ident_parser<'src>() -> impl Parser<'src, Input<'src>, NodeId<tree::Ident>, Extra<'src>> {
     unicode::ident().to_ecow().map_to_node(tree::Ident)
 }
 
@@ -40,10 +44,14 @@ pub fn ident_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Ident>,
 /// Handles various expression types including:
 /// - Identifiers
 /// - Literals
-/// - Function calls
+/// - Function calls and accesses to fields of maps
 /// - For loops
+/// - Break and continue
+/// - Includes and imports of other files
+/// - Set and show rules
 /// - If-then-else conditionals
 /// - Let bindings
+/// - Macro definitions
 /// - Content blocks
 ///
 /// # Arguments
@@ -55,9 +63,9 @@ pub fn ident_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Ident>,
 /// A parser that recognizes expressions and produces a `tree::Expr` node
 pub fn expr_parser<'src, I>(
     inline: I,
-) -> impl Parser<'src, &'src str, NodeId<tree::Expr>, Extra<'src>>
+) -> impl Parser<'src, Input<'src>, NodeId<tree::Expr>, Extra<'src>>
 where
-    I: Parser<'src, &'src str, NodeId<tree::Inline>, Extra<'src>> + Clone + 'src,
+    I: Parser<'src, Input<'src>, NodeId<tree::Inline>, Extra<'src>> + Clone + 'src,
 {
     recursive(|expr| {
         let ident = ident_parser().to_expr();
@@ -66,19 +74,70 @@ where
         let content = markup::content_parser(inline)
             .map_to_node(tree::Content)
             .boxed();
-        let args = args_parser(expr.clone(), content.clone());
+        let args = args_parser(expr.clone(), content.clone()).boxed();
 
         let call = ident_parser()
-            .then(args)
+            .then(args.clone())
             .map_to_node(|(ident, args)| tree::Call { ident, args })
             .to_expr()
             .boxed();
 
-        let for_ = just("for")
+        let access = ident_parser()
+            .then(
+                just(".")
+                    .ignore_then(ident_parser())
+                    .repeated()
+                    .at_least(1)
+                    .collect(),
+            )
+            .then(args.clone().or_not())
+            .map_to_node(|((ident, fields), args)| tree::Access {
+                ident,
+                fields,
+                args,
+            })
+            .to_expr()
+            .boxed();
+
+        let set = just("set")
             .then(inline_whitespace().at_least(1))
             .ignore_then(ident_parser())
+            .then(args.clone())
+            .map_to_node(|(ident, args)| tree::Set { ident, args })
+            .to_expr()
+            .boxed();
+
+        let selector = ident_parser()
+            .to_expr()
+            .or(literal_parser().to_expr())
+            .then(just(".where").ignore_then(args).or_not());
+
+        let param = ident_parser()
+            .then_ignore(just("=>").padded_by(inline_whitespace()))
+            .or_not();
+
+        let show = just("show")
+            .then(inline_whitespace().at_least(1))
+            .ignore_then(selector)
+            .then_ignore(just(":").then(inline_whitespace()))
+            .then(param)
+            .then(expr.clone())
+            .map_to_node(|(((selector, filter), param), transform)| tree::Show {
+                selector,
+                filter,
+                param,
+                transform,
+            })
+            .to_expr()
+            .boxed();
+
+        let for_ = just("for")
+            .then(inline_whitespace().at_least(1))
+            .ignore_then(pattern_parser())
+            .then_ignore(inline_whitespace())
             .then_ignore(just("in").then(inline_whitespace().at_least(1)))
-            .then(ident_parser().to_expr().or(call.clone()))
+            .then(expr.clone())
+            .then_ignore(inline_whitespace())
             .then(content.clone())
             .map_to_node(|((el, inside), content)| tree::For {
                 el,
@@ -103,7 +162,7 @@ where
             .to_expr()
             .boxed();
 
-        let bind = ident_parser()
+        let bind = pattern_parser()
             .then_ignore(just("=").padded_by(inline_whitespace().at_least(1)))
             .then(expr.clone())
             .map_to_node(|(name, value)| tree::Bind { name, value });
@@ -119,7 +178,108 @@ where
             .map_to_node(tree::Let)
             .to_expr();
 
-        choice((literal, for_, if_, let_, call, ident, content.to_expr())).boxed()
+        let macro_ = just("macro")
+            .then(inline_whitespace().at_least(1))
+            .ignore_then(ident_parser())
+            .then(params_parser(expr.clone()))
+            .then_ignore(just("=").padded_by(inline_whitespace().at_least(1)))
+            .then(expr.clone())
+            .map_to_node(|((name, params), body)| tree::Macro { name, params, body })
+            .to_expr()
+            .boxed();
+
+        let break_ = unicode::ident()
+            .filter(|ident: &&str| *ident == "break")
+            .to(tree::Break)
+            .to_node()
+            .to_expr();
+
+        let continue_ = unicode::ident()
+            .filter(|ident: &&str| *ident == "continue")
+            .to(tree::Continue)
+            .to_node()
+            .to_expr();
+
+        let path = expr.clone().padded().delimited_by(just("("), just(")"));
+
+        let include = just("include")
+            .ignore_then(path.clone())
+            .map_to_node(|path| tree::Include { path })
+            .to_expr()
+            .boxed();
+
+        let import = just("import")
+            .ignore_then(path)
+            .map_to_node(|path| tree::Import { path })
+            .to_expr()
+            .boxed();
+
+        choice((
+            literal,
+            for_,
+            if_,
+            let_,
+            macro_,
+            break_,
+            continue_,
+            include,
+            import,
+            set,
+            show,
+            access,
+            call,
+            ident,
+            content.to_expr(),
+        ))
+        .boxed()
+    })
+}
+
+/// Parses binding patterns used by let bindings and for loops
+///
+/// A pattern is either a wildcard `_`, an identifier or a destructuring
+/// like `(first, name: n, ..rest)`, which may contain nested patterns.
+///
+/// # Returns
+///
+/// A parser that recognizes patterns and produces a `tree::Pattern` node
+pub fn pattern_parser<'src>() -> impl Parser<'src, Input<'src>, NodeId<tree::Pattern>, Extra<'src>>
+{
+    recursive(|pattern| {
+        let wildcard = just("_")
+            .then(
+                any()
+                    .filter(|c: &char| c.is_alphanumeric() || *c == '_')
+                    .not(),
+            )
+            .to(tree::Pattern::Wildcard)
+            .to_node();
+
+        let ident = ident_parser().map(tree::Pattern::Ident).to_node();
+
+        let rest = just("..")
+            .ignore_then(ident_parser().or_not())
+            .map(tree::DestructureItem::Rest);
+
+        let named = ident_parser()
+            .then_ignore(just(": "))
+            .then(pattern.clone())
+            .map_to_node(|(name, pattern)| tree::NamedPattern { name, pattern })
+            .map(tree::DestructureItem::Named);
+
+        let item = choice((rest, named, pattern.map(tree::DestructureItem::Pattern))).to_node();
+
+        let destructure = item
+            .separated_by(just(",").padded())
+            .allow_trailing()
+            .collect()
+            .padded()
+            .delimited_by(just("("), just(")"))
+            .map_to_node(tree::Destructure)
+            .map(tree::Pattern::Destructure)
+            .to_node();
+
+        choice((wildcard, ident, destructure)).boxed()
     })
 }
 
@@ -136,10 +296,10 @@ where
 pub fn args_parser<'src, E, C>(
     expr: E,
     content: C,
-) -> impl Parser<'src, &'src str, NodeId<tree::Args>, Extra<'src>>
+) -> impl Parser<'src, Input<'src>, NodeId<tree::Args>, Extra<'src>>
 where
-    E: Parser<'src, &'src str, NodeId<tree::Expr>, Extra<'src>>,
-    C: Parser<'src, &'src str, NodeId<tree::Content>, Extra<'src>> + 'src,
+    E: Parser<'src, Input<'src>, NodeId<tree::Expr>, Extra<'src>>,
+    C: Parser<'src, Input<'src>, NodeId<tree::Content>, Extra<'src>> + 'src,
 {
     let arg = ident_parser()
         .then_ignore(just(": "))
@@ -156,37 +316,95 @@ where
         .map_to_node(|(args, content)| tree::Args { args, content })
 }
 
+/// Parses the parameter list of a macro definition and an optional content parameter
+///
+/// Parameters are written like arguments, where a default value may follow the name:
+/// `(name, greeting: "Hello")[body]`
+///
+/// # Arguments
+///
+/// * `expr` - Parser for expressions that can appear as default values
+///
+/// # Returns
+///
+/// A parser that recognizes parameter lists and produces a `tree::Params` node
+pub fn params_parser<'src, E>(
+    expr: E,
+) -> impl Parser<'src, Input<'src>, NodeId<tree::Params>, Extra<'src>>
+where
+    E: Parser<'src, Input<'src>, NodeId<tree::Expr>, Extra<'src>>,
+{
+    let param = ident_parser()
+        .then(just(": ").ignore_then(expr).or_not())
+        .map_to_node(|(name, default)| tree::Param { name, default });
+
+    let content = ident_parser().padded().delimited_by(just("["), just("]"));
+
+    param
+        .separated_by(just(",").padded())
+        .allow_trailing()
+        .collect()
+        .padded()
+        .delimited_by(just("("), just(")"))
+        .then(content.or_not())
+        .map_to_node(|(params, content)| tree::Params { params, content })
+}
+
 /// Parses literal values
 ///
 /// Supports the following literal types:
 /// - Booleans (`true` or `false`)
 /// - Integers (decimal, octal with `0o` prefix, hexadecimal with `0x` prefix)
 /// - Floating point numbers
+/// - Negative decimal integers and floating point numbers, e.g. `-5` or `-0.5`
 /// - String literals enclosed in double quotes
 ///
 /// # Returns
 ///
 /// A parser that recognizes literals and produces a `tree::Literal` node
-pub fn literal_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Literal>, Extra<'src>> {
+pub fn literal_parser<'src>() -> impl Parser<'src, Input<'src>, NodeId<tree::Literal>, Extra<'src>>
+{
     let boolean = just("true")
         .to(true)
         .or(just("false").to(false))
         .map(tree::Literal::Bool);
 
     let int = choice((
-        just("0o").ignore_then(text::int(8)),
-        just("0x").ignore_then(text::int(16)),
-        text::int(10),
+        just("0o")
+            .ignore_then(text::int(8))
+            .map(|digits| (digits, 8)),
+        just("0x")
+            .ignore_then(text::int(16))
+            .map(|digits| (digits, 16)),
+        just('-')
+            .or_not()
+            .then(text::int(10))
+            .to_slice()
+            .map(|digits| (digits, 10)),
     ))
-    .from_str()
-    .unwrapped()
+    // Overflowing integers are reported, but still parsed as a literal so that the
+    // error is not replaced by the errors of other alternatives
+    .validate(|(digits, radix): (&str, u32), e, emitter| {
+        i64::from_str_radix(digits, radix).unwrap_or_else(|err| {
+            let message = format!("Invalid integer '{digits}': {err}");
+            emitter.emit(Rich::custom(e.span(), message));
+            0
+        })
+    })
     .map(tree::Literal::Int);
 
-    let float = text::int(10)
+    let float = just('-')
+        .or_not()
+        .then(text::int(10))
         .then(just('.').then(text::digits(10)))
         .to_slice()
-        .from_str()
-        .unwrapped()
+        .validate(|digits: &str, e, emitter| {
+            digits.parse().unwrap_or_else(|err| {
+                let message = format!("Invalid float '{digits}': {err}");
+                emitter.emit(Rich::custom(e.span(), message));
+                0.0
+            })
+        })
         .map(tree::Literal::Float);
 
     let string = none_of("\"")
@@ -196,5 +414,100 @@ pub fn literal_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Liter
         .map(tree::Literal::Str)
         .delimited_by(just("\""), just("\""));
 
-    choice((boolean, int, float, string)).to_node()
+    choice((boolean, float, int, string)).to_node()
+}
+
+#[cfg(test)]
+mod tests {
+    use tyd_core::prelude::*;
+
+    use crate::{parser::parse, source::Source};
+
+    /// Parses a document and returns its literals and whether it contains errors
+    fn literals(text: &str) -> (Vec<tree::Literal>, bool) {
+        let source = Source::new("test.tyd", "test.tyd", text);
+        let result = parse(&source);
+
+        let literals = result
+            .doc
+            .iter()
+            .flat_map(|doc| doc.iter_nodes())
+            .filter_map(|node| match node {
+                Node::Literal(literal) => Some(literal.clone()),
+                _ => None,
+            })
+            .collect();
+
+        (literals, !result.errors.is_empty())
+    }
+
+    #[test]
+    fn negative_int() {
+        let (literals, failed) = literals("#calc.abs(-5)\n\n");
+        assert!(!failed);
+        assert_eq!(literals, [tree::Literal::Int(-5)]);
+    }
+
+    #[test]
+    fn negative_float() {
+        let (literals, failed) = literals("#calc.pow(-0.5, -1)\n\n");
+        assert!(!failed);
+        assert_eq!(
+            literals,
+            [tree::Literal::Float(-0.5), tree::Literal::Int(-1)]
+        );
+    }
+
+    #[test]
+    fn minus_without_digits_is_not_a_literal() {
+        let (literals, _) = literals("#calc.abs(-)\n\n");
+        assert!(literals.is_empty());
+    }
+
+    #[test]
+    fn literals_of_every_kind() {
+        let (literals, failed) = literals("#f(true, 12, 1.5, \"text\")\n\n");
+        assert!(!failed);
+        assert_eq!(
+            literals,
+            [
+                tree::Literal::Bool(true),
+                tree::Literal::Int(12),
+                tree::Literal::Float(1.5),
+                tree::Literal::Str("text".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_and_octal_int() {
+        let (literals, failed) = literals("#f(0x1F, 0xff, 0o17)\n\n");
+        assert!(!failed);
+        assert_eq!(
+            literals,
+            [
+                tree::Literal::Int(31),
+                tree::Literal::Int(255),
+                tree::Literal::Int(15)
+            ]
+        );
+    }
+
+    #[test]
+    fn overflowing_int_is_an_error() {
+        for text in [
+            "#calc.abs(99999999999999999999)\n\n",
+            "#calc.abs(0xFFFFFFFFFFFFFFFFFF)\n\n",
+        ] {
+            let source = Source::new("test.tyd", "test.tyd", text);
+            let errors = parse(&source).errors;
+
+            assert!(
+                errors
+                    .iter()
+                    .any(|error| error.message.starts_with("Invalid integer")),
+                "{text}: {errors:?}"
+            );
+        }
+    }
 }
@@ -4,13 +4,13 @@ use tyd_core::prelude::*;
 
 use crate::{LocationPhase, Span};
 
-use super::extra::{Extra, State};
+use super::extra::{Extra, Input, State};
 
 /// Extension methods for the Chumsky parser
 ///
 /// This trait provides useful extension methods for parsers in the context
 /// of the application, allowing conversion to various data structures and nodes.
-pub trait ParserExt<'src, T>: Parser<'src, &'src str, T, Extra<'src>> + Sized {
+pub trait ParserExt<'src, T>: Parser<'src, Input<'src>, T, Extra<'src>> + Sized {
     /// Converts the parser's output to an `EcoString`
     ///
     /// This is useful for efficiently handling string data in the parser.
@@ -24,7 +24,7 @@ pub trait ParserExt<'src, T>: Parser<'src, &'src str, T, Extra<'src>> + Sized {
     /// This creates a new node with the current span information and inserts it
     /// into the parse state.
     #[inline]
-    fn to_node(self) -> impl Parser<'src, &'src str, NodeId<T>, Extra<'src>>
+    fn to_node(self) -> impl Parser<'src, Input<'src>, NodeId<T>, Extra<'src>>
     where
         T: MetaCast<LocationPhase, Meta = Span>,
         Node: From<T>,
@@ -41,7 +41,7 @@ pub trait ParserExt<'src, T>: Parser<'src, &'src str, T, Extra<'src>> + Sized {
     ///
     /// This is a convenience method that combines `map` and `to_node`.
     #[inline]
-    fn map_to_node<F, U>(self, f: F) -> impl Parser<'src, &'src str, NodeId<U>, Extra<'src>>
+    fn map_to_node<F, U>(self, f: F) -> impl Parser<'src, Input<'src>, NodeId<U>, Extra<'src>>
     where
         F: Fn(T) -> U,
         U: MetaCast<LocationPhase, Meta = Span>,
@@ -54,7 +54,7 @@ pub trait ParserExt<'src, T>: Parser<'src, &'src str, T, Extra<'src>> + Sized {
     ///
     /// This is a convenience method for creating inline elements.
     #[inline]
-    fn to_inline(self) -> impl Parser<'src, &'src str, NodeId<tree::Inline>, Extra<'src>>
+    fn to_inline(self) -> impl Parser<'src, Input<'src>, NodeId<tree::Inline>, Extra<'src>>
     where
         tree::Inline: From<T>,
     {
@@ -65,7 +65,7 @@ pub trait ParserExt<'src, T>: Parser<'src, &'src str, T, Extra<'src>> + Sized {
     ///
     /// This is a convenience method for creating block elements.
     #[inline]
-    fn to_block(self) -> impl Parser<'src, &'src str, NodeId<tree::Block>, Extra<'src>>
+    fn to_block(self) -> impl Parser<'src, Input<'src>, NodeId<tree::Block>, Extra<'src>>
     where
         tree::Block: From<T>,
     {
@@ -76,7 +76,7 @@ pub trait ParserExt<'src, T>: Parser<'src, &'src str, T, Extra<'src>> + Sized {
     ///
     /// This is a convenience method for creating expression nodes.
     #[inline]
-    fn to_expr(self) -> impl Parser<'src, &'src str, NodeId<tree::Expr>, Extra<'src>>
+    fn to_expr(self) -> impl Parser<'src, Input<'src>, NodeId<tree::Expr>, Extra<'src>>
     where
         tree::Expr: From<T>,
     {
@@ -87,4 +87,4 @@ pub trait ParserExt<'src, T>: Parser<'src, &'src str, T, Extra<'src>> + Sized {
 /// Implements the `ParserExt` trait for all types that implement the `Parser` trait
 ///
 /// This blanket implementation ensures that all parsers can use the extension methods.
-impl<'src, T, P: Parser<'src, &'src str, T, Extra<'src>> + Sized> ParserExt<'src, T> for P {}
+impl<'src, T, P: Parser<'src, Input<'src>, T, Extra<'src>> + Sized> ParserExt<'src, T> for P {}
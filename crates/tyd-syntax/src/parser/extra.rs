@@ -1,4 +1,4 @@
-use chumsky::prelude::*;
+use chumsky::{input::WithContext, prelude::*};
 use tyd_core::prelude::*;
 
 use crate::{LocationPhase, Span};
@@ -6,6 +6,9 @@ use crate::{LocationPhase, Span};
 /// Represents the parser state using a simple state wrapper around `StateRepr`.
 pub type State = extra::SimpleState<StateRepr>;
 
+/// Represents the input of the parser, which tags every span with the file being parsed.
+pub type Input<'src> = WithContext<Span, &'src str>;
+
 /// Represents extra data passed to the parser, combining rich error reporting
 /// with state and context information.
 ///
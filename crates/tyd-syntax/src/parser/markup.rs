@@ -8,7 +8,7 @@ use tyd_core::prelude::*;
 use super::{
     code::code_parser,
     ext::ParserExt,
-    extra::{Context, Extra, State},
+    extra::{Context, Extra, Input, State},
 };
 
 /// Special characters that have semantic meaning in the markup language
@@ -22,7 +22,7 @@ const TERM_SPECIAL: &[char] = concat_slices!([char]: SPECIAL, &[':']);
 /// Root parser that processes the entire document
 ///
 /// Returns a vector of Block nodes representing the entire document structure
-pub fn parser<'src>() -> impl Parser<'src, &'src str, Vec<NodeId<tree::Block>>, Extra<'src>> {
+pub fn parser<'src>() -> impl Parser<'src, Input<'src>, Vec<NodeId<tree::Block>>, Extra<'src>> {
     block_parser()
         .separated_by(newline().repeated().at_least(2))
         .allow_trailing()
@@ -34,7 +34,7 @@ pub fn parser<'src>() -> impl Parser<'src, &'src str, Vec<NodeId<tree::Block>>,
 /// Parser for matching the current indentation level
 ///
 /// Uses the context's indent value to determine how many indentation units to match
-pub fn level_parser<'src>() -> impl Parser<'src, &'src str, usize, Extra<'src>> {
+pub fn level_parser<'src>() -> impl Parser<'src, Input<'src>, usize, Extra<'src>> {
     let indent = just("    ").or(just("\t"));
 
     indent
@@ -46,7 +46,7 @@ pub fn level_parser<'src>() -> impl Parser<'src, &'src str, usize, Extra<'src>>
 /// Parser for matching one level of additional indentation beyond the current level
 ///
 /// Uses the context's indent value + 1 to determine how many indentation units to match
-pub fn indent_parser<'src>() -> impl Parser<'src, &'src str, usize, Extra<'src>> {
+pub fn indent_parser<'src>() -> impl Parser<'src, Input<'src>, usize, Extra<'src>> {
     let indent = just("    ").or(just("\t"));
 
     indent
@@ -58,7 +58,7 @@ pub fn indent_parser<'src>() -> impl Parser<'src, &'src str, usize, Extra<'src>>
 /// Parser for all types of block-level elements
 ///
 /// Includes headings, code blocks, lists, enumerations, term definitions, tables, and paragraphs
-pub fn block_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Block>, Extra<'src>> {
+pub fn block_parser<'src>() -> impl Parser<'src, Input<'src>, NodeId<tree::Block>, Extra<'src>> {
     let inline = inline_parser(SPECIAL).boxed();
     let text = text_parser(inline.clone()).boxed();
 
@@ -106,16 +106,25 @@ pub fn block_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Block>,
         .boxed();
 
     // List item parsers - handles "+" for enumerated lists and "-" for bullet lists
+    let item_label = label_parser().or_not().boxed();
     let enum_item = just("+ ")
         .ignore_then(plain.clone())
-        .map(|plain| tree::EnumItem(vec![plain]));
+        .then(item_label.clone())
+        .map(|(plain, label)| tree::EnumItem {
+            content: vec![plain],
+            label,
+        });
     let list_item = just("- ")
         .ignore_then(plain)
-        .map(|plain| tree::ListItem(vec![plain]));
+        .then(item_label)
+        .map(|(plain, label)| tree::ListItem {
+            content: vec![plain],
+            label,
+        });
 
     // Enumeration parser - handles numbered lists with "+" markers and nested structure
     let enumeration = recursive(
-        |enumeration: Recursive<dyn Parser<&'src str, NodeId<tree::Block>, Extra<'src>>>| {
+        |enumeration: Recursive<dyn Parser<Input<'src>, NodeId<tree::Block>, Extra<'src>>>| {
             let nested = newline()
                 .ignore_then(indent_parser())
                 .map(|indent| Context { indent })
@@ -126,7 +135,7 @@ pub fn block_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Block>,
                 .then(nested.or_not())
                 .map_to_node(|(mut item, nested)| {
                     if let Some(nested) = nested {
-                        item.0.push(nested);
+                        item.content.push(nested);
                     }
                     item
                 });
@@ -142,7 +151,7 @@ pub fn block_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Block>,
 
     // List parser - handles bullet lists with "-" markers and nested structure
     let list = recursive(
-        |list: Recursive<dyn Parser<&'src str, NodeId<tree::Block>, Extra<'src>>>| {
+        |list: Recursive<dyn Parser<Input<'src>, NodeId<tree::Block>, Extra<'src>>>| {
             let nested = newline()
                 .ignore_then(indent_parser())
                 .map(|indent| Context { indent })
@@ -153,7 +162,7 @@ pub fn block_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Block>,
                 .then(nested.or_not())
                 .map_to_node(|(mut item, nested)| {
                     if let Some(nested) = nested {
-                        item.0.push(nested);
+                        item.content.push(nested);
                     }
                     item
                 });
@@ -252,7 +261,7 @@ pub fn block_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Block>,
 /// Parser for label annotations like {label}
 ///
 /// Labels can be attached to various elements to provide identifiers
-pub fn label_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Label>, Extra<'src>> {
+pub fn label_parser<'src>() -> impl Parser<'src, Input<'src>, NodeId<tree::Label>, Extra<'src>> {
     unicode::ident()
         .to_ecow()
         .delimited_by(just("{"), just("}"))
@@ -265,9 +274,9 @@ pub fn label_parser<'src>() -> impl Parser<'src, &'src str, NodeId<tree::Label>,
 /// Handles soft line breaks and indentation to maintain proper text flow
 pub fn text_parser<'src, I>(
     inline: I,
-) -> impl Parser<'src, &'src str, Vec<NodeId<tree::Inline>>, Extra<'src>>
+) -> impl Parser<'src, Input<'src>, Vec<NodeId<tree::Inline>>, Extra<'src>>
 where
-    I: Parser<'src, &'src str, NodeId<tree::Inline>, Extra<'src>> + 'src,
+    I: Parser<'src, Input<'src>, NodeId<tree::Inline>, Extra<'src>> + 'src,
 {
     let soft_break = newline()
         .to(tree::SoftBreak)
@@ -278,7 +287,7 @@ where
     let line = inline.repeated().at_least(1).collect::<Vec<_>>();
 
     recursive(
-        |paragraph: Recursive<dyn Parser<&'src str, Vec<NodeId<tree::Inline>>, Extra<'src>>>| {
+        |paragraph: Recursive<dyn Parser<Input<'src>, Vec<NodeId<tree::Inline>>, Extra<'src>>>| {
             let nested = soft_break
                 .clone()
                 .then(
@@ -325,9 +334,9 @@ where
 /// Handles both simple inline content and nested multi-line content
 pub fn content_parser<'src, I>(
     inline: I,
-) -> impl Parser<'src, &'src str, Vec<NodeId<tree::Inline>>, Extra<'src>>
+) -> impl Parser<'src, Input<'src>, Vec<NodeId<tree::Inline>>, Extra<'src>>
 where
-    I: Parser<'src, &'src str, NodeId<tree::Inline>, Extra<'src>> + Clone + 'src,
+    I: Parser<'src, Input<'src>, NodeId<tree::Inline>, Extra<'src>> + Clone + 'src,
 {
     let simple = inline
         .clone()
@@ -351,7 +360,7 @@ where
 /// Handles formatting like quotes, emphasis, links, and other inline markup
 pub fn inline_parser<'src>(
     special: &'src [char],
-) -> impl Parser<'src, &'src str, NodeId<tree::Inline>, Extra<'src>> + Clone {
+) -> impl Parser<'src, Input<'src>, NodeId<tree::Inline>, Extra<'src>> + Clone {
     recursive(|inline| {
         // Quote parser - handles "quoted text"
         let quote = inline
@@ -437,29 +446,34 @@ pub fn inline_parser<'src>(
             .delimited_by(just("<"), just(">"))
             .map(tree::Text)
             .to_node()
-            .then(content.or_not())
+            .then(content.clone().or_not())
             .map_to_node(|(href, content)| tree::Link { href, content })
             .to_inline()
             .boxed();
 
-        // Reference parser - handles @identifier
-        let ref_ = just("@").ignore_then(
-            unicode::ident()
-                .to_ecow()
-                .map_to_node(tree::Ref)
-                .to_inline()
-                .boxed(),
-        );
+        // Reference parser - handles @identifier and @identifier[content]
+        let ref_ = just("@")
+            .ignore_then(unicode::ident().to_ecow().map_to_node(tree::Label))
+            .then(content.or_not())
+            .map_to_node(|(label, content)| tree::Ref { label, content })
+            .to_inline()
+            .boxed();
 
-        // Raw inline code parser - handles `code`
+        // Raw inline code parser - handles `code` and `code`{.lang}
         let delim = "`";
+        let raw_lang = unicode::ident()
+            .to_ecow()
+            .map_to_node(tree::Tag)
+            .delimited_by(just("{."), just("}"));
         let raw_inline = none_of(delim)
             .and_is(newline().not())
             .repeated()
             .at_least(1)
             .to_ecow()
+            .map_to_node(tree::Text)
             .delimited_by(just(delim), just(delim))
-            .map_to_node(tree::RawInline)
+            .then(raw_lang.or_not())
+            .map_to_node(|(text, lang)| tree::RawInline { text, lang })
             .to_inline()
             .boxed();
 
@@ -542,7 +556,7 @@ pub fn inline_parser<'src>(
 /// Helps provide better error reporting for malformed inline elements
 pub fn inline_recovery<'src>(
     delim: &'src str,
-) -> impl Parser<'src, &'src str, NodeId<tree::Inline>, Extra<'src>> {
+) -> impl Parser<'src, Input<'src>, NodeId<tree::Inline>, Extra<'src>> {
     just(delim)
         .then(
             none_of(SPECIAL)
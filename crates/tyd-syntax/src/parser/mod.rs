@@ -37,7 +37,7 @@ pub struct ParseResult {
 pub fn parse(source: &Source) -> ParseResult {
     use self::extra::*;
 
-    let input = source.as_str();
+    let input = source.as_str().with_context(source.id());
     let parser = markup::parser();
 
     let mut state = State::from(StateRepr::new());
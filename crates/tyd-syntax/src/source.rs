@@ -1,10 +1,33 @@
-use std::{fmt, fs, io, path::Path, sync::Arc};
+use std::{
+    fmt, fs, io,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+};
 
 use miette::SourceCode;
 use ropey::Rope;
 
 use crate::Span;
 
+/// Uniquely identifies a source file.
+///
+/// Every `Source` is assigned a new id when it is created, which is stored
+/// in the spans of the nodes parsed from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// Creates a new id, which is different from all previously created ids.
+    fn unique() -> Self {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 /// Represents a source file with its path, name, and content.
 ///
 /// This struct is used to track source code information for error reporting
@@ -12,6 +35,8 @@ use crate::Span;
 /// with the `miette` error reporting library.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Source {
+    /// The unique id of the file
+    id: FileId,
     /// The file path, wrapped in an Arc for efficient cloning
     path: Arc<Path>,
     /// The file name, wrapped in an Arc for efficient cloning
@@ -37,6 +62,7 @@ impl Source {
         let source = source.as_ref();
 
         Self {
+            id: FileId::unique(),
             path: Arc::from(path.as_ref()),
             name: Arc::from(name.as_ref()),
             source: Arc::from(source),
@@ -59,6 +85,14 @@ impl Source {
         Ok(Self::new(&path, name, source))
     }
 
+    /// Returns the unique id of the source.
+    ///
+    /// # Returns
+    /// The id, which is stored in the spans of the nodes parsed from this source
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
     /// Returns the name of the source.
     ///
     /// # Returns
@@ -118,7 +152,7 @@ impl Source {
         Span {
             start: self.len(),
             end: self.len(),
-            context: (),
+            context: self.id,
         }
     }
 }
@@ -20,6 +20,12 @@ pub enum Commands {
     Check {
         /// Path to the document to check
         path: PathBuf,
+        /// Convert math into MathML instead of leaving it to pandoc
+        #[arg(long)]
+        mathml: bool,
+        /// Variables available in the document as `sys.inputs` and top-level names
+        #[arg(long = "input", value_name = "KEY=VALUE", value_parser = parse_input)]
+        inputs: Vec<(String, String)>,
     },
     /// Format a TYD document
     Format {
@@ -34,7 +40,15 @@ pub enum Commands {
         input: PathBuf,
         /// Optional path for the output file (defaults to stdout)
         output: Option<PathBuf>,
+        /// Convert math into MathML instead of leaving it to pandoc
+        #[arg(long)]
+        mathml: bool,
+        /// Variables available in the document as `sys.inputs` and top-level names
+        #[arg(long = "input", value_name = "KEY=VALUE", value_parser = parse_input)]
+        inputs: Vec<(String, String)>,
     },
+    /// Print the reference of all builtin functions as markdown
+    Reference,
 }
 
 /// Supported output formats for document compilation
@@ -51,6 +65,26 @@ pub enum Format {
     Json,
 }
 
+impl From<Format> for Target {
+    /// Converts the output format into the target known to the evaluated document.
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Html => Target::Html,
+            Format::Pdf => Target::Pdf,
+            Format::Docx => Target::Docx,
+            Format::Json => Target::Json,
+        }
+    }
+}
+
+/// Parses an input variable of the form `key=value`.
+fn parse_input(input: &str) -> Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err(format!("expected KEY=VALUE, found '{input}'")),
+    }
+}
+
 /// Entry point for the TYD document processor
 fn main() -> Result<()> {
     // Parse command line arguments
@@ -60,12 +94,19 @@ fn main() -> Result<()> {
     let mut global_scope = Scope::empty();
 
     global_scope
-        .register::<BuiltinPlugin>()
+        .register(BuiltinPlugin)
         .with("title", "Default title")
         .with("author", vec![Value::from("Max Mustermann")]);
 
     match args.command {
-        Commands::Check { path } => {
+        Commands::Check {
+            path,
+            mathml,
+            inputs,
+        } => {
+            // Add the input variables to the global scope
+            let hidden = global_scope.insert_inputs(inputs);
+
             // Load the source document from the specified path
             let source = Source::from_path(path).into_diagnostic()?;
 
@@ -73,7 +114,12 @@ fn main() -> Result<()> {
             let ParseResult { doc, spans, errors } = parse(&source);
 
             // Initialize the tracer with any parse errors
-            let tracer = Tracer::with_diagnostics(errors, source, spans);
+            let mut tracer = Tracer::with_diagnostics(errors, source, spans);
+
+            // Warn about input variables which would replace builtins
+            for name in hidden {
+                tracer.warn(EngineError::HiddenInput(name));
+            }
 
             // Return error if parsing failed
             let doc = if let Some(doc) = doc {
@@ -83,7 +129,10 @@ fn main() -> Result<()> {
             };
 
             // Run the evaluation engine
-            let EngineResult { pandoc, mut tracer } = Engine::new(global_scope, tracer).run(doc);
+            let EngineResult { pandoc, mut tracer } =
+                Engine::new(global_scope, tracer, Target::Json)
+                    .with_mathml(mathml)
+                    .run(doc);
 
             // Return error if evaluation failed
             let pandoc = if let Some(pandoc) = pandoc {
@@ -98,6 +147,15 @@ fn main() -> Result<()> {
             // Display any warnings or non-fatal errors
             eprintln!("{:?}", Report::new(tracer))
         }
+        Commands::Reference => {
+            println!("# Functions\n");
+
+            // Render the signature of every function in the global scope and its modules
+            for (name, func) in global_scope.functions() {
+                println!("## {name}\n");
+                println!("{}", func.signature().markdown(&name));
+            }
+        }
         Commands::Format { path: _ } => {
             // Not yet implemented
             todo!()
@@ -106,7 +164,12 @@ fn main() -> Result<()> {
             input,
             output,
             format,
+            mathml,
+            inputs,
         } => {
+            // Add the input variables to the global scope
+            let hidden = global_scope.insert_inputs(inputs);
+
             // Load the source document from the specified path
             let source = Source::from_path(input).into_diagnostic()?;
 
@@ -114,7 +177,12 @@ fn main() -> Result<()> {
             let ParseResult { doc, spans, errors } = parse(&source);
 
             // Initialize the tracer with any parse errors
-            let tracer = Tracer::with_diagnostics(errors, source, spans);
+            let mut tracer = Tracer::with_diagnostics(errors, source, spans);
+
+            // Warn about input variables which would replace builtins
+            for name in hidden {
+                tracer.warn(EngineError::HiddenInput(name));
+            }
 
             // Return error if parsing failed
             let doc = if let Some(doc) = doc {
@@ -124,7 +192,10 @@ fn main() -> Result<()> {
             };
 
             // Run the evaluation engine
-            let EngineResult { pandoc, mut tracer } = Engine::new(global_scope, tracer).run(doc);
+            let EngineResult { pandoc, mut tracer } =
+                Engine::new(global_scope, tracer, format.into())
+                    .with_mathml(mathml)
+                    .run(doc);
 
             // Return error if evaluation failed
             let pandoc = if let Some(pandoc) = pandoc {